@@ -0,0 +1,105 @@
+use std::fmt::{Display, Formatter};
+
+/// The answer to one part of a puzzle.
+///
+/// Most parts produce a number, but some (e.g. day 17 part 1, day 18 part 2, day 23 part 2)
+/// produce text; this gives them all a uniform type.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Answer {
+    Int(i64),
+    UInt(u64),
+    Text(String)
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{n}"),
+            Answer::UInt(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}")
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident, $target:ty, $( $source:ty ),+) => {
+        $(
+        impl From<$source> for Answer {
+            fn from(value: $source) -> Answer {
+                Answer::$variant(value as $target)
+            }
+        }
+        )+
+    };
+}
+
+answer_from!(Int, i64, i64, isize, i32);
+answer_from!(UInt, u64, u64, usize, u32);
+
+/// Counts too big for a `u64` are answered as text.
+impl From<u128> for Answer {
+    fn from(value: u128) -> Answer {
+        match u64::try_from(value) {
+            Ok(n) => Answer::UInt(n),
+            Err(_) => Answer::Text(value.to_string())
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Answer {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Answer {
+        Answer::Text(value.to_owned())
+    }
+}
+
+/// What a part can return: anything convertible to an [`Answer`], or a `Result` of one.
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<Answer, String>;
+}
+
+impl<T: Into<Answer>> IntoAnswer for T {
+    fn into_answer(self) -> Result<Answer, String> {
+        Ok(self.into())
+    }
+}
+
+impl<T: Into<Answer>, E: Display> IntoAnswer for Result<T, E> {
+    fn into_answer(self) -> Result<Answer, String> {
+        self.map(Into::into).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Answer, IntoAnswer};
+
+    #[test]
+    fn test_from() {
+        assert_eq!(Answer::from(-3isize), Answer::Int(-3));
+        assert_eq!(Answer::from(7usize), Answer::UInt(7));
+        assert_eq!(Answer::from("6,1"), Answer::Text("6,1".to_owned()));
+        assert_eq!(Answer::from("co,de".to_owned()), Answer::Text("co,de".to_owned()));
+        assert_eq!(Answer::from(7u128), Answer::UInt(7));
+        assert_eq!(Answer::from(1u128 << 64), Answer::Text("18446744073709551616".to_owned()));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::Int(-12).to_string(), "-12");
+        assert_eq!(Answer::UInt(u64::MAX).to_string(), "18446744073709551615");
+        assert_eq!(Answer::Text("4,6,3,5,6,3,5,2,1,0".to_owned()).to_string(), "4,6,3,5,6,3,5,2,1,0");
+    }
+
+    #[test]
+    fn test_into_answer() {
+        assert_eq!(12usize.into_answer(), Ok(Answer::UInt(12)));
+        assert_eq!(Ok::<_, String>("6,1").into_answer(), Ok(Answer::Text("6,1".to_owned())));
+        assert_eq!(Err::<i64, _>("no solution").into_answer(), Err("no solution".to_owned()));
+    }
+}
@@ -0,0 +1,133 @@
+//! Command-line arguments shared by every day's binary.
+//!
+//! Each day can declare its own extra arguments with [`day_args!`](crate::day_args) (e.g. a grid
+//! size or a threshold); these helpers do the parsing behind the generated `Args` struct. They can
+//! also be set in the day's section of `config.toml`; see [`config`](crate::config).
+
+use std::fmt::Display;
+use std::str::FromStr;
+use crate::config::Section;
+
+/// A day's extra arguments; see [`day_args!`](crate::day_args).
+pub trait DayArgs: Default {
+    /// The arguments' names, as written in the config (and, with dashes, on the command line).
+    const NAMES: &'static [&'static str];
+
+    /// Set the argument `name` from its text; returns `Ok(false)` if there's no such argument.
+    fn set(&mut self, name: &str, value: &str) -> Result<bool, String>;
+}
+
+/// Days without extra arguments.
+impl DayArgs for () {
+    const NAMES: &'static [&'static str] = &[];
+
+    fn set(&mut self, _name: &str, _value: &str) -> Result<bool, String> {
+        Ok(false)
+    }
+}
+
+/// The defaults, overridden by the settings in `config`.
+pub fn from_config<A: DayArgs>(config: &Section) -> Result<A, String> {
+    let mut args = A::default();
+    for (name, value) in config {
+        if !args.set(name, value)? {
+            return Err(format!("Unknown setting {name:?} in config"));
+        }
+    }
+
+    Ok(args)
+}
+
+/// The defaults, overridden by the settings in `config`, overridden by the command line `args`.
+/// The arguments used are removed from `args`, leaving the ones that are someone else's.
+pub fn from_cli<A: DayArgs>(config: &Section, args: &mut Vec<String>) -> Result<A, String> {
+    let mut day_args = from_config::<A>(config)?;
+    for name in A::NAMES {
+        if let Some(value) = take::<String>(args, name)? {
+            day_args.set(name, &value)?;
+        }
+    }
+
+    Ok(day_args)
+}
+
+/// The value following `--name` in `args`, parsed as a `T`; both are removed from `args`.
+///
+/// Underscores in `name` are written as dashes on the command line, so `min_saving` is given as
+/// `--min-saving 50`.
+pub fn take<T>(args: &mut Vec<String>, name: &str) -> Result<Option<T>, String>
+where
+    T: FromStr,
+    T::Err: Display
+{
+    let flag = format!("--{}", name.replace('_', "-"));
+    let Some(i) = args.iter().position(|arg| *arg == flag) else { return Ok(None) };
+
+    if i + 1 == args.len() {
+        return Err(format!("{flag} needs a value"));
+    }
+    let raw: Vec<String> = args.drain(i..i + 2).collect();
+    raw[1].parse().map(Some).map_err(|e| format!("Invalid value {:?} for {flag}: {e}", raw[1]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_cli, from_config, take, DayArgs};
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Grid { size: usize }
+
+    impl DayArgs for Grid {
+        const NAMES: &'static [&'static str] = &["size"];
+
+        fn set(&mut self, name: &str, value: &str) -> Result<bool, String> {
+            match name {
+                "size" => self.size = value.parse().map_err(|_| "Bad size".to_owned())?,
+                _ => return Ok(false)
+            }
+            Ok(true)
+        }
+    }
+
+    fn args(s: &str) -> Vec<String> {
+        s.split(' ').map(|a| a.to_owned()).collect()
+    }
+
+    #[test]
+    fn test_take() {
+        let mut rest = args("--size 7 --bytes 12");
+        assert_eq!(take::<usize>(&mut rest, "size"), Ok(Some(7)));
+        assert_eq!(rest, ["--bytes", "12"]);
+        assert_eq!(take::<usize>(&mut rest, "size"), Ok(None));
+        assert_eq!(take::<usize>(&mut args("--min-saving 50"), "min_saving"), Ok(Some(50)));
+    }
+
+    #[test]
+    fn test_take_errors() {
+        let missing = take::<usize>(&mut args("--size"), "size");
+        assert_eq!(missing, Err("--size needs a value".to_owned()));
+        assert!(take::<usize>(&mut args("--size big"), "size").unwrap_err().contains("\"big\""));
+    }
+
+    #[test]
+    fn test_from_cli() {
+        let config = vec![("size".to_owned(), "5".to_owned())];
+        let mut rest = args("--render --size 7");
+        assert_eq!(from_cli::<Grid>(&config, &mut rest), Ok(Grid { size: 7 }));
+        assert_eq!(rest, ["--render"]);
+        assert_eq!(from_cli::<Grid>(&config, &mut args("--render")), Ok(Grid { size: 5 }));
+    }
+
+    #[test]
+    fn test_from_config() {
+        let setting = |name: &str, value: &str| vec![(name.to_owned(), value.to_owned())];
+        assert_eq!(from_config::<Grid>(&[]), Ok(Grid { size: 0 }));
+        assert_eq!(from_config::<Grid>(&setting("size", "7")), Ok(Grid { size: 7 }));
+        assert_eq!(from_config::<Grid>(&setting("size", "x")), Err("Bad size".to_owned()));
+        assert_eq!(
+            from_config::<Grid>(&setting("bytes", "12")),
+            Err("Unknown setting \"bytes\" in config".to_owned())
+        );
+        assert!(from_config::<()>(&setting("size", "7")).is_err());
+    }
+}
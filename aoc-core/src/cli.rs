@@ -0,0 +1,252 @@
+//! The flags understood by every binary, parsed in one place so that the days' binaries (see
+//! [`build_main!`](crate::build_main)) and `aoc run`/`aoc verify` treat them the same way.
+//!
+//! - `--input PATH` reads the input from `PATH` instead of the usual file.
+//! - `--json` prints each part's result as a line of JSON rather than as text.
+//! - `--strategy NAME` picks which of a part's solvers to run; see [`strategy`](crate::strategy).
+//! - `--timeout SECS` fails a part that hasn't finished after `SECS` seconds.
+//! - `--repeat N` runs each part `N` times, and reports the fastest.
+//! - `--dump DIR` writes the solvers' intermediate artifacts under `DIR`; see
+//!   [`dump`](crate::dump).
+//!
+//! Anything else is left for the binary: a day's own arguments and modes, or `aoc`'s day
+//! selection. A day's binary takes its arguments out first, and then [`mode`] makes sure what's
+//! left is at most one of its modes.
+
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use serde_json::json;
+use crate::answer::Answer;
+use crate::logging::is_verbosity_flag;
+
+/// The flags shared by every binary.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Flags {
+    pub input: Option<PathBuf>,
+    pub json: bool,
+    pub strategy: Option<String>,
+    pub timeout: Option<Duration>,
+    /// How many times to run each part; at least 1.
+    pub repeat: usize,
+    pub dump: Option<PathBuf>
+}
+
+impl Default for Flags {
+    fn default() -> Flags {
+        Flags { input: None, json: false, strategy: None, timeout: None, repeat: 1, dump: None }
+    }
+}
+
+impl Flags {
+    /// The shared flags in `args`, and the rest of the arguments, in order.
+    pub fn parse<S: AsRef<str>>(args: &[S]) -> Result<(Flags, Vec<String>), String> {
+        let mut flags = Flags::default();
+        let mut rest = Vec::new();
+
+        let mut args = args.iter().map(AsRef::as_ref);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+            match arg {
+                "--input" => flags.input = Some(value()?.into()),
+                "--json" => flags.json = true,
+                "--strategy" => flags.strategy = Some(value()?.to_owned()),
+                "--timeout" => {
+                    let secs = value()?;
+                    let timeout = secs.parse().ok()
+                        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                        .ok_or_else(|| format!("Invalid timeout {secs:?}"))?;
+                    flags.timeout = Some(timeout);
+                },
+                "--repeat" => {
+                    let n = value()?;
+                    flags.repeat = n.parse().ok().filter(|&n| n > 0)
+                        .ok_or_else(|| format!("Invalid repeat count {n:?}"))?;
+                },
+                "--dump" => flags.dump = Some(value()?.into()),
+                arg => rest.push(arg.to_owned())
+            }
+        }
+
+        Ok((flags, rest))
+    }
+
+    /// The shared flags on the process's command line, and the rest of its arguments.
+    pub fn from_env() -> Result<(Flags, Vec<String>), String> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        Flags::parse(&args)
+    }
+
+    /// Contents of the file given with `--input`, if there was one.
+    pub fn read_input(&self) -> Result<Option<String>, String> {
+        self.input.as_ref()
+            .map(|path| {
+                std::fs::read_to_string(path)
+                    .map_err(|e| format!("Couldn't read input {}: {e}", path.display()))
+            })
+            .transpose()
+    }
+
+    /// Run `f` as many times as `--repeat` asks, returning its last result and its fastest time
+    /// in microseconds. Stops early if `stop` says a result is bad enough not to try again.
+    pub fn repeat<T>(&self, mut f: impl FnMut() -> T, stop: impl Fn(&T) -> bool) -> (T, u128) {
+        let mut fastest = u128::MAX;
+        let mut result = None;
+
+        for _ in 0..self.repeat {
+            let start = Instant::now();
+            let value = f();
+            fastest = fastest.min(start.elapsed().as_micros());
+
+            let stopping = stop(&value);
+            result = Some(value);
+            if stopping {
+                break;
+            }
+        }
+
+        (result.expect("--repeat is at least 1"), fastest)
+    }
+
+    /// One part's result, labelled e.g. `Part 1`, as text or (with `--json`) as JSON.
+    pub fn report(&self, label: &str, result: &Result<Answer, String>, micros: u128) -> String {
+        match (self.json, result) {
+            (false, Ok(answer)) => format!("{label}: {answer} (Time: {micros}μs)"),
+            (false, Err(e)) => format!("{label}: FAILED: {e} (Time: {micros}μs)"),
+            (true, Ok(answer)) => {
+                json!({ "part": label, "answer": answer.to_string(), "micros": micros }).to_string()
+            },
+            (true, Err(e)) => json!({ "part": label, "error": e, "micros": micros }).to_string()
+        }
+    }
+}
+
+/// Which of `modes` the leftover arguments in `rest` ask for, if any. The `-v` flags (which
+/// [`logging`](crate::logging) reads) are ignored; anything else is an error.
+pub fn mode(rest: &[String], modes: &[&str]) -> Result<Option<String>, String> {
+    let mut mode = None;
+    for arg in rest.iter().filter(|arg| !is_verbosity_flag(arg)) {
+        if !modes.contains(&arg.as_str()) {
+            return Err(format!("Unknown argument {arg:?}"));
+        }
+        if let Some(first) = mode.replace(arg) {
+            return Err(format!("Can't use both {first} and {arg}"));
+        }
+    }
+
+    Ok(mode.cloned())
+}
+
+/// Run `f`, on a worker thread if there's a `timeout`, giving up with an error if it takes longer.
+///
+/// There's no way to stop a thread, so one that times out is left running in the background until
+/// the process exits.
+pub fn with_timeout<T, F>(timeout: Option<Duration>, f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static
+{
+    let Some(timeout) = timeout else { return Ok(f()) };
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || sender.send(f()));
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => Ok(result),
+        Err(RecvTimeoutError::Timeout) => {
+            Err(format!("timed out after {}s", timeout.as_secs_f64()))
+        },
+        Err(RecvTimeoutError::Disconnected) => Err("worker thread died".to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use crate::answer::Answer;
+    use super::{mode, with_timeout, Flags};
+
+    #[test]
+    fn test_parse() {
+        let args = [
+            "--input", "small.txt", "--size", "7", "--strategy", "memo", "--json", "--render",
+            "--timeout", "1.5", "--repeat", "3", "--dump", "out"
+        ];
+        let (flags, rest) = Flags::parse(&args).unwrap();
+        assert_eq!(flags, Flags {
+            input: Some(PathBuf::from("small.txt")),
+            json: true,
+            strategy: Some("memo".to_owned()),
+            timeout: Some(Duration::from_millis(1500)),
+            repeat: 3,
+            dump: Some(PathBuf::from("out"))
+        });
+        assert_eq!(rest, ["--size", "7", "--render"]);
+
+        assert_eq!(Flags::parse(&["17"]).unwrap(), (Flags::default(), vec!["17".to_owned()]));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Flags::parse(&["--input"]), Err("--input needs a value".to_owned()));
+        assert_eq!(Flags::parse(&["--timeout", "soon"]), Err("Invalid timeout \"soon\"".into()));
+        assert!(Flags::parse(&["--timeout", "-1"]).is_err());
+        assert_eq!(Flags::parse(&["--repeat", "0"]), Err("Invalid repeat count \"0\"".to_owned()));
+    }
+
+    #[test]
+    fn test_mode() {
+        let rest = |args: &[&str]| args.iter().map(|&arg| arg.to_owned()).collect::<Vec<_>>();
+        let modes = ["--render", "--steps"];
+        assert_eq!(mode(&rest(&[]), &modes), Ok(None));
+        assert_eq!(mode(&rest(&["-v", "--render"]), &modes), Ok(Some("--render".to_owned())));
+        assert_eq!(mode(&rest(&["--rendr"]), &modes), Err("Unknown argument \"--rendr\"".into()));
+        assert_eq!(mode(&rest(&["7"]), &[]), Err("Unknown argument \"7\"".to_owned()));
+        assert!(mode(&rest(&["--render", "--steps"]), &modes).is_err());
+    }
+
+    #[test]
+    fn test_repeat() {
+        let flags = Flags { repeat: 4, ..Flags::default() };
+        let mut runs = 0;
+        let (result, _) = flags.repeat(|| { runs += 1; runs }, |_| false);
+        assert_eq!((result, runs), (4, 4));
+
+        let mut runs = 0;
+        let (result, _) = flags.repeat(|| { runs += 1; runs }, |&n| n == 2);
+        assert_eq!((result, runs), (2, 2));
+    }
+
+    #[test]
+    fn test_report() {
+        let text = Flags::default();
+        assert_eq!(text.report("Part 1", &Ok(Answer::UInt(11)), 5), "Part 1: 11 (Time: 5μs)");
+        assert_eq!(
+            text.report("Part 2", &Err("oops".to_owned()), 5),
+            "Part 2: FAILED: oops (Time: 5μs)"
+        );
+
+        let json = Flags { json: true, ..Flags::default() };
+        assert_eq!(
+            json.report("Part 1", &Ok(Answer::from("4,6,3")), 5),
+            "{\"part\":\"Part 1\",\"answer\":\"4,6,3\",\"micros\":5}"
+        );
+        assert_eq!(
+            json.report("Part 2", &Err("bad \"A\"".to_owned()), 7),
+            "{\"part\":\"Part 2\",\"error\":\"bad \\\"A\\\"\",\"micros\":7}"
+        );
+    }
+
+    #[test]
+    fn test_with_timeout() {
+        assert_eq!(with_timeout(None, || 3), Ok(3));
+        assert_eq!(with_timeout(Some(Duration::from_secs(5)), || 3), Ok(3));
+        let stuck = || std::thread::sleep(Duration::from_secs(5));
+        assert_eq!(
+            with_timeout(Some(Duration::from_millis(20)), stuck),
+            Err("timed out after 0.02s".to_owned())
+        );
+    }
+}
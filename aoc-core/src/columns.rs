@@ -0,0 +1,152 @@
+//! Lists given side by side as columns of the input, compared rank by rank or by how often their
+//! values recur. Day 1's pair of location lists is the original case; these work for any number of
+//! columns, comparing every pair of them.
+
+use std::iter::Sum;
+use std::str::FromStr;
+use itertools::Itertools;
+use nom::character::complete::space1;
+use nom::multi::separated_list1;
+use crate::parse::{parse_lines, unsigned, ParseError, Result};
+
+/// The columns of whitespace-separated numbers in `input`, which must have the same number of
+/// numbers on every line.
+pub fn parse_columns<T: FromStr>(input: &str) -> Result<Vec<Vec<T>>> {
+    let mut columns: Vec<Vec<T>> = Vec::new();
+
+    for (i, row) in parse_lines(input, separated_list1(space1, unsigned::<T>)).enumerate() {
+        let row = row?;
+        if i == 0 {
+            columns.resize_with(row.len(), Vec::new);
+        }
+        else if row.len() != columns.len() {
+            let text = input.lines().nth(i).unwrap_or_default();
+            let reason = format!("expected {} numbers, found {}", columns.len(), row.len());
+            return Err(ParseError::new(i + 1, text).because(reason));
+        }
+
+        columns.iter_mut().zip(row).for_each(|(column, value)| column.push(value));
+    }
+
+    Ok(columns)
+}
+
+/// Each column sorted into increasing order.
+fn sorted<T: Ord + Copy>(columns: &[Vec<T>]) -> Vec<Vec<T>> {
+    columns.iter().map(|column| column.iter().copied().sorted().collect()).collect()
+}
+
+/// Total `distance` between every pair of columns, pairing up their values in sorted order: the
+/// smallest with the smallest, and so on. Columns of different lengths are paired up to the end of
+/// the shorter one.
+pub fn sorted_distance<T, D, F>(columns: &[Vec<T>], distance: F) -> D
+where
+    T: Ord + Copy,
+    D: Sum,
+    F: Fn(T, T) -> D
+{
+    sorted(columns).iter()
+        .tuple_combinations()
+        .flat_map(|(a, b)| a.iter().zip(b).map(|(&x, &y)| distance(x, y)))
+        .sum()
+}
+
+/// A sorted column condensed into `(value, count)` runs.
+fn condensed<T: Eq + Copy>(column: &[T]) -> impl Iterator<Item=(T, usize)> + '_ {
+    column.iter().map(|&c| (c, 1))
+        .coalesce(|(a, a_count), (b, b_count)| {
+            if a == b { Ok((a, a_count + b_count)) } else { Err(((a, a_count), (b, b_count))) }
+        })
+}
+
+/// Total similarity between every pair of columns: for each value in one, its `weight` times the
+/// number of times it appears in the other.
+///
+/// Both columns of a pair are condensed into runs of equal values and merged like in merge sort,
+/// so every value is compared at most once.
+pub fn similarity<T, F>(columns: &[Vec<T>], weight: F) -> usize
+where
+    T: Ord + Copy,
+    F: Fn(T) -> usize
+{
+    let sorted = sorted(columns);
+    let mut result = 0;
+
+    for (a, b) in sorted.iter().tuple_combinations() {
+        let mut a_runs = condensed(a);
+        let mut b_runs = condensed(b);
+        let mut a_cur = a_runs.next();
+        let mut b_cur = b_runs.next();
+
+        while let (Some((x, x_count)), Some((y, y_count))) = (a_cur, b_cur) {
+            if x < y {
+                a_cur = a_runs.next();
+            }
+            else if x > y {
+                b_cur = b_runs.next();
+            }
+            else {
+                result += weight(x) * x_count * y_count;
+                a_cur = a_runs.next();
+                b_cur = b_runs.next();
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use crate::parse::ParseError;
+    use super::{parse_columns, similarity, sorted_distance};
+
+    const THREE: &str = "3 4 1\n4 3 3\n2 5 3\n1 3 8\n3 9 2\n3 3 3";
+
+    #[test]
+    fn test_parse_columns() {
+        let columns: Vec<Vec<u32>> = parse_columns(THREE).unwrap();
+        assert_eq!(columns, vec![
+            vec![3, 4, 2, 1, 3, 3],
+            vec![4, 3, 5, 3, 9, 3],
+            vec![1, 3, 3, 8, 2, 3]
+        ]);
+
+        assert_eq!(
+            parse_columns::<u32>("1 2\n3 4 5"),
+            Err(ParseError::new(2, "3 4 5").because("expected 2 numbers, found 3"))
+        );
+        assert!(parse_columns::<u32>("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_pairwise() {
+        let columns: Vec<Vec<usize>> = parse_columns(THREE).unwrap();
+        let distance = |a: usize, b: usize| a.abs_diff(b);
+
+        // Every pair of columns contributes as if it were the only one.
+        let pairs: Vec<[Vec<usize>; 2]> = columns.iter().cloned().tuple_combinations()
+            .map(|(a, b)| [a, b])
+            .collect();
+        let pairwise_distance: usize = pairs.iter().map(|pair| sorted_distance(pair, distance)).sum();
+        let pairwise_similarity: usize = pairs.iter().map(|pair| similarity(pair, |x| x)).sum();
+
+        assert_eq!(sorted_distance(&columns, distance), pairwise_distance);
+        assert_eq!(similarity(&columns, |x| x), pairwise_similarity);
+
+        // Brute force: each value in one column, times its count in the other.
+        let brute: usize = pairs.iter()
+            .flat_map(|[a, b]| a.iter().map(|&x| x * b.iter().filter(|&&y| y == x).count()))
+            .sum();
+        assert_eq!(similarity(&columns, |x| x), brute);
+    }
+
+    #[test]
+    fn test_weights_and_distances() {
+        let columns = vec![vec![1, 5, 9], vec![2, 9, 9]];
+        assert_eq!(sorted_distance(&columns, |a: i64, b| (a - b) * (a - b)), 1 + 16);
+        assert_eq!(similarity(&columns, |_| 1), 2);
+        assert_eq!(similarity(&[vec![1, 2]], |x| x), 0);
+    }
+}
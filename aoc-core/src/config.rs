@@ -0,0 +1,134 @@
+//! Per-day settings from a solutions crate's `config.toml`, so that thresholds, grid sizes and
+//! the like can be changed without recompiling.
+//!
+//! Each day's section, named for its year and day, sets some of its
+//! [`day_args!`](crate::day_args), e.g.
+//!
+//! ```toml
+//! [2024.day18]
+//! size = 7
+//! bytes = 12
+//! ```
+//!
+//! Settings that are left out keep their defaults. Command-line arguments override the config,
+//! which overrides the defaults.
+
+use std::collections::HashMap;
+use std::path::Path;
+use toml::{Table, Value};
+
+/// The `key = value` settings from one section of the config, with the values as text.
+pub type Section = [(String, String)];
+
+/// Every section of a config file, by year and day.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Config {
+    sections: HashMap<(u32, u32), Vec<(String, String)>>
+}
+
+impl Config {
+    /// Parse the text of a config file.
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+
+        for (year_name, days) in text.parse::<Table>().map_err(|e| e.to_string())? {
+            let error = |name: &str| format!("Expected a [YEAR.dayNN] section, got [{name}]");
+            let year = year_name.parse().map_err(|_| error(&year_name))?;
+            let Value::Table(days) = days else { return Err(error(&year_name)) };
+
+            for (day_name, settings) in days {
+                let name = format!("{year_name}.{day_name}");
+                let number = day_name.strip_prefix("day").and_then(|n| n.parse().ok())
+                    .ok_or_else(|| error(&name))?;
+                let Value::Table(settings) = settings else { return Err(error(&name)) };
+
+                let section = settings.into_iter()
+                    .map(|(key, value)| match value {
+                        Value::String(text) => Ok((key, text)),
+                        Value::Integer(_) | Value::Float(_) | Value::Boolean(_) => {
+                            Ok((key, value.to_string()))
+                        },
+                        _ => Err(format!("[{name}] {key}: expected a number, string or boolean"))
+                    })
+                    .collect::<Result<_, String>>()?;
+                config.sections.insert((year, number), section);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// The config at `path`, or an empty one if there's no such file.
+    pub fn load(path: &Path) -> Result<Config, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Config::parse(&text).map_err(|e| format!("{}: {e}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("Couldn't read config {}: {e}", path.display()))
+        }
+    }
+
+    /// The settings for day `number` of `year`, which are empty if it has no section.
+    pub fn section(&self, year: u32, number: u32) -> &Section {
+        self.sections.get(&(year, number)).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// The year and day whose input is `input_file`, e.g. `(2024, 7)` for `2024/day07.txt`.
+pub fn input_day(input_file: &str) -> Option<(u32, u32)> {
+    let (year, name) = input_file.rsplit_once('/')?;
+    let year = year.rsplit('/').next()?.parse().ok()?;
+    let number = name.strip_prefix("day")?.split('.').next()?.parse().ok()?;
+    Some((year, number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{input_day, Config};
+
+    fn pairs(section: &[(&str, &str)]) -> Vec<(String, String)> {
+        section.iter().map(|&(k, v)| (k.to_owned(), v.to_owned())).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse("# Experiments
+[2024.day18]
+size = 7   # the example's grid
+bytes=12
+
+[2024.day20]
+min_saving = 50
+[2023.day22]
+name = \"a # b\"
+").unwrap();
+
+        assert_eq!(config.section(2024, 18), pairs(&[("bytes", "12"), ("size", "7")]));
+        assert_eq!(config.section(2024, 20), pairs(&[("min_saving", "50")]));
+        assert_eq!(config.section(2023, 22), pairs(&[("name", "a # b")]));
+        assert!(config.section(2024, 22).is_empty());
+        assert!(config.section(2024, 1).is_empty());
+    }
+
+    #[test]
+    fn test_input_day() {
+        assert_eq!(input_day("2024/day07.txt"), Some((2024, 7)));
+        assert_eq!(input_day("inputs/2023/day18.txt"), Some((2023, 18)));
+        assert_eq!(input_day("day18.txt"), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Config::parse("size = 7"),
+            Err("Expected a [YEAR.dayNN] section, got [size]".to_owned())
+        );
+        assert_eq!(
+            Config::parse("[day18]\nsize = 7"),
+            Err("Expected a [YEAR.dayNN] section, got [day18]".to_owned())
+        );
+        assert!(Config::parse("[2024.day18").is_err());
+        assert!(Config::parse("[2024.day18]\nsize").is_err());
+        assert!(Config::parse("[2024.eighteen]\nsize = 7").is_err());
+        assert!(Config::parse("[2024.day18]\nsize = [7]").is_err());
+    }
+}
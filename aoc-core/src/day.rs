@@ -0,0 +1,52 @@
+//! Each year's puzzles as a solutions crate registers them, for tools that work across days and
+//! years.
+
+use crate::answer::{Answer, IntoAnswer};
+use crate::args::{from_config, DayArgs};
+use crate::config::Section;
+use crate::parse::normalize;
+use crate::strategy::Solver;
+
+/// One part of a day's puzzle, run with the named strategy (or its default), and its arguments as
+/// set by the day's section of the config.
+pub type Part = fn(&str, Option<&str>, &Section) -> Result<Answer, String>;
+
+/// One day's puzzle, as compiled into this build.
+#[derive(Debug, Clone, Copy)]
+pub struct Day {
+    pub number: u32,
+    /// The module's source code, from which [`describe`](crate::describe) extracts its docs.
+    pub source: &'static str,
+    pub parts: [Part; 2]
+}
+
+/// One year's puzzles, as compiled into this build.
+#[derive(Debug, Clone, Copy)]
+pub struct Year {
+    pub year: u32,
+    /// The days compiled into this build, in order.
+    pub days: &'static [Day]
+}
+
+impl Year {
+    /// The day numbered `number`, if it's compiled into this build.
+    pub fn get(&self, number: u32) -> Option<&'static Day> {
+        self.days.iter().find(|day| day.number == number)
+    }
+}
+
+/// Run `solver` on `input`, after [normalizing](normalize) it, with the named strategy (or its
+/// default) and arguments `A` as set by `config`.
+pub fn solve<T, A, M, S>(
+    solver: &S,
+    input: &str,
+    strategy: Option<&str>,
+    config: &Section
+) -> Result<Answer, String>
+where
+    S: Solver<T, A, M>,
+    A: DayArgs,
+    T: IntoAnswer
+{
+    solver.solve(&normalize(input), strategy, &from_config::<A>(config)?)?.into_answer()
+}
@@ -0,0 +1,229 @@
+//! Markdown writeups of each day, generated from the doc comments in its source.
+//!
+//! The extraction relies on a few conventions in the day modules:
+//! - The module docs (`//!` at the top of the file) start with a `Day N: Title` line, followed by
+//!   an overview of the approach; `# Approach` and `# Complexity` sections are encouraged.
+//! - The `///` docs on `pub fn part1` and `pub fn part2` (or on a `pub const PART1`/`PART2`
+//!   strategy registry) describe each part.
+//! - `//!` docs inside any other function are included as notes, under that function's name.
+//!
+//! Everything from the `#[cfg(test)]` test module on is ignored.
+
+use std::fmt::Write;
+use crate::day::Day;
+
+/// The text of a doc comment line with the given marker (`///` or `//!`), if it is one.
+fn doc_text<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let text = line.trim_start().strip_prefix(marker)?;
+    Some(text.strip_prefix(' ').unwrap_or(text))
+}
+
+/// The part of `source` before its test module.
+fn without_tests(source: &str) -> &str {
+    source.match_indices("#[cfg(test)]")
+        .find(|&(i, attr)| source[i + attr.len()..].trim_start().starts_with("mod "))
+        .map_or(source, |(i, _)| &source[..i])
+}
+
+/// The module's own docs: the `//!` lines at the very top of `source`.
+pub fn module_docs(source: &str) -> String {
+    source.lines()
+        .map_while(|line| doc_text(line, "//!"))
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// The `///` docs on the item whose declaration starts with `decl` (e.g. `pub fn part1`), if it
+/// has any.
+pub fn item_docs(source: &str, decl: &str) -> Option<String> {
+    let lines: Vec<&str> = without_tests(source).lines().collect();
+    let at = lines.iter().position(|line| {
+        line.trim_start().strip_prefix(decl)
+            .is_some_and(|rest| rest.starts_with(['(', '<', ':']))
+    })?;
+
+    let mut docs: Vec<&str> = lines[..at].iter().rev()
+        .filter(|line| !line.trim_start().starts_with("#["))
+        .map_while(|line| doc_text(line, "///"))
+        .collect();
+    docs.reverse();
+
+    if docs.is_empty() { None } else { Some(docs.join("\n")) }
+}
+
+/// The `//!` docs inside functions, as `(function name, docs)` pairs in source order.
+pub fn function_notes(source: &str) -> Vec<(String, String)> {
+    let mut notes: Vec<(String, String)> = Vec::new();
+    let mut current_fn: Option<&str> = None;
+    let mut in_notes = false;
+
+    // Skip the module docs at the top, which are also `//!` lines.
+    let lines = without_tests(source).lines().skip_while(|line| doc_text(line, "//!").is_some());
+    for line in lines {
+        if let Some(text) = doc_text(line, "//!") {
+            let Some(name) = current_fn else { continue };
+            if in_notes {
+                let (_, docs) = notes.last_mut().unwrap();
+                docs.push('\n');
+                docs.push_str(text);
+            }
+            else {
+                notes.push((name.to_owned(), text.to_owned()));
+                in_notes = true;
+            }
+            continue;
+        }
+
+        in_notes = false;
+        if let Some((_, rest)) = line.split_once("fn ") {
+            let name = rest.split(|c: char| !(c.is_alphanumeric() || c == '_')).next().unwrap();
+            if !name.is_empty() {
+                current_fn = Some(name);
+            }
+        }
+    }
+
+    notes
+}
+
+/// `markdown` with every heading one level deeper, outside of code blocks.
+fn demote_headings(markdown: &str) -> String {
+    let mut in_code = false;
+    markdown.lines()
+        .map(|line| {
+            if line.starts_with("```") {
+                in_code = !in_code;
+            }
+
+            if !in_code && line.starts_with('#') { format!("#{line}") } else { line.to_owned() }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// A Markdown writeup of `day`: its title and overview, the docs on each part, and any notes
+/// inside its functions.
+pub fn markdown(day: &Day) -> String {
+    let docs = module_docs(day.source);
+    let (title, overview) = match docs.split_once('\n') {
+        Some((first, rest)) if first.starts_with("Day ") => (first.to_owned(), rest.trim()),
+        _ if docs.starts_with("Day ") => (docs.clone(), ""),
+        _ => (format!("Day {}", day.number), docs.trim())
+    };
+
+    let mut result = format!("# {title}\n");
+    if !overview.is_empty() {
+        write!(result, "\n{}\n", demote_headings(overview)).unwrap();
+    }
+
+    for n in 1..=2 {
+        let docs = item_docs(day.source, &format!("pub fn part{n}"))
+            .or_else(|| item_docs(day.source, &format!("pub const PART{n}")));
+        if let Some(docs) = docs {
+            write!(result, "\n## Part {n}\n\n{}\n", demote_headings(&docs)).unwrap();
+        }
+    }
+
+    let notes = function_notes(day.source);
+    if !notes.is_empty() {
+        result.push_str("\n## Notes\n");
+        for (name, docs) in notes {
+            write!(result, "\n### `{name}`\n\n{}\n", demote_headings(&docs)).unwrap();
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answer::Answer;
+    use crate::day::Day;
+    use super::{function_notes, item_docs, markdown, module_docs};
+
+    const SOURCE: &str = "//! Day 99: Example Puzzle
+//!
+//! Count things.
+//!
+//! # Complexity
+//!
+//! Linear.
+
+use std::collections::HashSet;
+
+fn helper(x: usize) -> usize {
+    //! Not a part, but worth a note:
+    //! it doubles things.
+    x * 2
+}
+
+/// Count the things.
+#[allow(dead_code)]
+pub fn part1(input: &str) -> usize {
+    input.len()
+}
+
+pub fn part2(input: &str) -> usize {
+    helper(input.len())
+}
+
+#[cfg(test)]
+fn only_for_tests() {}
+
+#[cfg(test)]
+mod tests {
+    /// Not included.
+    pub fn part2() {
+        //! Nor this.
+    }
+}
+";
+
+    #[test]
+    fn test_extraction() {
+        assert!(module_docs(SOURCE).starts_with("Day 99: Example Puzzle\n\nCount things."));
+        assert!(module_docs(SOURCE).ends_with("# Complexity\n\nLinear."));
+
+        assert_eq!(item_docs(SOURCE, "pub fn part1"), Some("Count the things.".to_owned()));
+        assert_eq!(item_docs(SOURCE, "pub fn part2"), None);
+        assert_eq!(item_docs(SOURCE, "pub fn part"), None);
+
+        let note = "Not a part, but worth a note:\nit doubles things.".to_owned();
+        assert_eq!(function_notes(SOURCE), vec![("helper".to_owned(), note)]);
+    }
+
+    fn unsolved(_: &str, _: Option<&str>, _: &[(String, String)]) -> Result<Answer, String> {
+        Err("Not solved".to_owned())
+    }
+
+    #[test]
+    fn test_markdown() {
+        let day = Day { number: 99, source: SOURCE, parts: [unsolved, unsolved] };
+        let expected = "# Day 99: Example Puzzle
+
+Count things.
+
+## Complexity
+
+Linear.
+
+## Part 1
+
+Count the things.
+
+## Notes
+
+### `helper`
+
+Not a part, but worth a note:
+it doubles things.
+";
+        assert_eq!(markdown(&day), expected);
+    }
+
+    #[test]
+    fn test_markdown_without_title() {
+        let day = Day { number: 3, source: "pub fn part1() {}", parts: [unsolved, unsolved] };
+        assert_eq!(markdown(&day), "# Day 3\n");
+    }
+}
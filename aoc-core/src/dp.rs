@@ -0,0 +1,46 @@
+//! Dynamic programming over the positions of a sequence, for puzzles about building it out of
+//! pieces (like 2024's day 19 towels).
+
+/// Number of ways to build a sequence of length `target_len` out of pieces, one after another,
+/// where `candidates_at(n)` gives the lengths of the pieces that could start at position `n`.
+///
+/// The counts are filled in from the end: the ways to build the suffix from `n` sum those from
+/// `n + len` over each candidate `len`. An empty target has one way, with no pieces. Candidates
+/// that are empty or run past the end are ignored.
+pub fn count_compositions<F, I>(target_len: usize, mut candidates_at: F) -> usize
+where
+    F: FnMut(usize) -> I,
+    I: IntoIterator<Item=usize>
+{
+    // ways[n] counts the ways to build the suffix starting at n.
+    let mut ways = vec![0; target_len + 1];
+    ways[target_len] = 1;
+
+    for n in (0..target_len).rev() {
+        ways[n] = candidates_at(n).into_iter()
+            .filter(|&len| len > 0 && n + len <= target_len)
+            .map(|len| ways[n + len])
+            .sum();
+    }
+
+    ways[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::count_compositions;
+
+    #[test]
+    fn test_count_compositions() {
+        // Steps of 1 or 2 give the Fibonacci numbers.
+        let fibonacci: Vec<usize> = (0..10).map(|n| count_compositions(n, |_| [1, 2])).collect();
+        assert_eq!(fibonacci, vec![1, 1, 2, 3, 5, 8, 13, 21, 34, 55]);
+
+        assert_eq!(count_compositions(0, |_| None), 1);
+        assert_eq!(count_compositions(3, |_| None), 0);
+
+        // Empty and overlong pieces don't count.
+        assert_eq!(count_compositions(4, |_| [0, 2, 5]), 1);
+        assert_eq!(count_compositions(5, |n| if n == 0 { vec![2, 3] } else { vec![3] }), 1);
+    }
+}
@@ -0,0 +1,92 @@
+//! Intermediate artifacts from solvers (distance maps, histograms, labellings, ...), written under
+//! the directory given with `--dump DIR`.
+//!
+//! Solvers write them through a [`DebugSink`] rather than printing, so that they end up as files
+//! that can be opened in a spreadsheet or diffed between runs. Each day's go in a directory of
+//! their own, e.g. `DIR/day16/distances.csv`. Without `--dump`, they aren't even computed.
+
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use itertools::Itertools;
+
+/// Where artifacts go, if anywhere.
+static DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Somewhere for one day's artifacts, or nowhere.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DebugSink {
+    dir: Option<PathBuf>
+}
+
+impl DebugSink {
+    /// The sink for the artifacts of `scope` (e.g. `day16`): its directory under the one given
+    /// with `--dump`, or nowhere if there wasn't one.
+    pub fn new(scope: &str) -> DebugSink {
+        DebugSink { dir: DIR.get().map(|dir| dir.join(scope)) }
+    }
+
+    /// A sink writing straight into `dir`.
+    pub fn in_dir(dir: impl Into<PathBuf>) -> DebugSink {
+        DebugSink { dir: Some(dir.into()) }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.dir.is_some()
+    }
+
+    /// Write the artifact `name` (e.g. `distances.csv`), computing its `contents` only if the sink
+    /// is enabled.
+    ///
+    /// Failing to write is only logged: it shouldn't stop the solver from answering.
+    pub fn write<F: FnOnce() -> String>(&self, name: &str, contents: F) {
+        let Some(dir) = &self.dir else { return };
+        let path = dir.join(name);
+
+        let result = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, contents()));
+        match result {
+            Ok(()) => log::debug!("Dumped {}", path.display()),
+            Err(e) => log::warn!("Couldn't dump {}: {e}", path.display())
+        }
+    }
+}
+
+/// `rows` as CSV, one line per row. Fields aren't quoted, so they mustn't contain commas.
+pub fn csv<R, T>(rows: impl IntoIterator<Item=R>) -> String
+where
+    R: IntoIterator<Item=T>,
+    T: Display
+{
+    rows.into_iter().map(|row| row.into_iter().join(",") + "\n").collect()
+}
+
+/// Dump artifacts under `dir` from now on. Only the first call has any effect.
+pub fn init_with(dir: impl Into<PathBuf>) {
+    let _ = DIR.set(dir.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csv, DebugSink};
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(csv([vec![1, 2], vec![3, 4]]), "1,2\n3,4\n");
+        assert_eq!(csv([["saving", "cheats"]]), "saving,cheats\n");
+        assert_eq!(csv(Vec::<Vec<u8>>::new()), "");
+    }
+
+    #[test]
+    fn test_sink() {
+        let dir = std::env::temp_dir().join(format!("aoc-dump-test-{}", std::process::id()));
+        let sink = DebugSink::in_dir(&dir);
+        assert!(sink.is_enabled());
+        sink.write("counts.csv", || csv([[1, 2]]));
+        assert_eq!(std::fs::read_to_string(dir.join("counts.csv")).unwrap(), "1,2\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let sink = DebugSink { dir: None };
+        assert!(!sink.is_enabled());
+        sink.write("counts.csv", || unreachable!("Disabled sinks don't compute their contents"));
+    }
+}
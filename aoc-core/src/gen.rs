@@ -0,0 +1,449 @@
+//! Synthetic puzzle inputs, for measuring how solutions scale beyond the official input sizes.
+//!
+//! Every generator takes an explicit [`Rng`], so the same seed always produces the same input.
+
+use itertools::Itertools;
+
+/// A small deterministic PRNG (SplitMix64).
+///
+/// Not suitable for anything but generating test data; the point is that it's reproducible
+/// across platforms and dependency versions.
+#[derive(Debug, Clone)]
+pub struct Rng { state: u64 }
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly random number in `0..n`; `n` must be positive.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "Can't pick from an empty range");
+        // Rejection sampling, to avoid modulo bias.
+        let n = n as u64;
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return (x % n) as usize;
+            }
+        }
+    }
+
+    /// A uniformly random number in `lo..=hi`.
+    pub fn between(&mut self, lo: usize, hi: usize) -> usize {
+        lo + self.below(hi - lo + 1)
+    }
+
+    /// `true` with probability `num / den`.
+    pub fn chance(&mut self, num: usize, den: usize) -> bool {
+        self.below(den) < num
+    }
+
+    /// Fisher-Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+/// Day 1: `count` lines of two five-digit location IDs, separated by three spaces as in the real
+/// input.
+///
+/// IDs are drawn independently, so beyond a few hundred lines the two lists share many of them,
+/// and long lists repeat IDs within themselves.
+pub fn day01_lists(rng: &mut Rng, count: usize) -> String {
+    (0..count)
+        .map(|_| format!("{}   {}", rng.between(10000, 99999), rng.between(10000, 99999)))
+        .join("\n")
+}
+
+/// Day 2: `count` reports of 5 to 8 levels each.
+///
+/// Roughly half are generated safe (monotonic, steps of 1 to 3); the rest have one level
+/// perturbed, which may or may not make them unsafe.
+pub fn day02_reports(rng: &mut Rng, count: usize) -> String {
+    (0..count)
+        .map(|_| {
+            let len = rng.between(5, 8);
+            let increasing = rng.chance(1, 2);
+            let mut level = if increasing { rng.between(1, 20) } else { rng.between(60, 80) };
+
+            let mut levels = vec![level];
+            for _ in 1..len {
+                let step = rng.between(1, 3);
+                level = if increasing { level + step } else { level - step };
+                levels.push(level);
+            }
+
+            if rng.chance(1, 2) {
+                let i = rng.below(len);
+                levels[i] = rng.between(1, 99);
+            }
+
+            levels.iter().join(" ")
+        })
+        .join("\n")
+}
+
+/// Day 4: a `rows`×`cols` word search of random letters from `XMAS`.
+pub fn day04_letters(rng: &mut Rng, rows: usize, cols: usize) -> String {
+    (0..rows)
+        .map(|_| (0..cols).map(|_| ['X', 'M', 'A', 'S'][rng.below(4)]).collect::<String>())
+        .join("\n")
+}
+
+/// Day 5: ordering rules over `num_pages` pages, followed by `num_updates` updates.
+///
+/// The rules list every pair of pages in some random total order, so every update has a unique
+/// correct ordering. About half the updates are already correctly ordered.
+pub fn day05_rules_updates(rng: &mut Rng, num_pages: usize, num_updates: usize) -> String {
+    assert!(num_pages >= 5, "Need at least 5 pages to make an update");
+
+    let mut order: Vec<usize> = (10..10 + num_pages).collect();
+    rng.shuffle(&mut order);
+
+    let mut rules: Vec<(usize, usize)> = order.iter().copied().tuple_combinations().collect();
+    rng.shuffle(&mut rules);
+
+    let max_half = ((num_pages - 1) / 2).min(11);
+    let updates: Vec<String> = (0..num_updates).map(|_| {
+        // Updates always have odd length, so that they have a middle page.
+        let len = 2 * rng.between(2, max_half) + 1;
+        let mut indices: Vec<usize> = (0..num_pages).collect();
+        rng.shuffle(&mut indices);
+        indices.truncate(len);
+
+        if rng.chance(1, 2) {
+            indices.sort();
+        }
+
+        indices.iter().map(|&i| order[i]).join(",")
+    }).collect();
+
+    let rules = rules.iter().map(|(a, b)| format!("{a}|{b}")).join("\n");
+    format!("{}\n\n{}", rules, updates.join("\n"))
+}
+
+/// Day 22: `count` initial secret numbers, each fitting in the 24-bit prune mask.
+pub fn day22_seeds(rng: &mut Rng, count: usize) -> String {
+    (0..count).map(|_| rng.between(1, 16777215)).join("\n")
+}
+
+/// Day 6: a `rows`×`cols` lab map with roughly `percent`% of cells obstructed and an upward-facing
+/// guard on some empty cell.
+///
+/// As in the real puzzle, the guard's path always leads off the map; boards where it would loop
+/// are rejected and regenerated, up to 10,000 times before giving up with a panic. (A guard placed
+/// on the top row always escapes, so that takes a very tall board or a lot of bad luck.)
+pub fn day06_board(rng: &mut Rng, rows: usize, cols: usize, percent: usize) -> String {
+    assert!(percent < 100, "Can't obstruct every cell but the guard's");
+
+    for _ in 0..10_000 {
+        let mut cells: Vec<Vec<char>> = (0..rows)
+            .map(|_| (0..cols).map(|_| if rng.chance(percent, 100) { '#' } else { '.' }).collect())
+            .collect();
+
+        let (i, j) = (rng.below(rows), rng.below(cols));
+        cells[i][j] = '^';
+
+        if guard_escapes(&cells, i, j) {
+            return cells.iter().map(|row| row.iter().collect::<String>()).join("\n");
+        }
+    }
+
+    panic!("None of 10,000 {rows}×{cols} boards with {percent}% obstructed let the guard escape");
+}
+
+fn guard_escapes(cells: &[Vec<char>], i: usize, j: usize) -> bool {
+    let (rows, cols) = (cells.len() as isize, cells[0].len() as isize);
+    let (mut i, mut j, mut di, mut dj) = (i as isize, j as isize, -1, 0);
+
+    // Any loop revisits a cell within 4 × (number of cells) steps.
+    for _ in 0..4 * rows * cols {
+        let (ni, nj) = (i + di, j + dj);
+        if ni < 0 || ni >= rows || nj < 0 || nj >= cols {
+            return true;
+        }
+
+        if cells[ni as usize][nj as usize] == '#' {
+            (di, dj) = (dj, -di);
+        }
+        else {
+            (i, j) = (ni, nj);
+        }
+    }
+
+    false
+}
+
+/// Day 18: every cell of a `size`×`size` memory space except the start and exit, in random
+/// order, as `x,y` lines.
+///
+/// Since every other cell eventually falls, the exit is always cut off at some point. For large
+/// spaces, the first few percent of bytes are very unlikely to block it.
+pub fn day18_bytes(rng: &mut Rng, size: usize) -> String {
+    let mut cells: Vec<(usize, usize)> = (0..size).cartesian_product(0..size)
+        .filter(|&pos| pos != (0, 0) && pos != (size - 1, size - 1))
+        .collect();
+    rng.shuffle(&mut cells);
+
+    cells.iter().map(|(x, y)| format!("{x},{y}")).join("\n")
+}
+
+/// Day 16: a `size`×`size` maze (`size` odd, and at least 5) with the start in the bottom left
+/// corner and the end in the top right, as in the puzzle.
+///
+/// The passages are first carved out as a random spanning tree of the cells with odd coordinates
+/// (by a recursive backtracker), so the end is always reachable, by exactly one route. Then
+/// roughly `percent`% of the remaining walls between two passages are knocked through, adding
+/// loops and so more paths for the search to weigh up.
+pub fn day16_maze(rng: &mut Rng, size: usize, percent: usize) -> String {
+    assert!(size >= 5 && size % 2 == 1, "Maze size must be odd and at least 5");
+    let mut cells = vec![vec!['#'; size]; size];
+
+    let start = (size - 2, 1);
+    cells[start.0][start.1] = '.';
+    let mut stack = vec![start];
+    while let Some(&(i, j)) = stack.last() {
+        // Out-of-bounds steps wrap around to huge indices, and are filtered out with the borders.
+        let steps = [(i.wrapping_sub(2), j), (i + 2, j), (i, j.wrapping_sub(2)), (i, j + 2)];
+        let unvisited: Vec<(usize, usize)> = steps.into_iter()
+            .filter(|&(s, t)| s < size - 1 && t < size - 1 && cells[s][t] == '#')
+            .collect();
+
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+
+        let (s, t) = unvisited[rng.below(unvisited.len())];
+        cells[(i + s) / 2][(j + t) / 2] = '.';
+        cells[s][t] = '.';
+        stack.push((s, t));
+    }
+
+    // Walls with one odd and one even coordinate separate two passages.
+    for (i, row) in cells.iter_mut().enumerate().take(size - 1).skip(1) {
+        for (j, cell) in row.iter_mut().enumerate().take(size - 1).skip(1) {
+            if (i + j) % 2 == 1 && *cell == '#' && rng.chance(percent, 100) {
+                *cell = '.';
+            }
+        }
+    }
+
+    cells[start.0][start.1] = 'S';
+    cells[1][size - 2] = 'E';
+    cells.iter().map(|row| row.iter().collect::<String>()).join("\n")
+}
+
+/// Day 20: a `size`×`size` racetrack (`size` odd, and at least 5) from the bottom left corner to
+/// the top right: the one route through a [`day16_maze`] without loops, with the rest walled up.
+pub fn day20_track(rng: &mut Rng, size: usize) -> String {
+    let maze: Vec<Vec<char>> = day16_maze(rng, size, 0).lines()
+        .map(|line| line.chars().collect())
+        .collect();
+    let (start, end) = ((size - 2, 1), (1, size - 2));
+
+    // The maze is a tree, so the route is the path back from the end through each cell's parent.
+    let mut parents = vec![vec![None; size]; size];
+    parents[start.0][start.1] = Some(start);
+    let mut stack = vec![start];
+    while let Some((i, j)) = stack.pop() {
+        // Open cells are never on the border, so their neighbours are in bounds.
+        for (s, t) in [(i - 1, j), (i + 1, j), (i, j - 1), (i, j + 1)] {
+            if maze[s][t] != '#' && parents[s][t].is_none() {
+                parents[s][t] = Some((i, j));
+                stack.push((s, t));
+            }
+        }
+    }
+
+    let mut cells = vec![vec!['#'; size]; size];
+    let mut pos = end;
+    while pos != start {
+        cells[pos.0][pos.1] = '.';
+        pos = parents[pos.0][pos.1].unwrap();
+    }
+
+    cells[start.0][start.1] = 'S';
+    cells[end.0][end.1] = 'E';
+    cells.iter().map(|row| row.iter().collect::<String>()).join("\n")
+}
+
+/// Day 9: a disk map of `files` files and the gaps between them, each of 0 to `max_size` blocks
+/// (files have at least one), as comma-separated sizes so that they can exceed 9. No files make an
+/// empty map.
+pub fn day09_disk_map(rng: &mut Rng, files: usize, max_size: usize) -> String {
+    (0..(2 * files).saturating_sub(1))
+        .map(|i| if i % 2 == 0 { rng.between(1, max_size) } else { rng.between(0, max_size) })
+        .join(",")
+}
+
+/// Day 12: a `rows`×`cols` garden of plots of `kinds` types (`A`, `B`, ...), in blobs: most plots
+/// copy the type of the plot above or to their left.
+pub fn day12_garden(rng: &mut Rng, rows: usize, cols: usize, kinds: usize) -> String {
+    assert!((1..=26).contains(&kinds), "Plot types are letters");
+    let mut cells = vec![vec!['A'; cols]; rows];
+
+    for i in 0..rows {
+        for j in 0..cols {
+            cells[i][j] = match rng.below(8) {
+                0..=2 if i > 0 => cells[i - 1][j],
+                3..=5 if j > 0 => cells[i][j - 1],
+                _ => (b'A' + rng.below(kinds) as u8) as char
+            };
+        }
+    }
+
+    cells.iter().map(|row| row.iter().collect::<String>()).join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use itertools::Itertools;
+    use super::{day01_lists, day02_reports, day04_letters, day05_rules_updates, day06_board};
+    use super::{day09_disk_map, day12_garden, day16_maze, day18_bytes, day20_track};
+    use super::{day22_seeds, Rng};
+
+    #[test]
+    fn test_rng_deterministic() {
+        let a: Vec<u64> = { let mut r = Rng::new(42); (0..5).map(|_| r.next_u64()).collect() };
+        let b: Vec<u64> = { let mut r = Rng::new(42); (0..5).map(|_| r.next_u64()).collect() };
+        assert_eq!(a, b);
+
+        let mut r = Rng::new(7);
+        assert!((0..1000).map(|_| r.between(3, 5)).all(|x| (3..=5).contains(&x)));
+    }
+
+    #[test]
+    fn test_day01_lists() {
+        let input = day01_lists(&mut Rng::new(1), 100);
+        assert_eq!(input, day01_lists(&mut Rng::new(1), 100));
+        assert_eq!(input.lines().count(), 100);
+        assert!(input.lines().all(|line| line.len() == 13 && &line[5..8] == "   "));
+    }
+
+    #[test]
+    fn test_day02_reports() {
+        let input = day02_reports(&mut Rng::new(1), 100);
+        assert_eq!(input, day02_reports(&mut Rng::new(1), 100));
+        assert_eq!(input.lines().count(), 100);
+        assert!(input.lines().all(|line| (5..=8).contains(&line.split(' ').count())));
+    }
+
+    #[test]
+    fn test_day04_letters() {
+        let input = day04_letters(&mut Rng::new(6), 3, 5);
+        assert_eq!(input.lines().count(), 3);
+        assert!(input.lines().all(|line| line.len() == 5));
+        assert!(input.lines().flat_map(str::chars).all(|c| "XMAS".contains(c)));
+    }
+
+    #[test]
+    fn test_day05_rules_updates() {
+        let input = day05_rules_updates(&mut Rng::new(2), 20, 50);
+        let (rules, updates) = input.split_once("\n\n").unwrap();
+
+        let rules: HashSet<(usize, usize)> = rules.lines()
+            .map(|line| line.split('|').map(|n| n.parse().unwrap()).collect_tuple().unwrap())
+            .collect();
+        assert_eq!(rules.len(), 20 * 19 / 2);
+
+        for update in updates.lines() {
+            let pages: Vec<usize> = update.split(',').map(|n| n.parse().unwrap()).collect();
+            assert_eq!(pages.len() % 2, 1);
+            assert!(pages.iter().all_unique());
+            // Every pair is covered by a rule in one direction or the other.
+            assert!(pages.iter().tuple_combinations()
+                .all(|(&a, &b)| rules.contains(&(a, b)) != rules.contains(&(b, a))));
+        }
+    }
+
+    #[test]
+    fn test_day06_board() {
+        let input = day06_board(&mut Rng::new(5), 4, 6, 20);
+        assert_eq!(input.lines().count(), 4);
+        assert!(input.lines().all(|line| line.len() == 6));
+        assert_eq!(input.chars().filter(|&c| c == '^').count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't obstruct every cell but the guard's")]
+    fn test_day06_board_full() {
+        day06_board(&mut Rng::new(5), 4, 6, 100);
+    }
+
+    #[test]
+    fn test_day09_disk_map() {
+        let input = day09_disk_map(&mut Rng::new(9), 50, 20);
+        let sizes: Vec<usize> = input.split(',').map(|n| n.parse().unwrap()).collect();
+        assert_eq!(sizes.len(), 99);
+        assert!(sizes.iter().all(|&size| size <= 20));
+        assert!(sizes.iter().step_by(2).all(|&size| size > 0));
+        assert_eq!(day09_disk_map(&mut Rng::new(9), 0, 20), "");
+    }
+
+    #[test]
+    fn test_day12_garden() {
+        let garden = day12_garden(&mut Rng::new(12), 6, 9, 3);
+        assert_eq!(garden, day12_garden(&mut Rng::new(12), 6, 9, 3));
+        assert_eq!(garden.lines().count(), 6);
+        assert!(garden.lines().all(|line| line.len() == 9));
+        assert!(garden.chars().all(|c| "ABC\n".contains(c)));
+    }
+
+    #[test]
+    fn test_day16_maze() {
+        let maze = day16_maze(&mut Rng::new(16), 11, 0);
+        assert_eq!(maze, day16_maze(&mut Rng::new(16), 11, 0));
+        assert_eq!(maze.lines().count(), 11);
+        assert!(maze.lines().all(|line| line.len() == 11));
+        assert_eq!(maze.lines().nth(9).unwrap().chars().nth(1), Some('S'));
+        assert_eq!(maze.lines().nth(1).unwrap().chars().nth(9), Some('E'));
+
+        // A spanning tree of the 25 cells has 24 passages between them.
+        let open = |maze: &str| maze.chars().filter(|&c| c != '#' && c != '\n').count();
+        assert_eq!(open(&maze), 25 + 24);
+        assert!(open(&day16_maze(&mut Rng::new(16), 11, 50)) > 25 + 24);
+    }
+
+    #[test]
+    fn test_day20_track() {
+        let track = day20_track(&mut Rng::new(20), 11);
+        let cells: Vec<&[u8]> = track.lines().map(str::as_bytes).collect();
+        assert_eq!((cells[9][1], cells[1][9]), (b'S', b'E'));
+
+        // A single track: the start and end have one neighbour on it, and every other cell two.
+        for (i, j) in (1..10).cartesian_product(1..10).filter(|&(i, j)| cells[i][j] != b'#') {
+            let neighbours = [cells[i - 1][j], cells[i + 1][j], cells[i][j - 1], cells[i][j + 1]];
+            let on_track = neighbours.iter().filter(|&&c| c != b'#').count();
+            assert_eq!(on_track, if cells[i][j] == b'.' { 2 } else { 1 }, "at {:?}", (i, j));
+        }
+    }
+
+    #[test]
+    fn test_day22_seeds() {
+        let input = day22_seeds(&mut Rng::new(3), 10);
+        assert!(input.lines().all(|line| line.parse::<usize>().unwrap() < 1 << 24));
+    }
+
+    #[test]
+    fn test_day18_bytes() {
+        let input = day18_bytes(&mut Rng::new(4), 7);
+        let cells: HashSet<&str> = input.lines().collect();
+        assert_eq!(cells.len(), 47);
+        assert!(!cells.contains("0,0") && !cells.contains("6,6"));
+    }
+}
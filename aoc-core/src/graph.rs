@@ -0,0 +1,513 @@
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Display, Formatter, Write};
+use std::hash::Hash;
+use crate::grid::Direction;
+use crate::hash::{HashMap, HashSet};
+
+/// Whether a DOT export draws its edges as arrows (`->`) or plain lines (`--`).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DotKind { Directed, Undirected }
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Render a graph in Graphviz DOT format, for dumping puzzle graphs for visual inspection.
+///
+/// Nodes are emitted in the order they are first seen (highlighted nodes first, then edge
+/// endpoints), and are given synthetic ids so that `labels` is free to produce any text.  For
+/// undirected output, an edge listed in both directions is only drawn once, and counts as
+/// highlighted if either orientation is in `highlighted_edges`.
+pub fn to_dot<N, L, I, F>(
+    kind: DotKind,
+    edges: I,
+    labels: F,
+    highlighted_nodes: &HashSet<N>,
+    highlighted_edges: &HashSet<(N, N)>
+) -> String
+where
+    N: Clone + Eq + Hash,
+    L: Display,
+    I: IntoIterator<Item=(N, N)>,
+    F: Fn(&N) -> L
+{
+    let edges: Vec<(N, N)> = edges.into_iter().collect();
+
+    let mut ids: HashMap<N, usize> = HashMap::default();
+    let mut nodes: Vec<N> = Vec::new();
+    let mut highlighted: Vec<&N> = highlighted_nodes.iter().collect();
+    highlighted.sort_by_key(|n| labels(n).to_string());
+
+    for n in highlighted.into_iter().chain(edges.iter().flat_map(|(a, b)| [a, b])) {
+        if !ids.contains_key(n) {
+            ids.insert(n.clone(), nodes.len());
+            nodes.push(n.clone());
+        }
+    }
+
+    let (header, arrow) = match kind {
+        DotKind::Directed => ("digraph", "->"),
+        DotKind::Undirected => ("graph", "--")
+    };
+
+    let mut result = String::new();
+    writeln!(result, "{header} {{").unwrap();
+
+    for (id, node) in nodes.iter().enumerate() {
+        let label = escape(&labels(node).to_string());
+        if highlighted_nodes.contains(node) {
+            writeln!(result, "    n{id} [label=\"{label}\", color=red, style=bold];").unwrap();
+        }
+        else {
+            writeln!(result, "    n{id} [label=\"{label}\"];").unwrap();
+        }
+    }
+
+    let mut drawn = HashSet::default();
+    for (a, b) in edges.iter() {
+        let (i, j) = (ids[a], ids[b]);
+        let is_highlighted = match kind {
+            DotKind::Directed => highlighted_edges.contains(&(a.clone(), b.clone())),
+            DotKind::Undirected => {
+                if !drawn.insert((i.min(j), i.max(j))) {
+                    continue;
+                }
+                highlighted_edges.contains(&(a.clone(), b.clone()))
+                    || highlighted_edges.contains(&(b.clone(), a.clone()))
+            }
+        };
+
+        if is_highlighted {
+            writeln!(result, "    n{i} {arrow} n{j} [color=red, penwidth=2];").unwrap();
+        }
+        else {
+            writeln!(result, "    n{i} {arrow} n{j};").unwrap();
+        }
+    }
+
+    result.push('}');
+    result
+}
+
+/// All cliques with exactly `k` vertices in the undirected graph with adjacency lists `adj`.
+///
+/// Each clique is sorted, and the cliques are listed in lexicographic order. Every vertex should
+/// appear as a key of `adj`, and edges should be listed in both directions.
+pub fn cliques_of_size<N>(adj: &HashMap<N, HashSet<N>>, k: usize) -> Vec<Vec<N>>
+where
+    N: Copy + Ord + Hash
+{
+    let mut result = Vec::new();
+    if k == 0 {
+        return result;
+    }
+
+    let mut verts: Vec<N> = adj.keys().copied().collect();
+    verts.sort();
+
+    // Depth-first, only ever extending a clique with vertices larger than all of its members, so
+    // that each clique is found exactly once (already sorted).
+    let mut stack: Vec<(Vec<N>, Vec<N>)> = verts.iter().rev()
+        .map(|&v| {
+            let mut candidates: Vec<N> = adj[&v].iter().copied().filter(|&w| w > v).collect();
+            candidates.sort();
+            (vec![v], candidates)
+        })
+        .collect();
+
+    while let Some((clique, candidates)) = stack.pop() {
+        if clique.len() == k {
+            result.push(clique);
+            continue;
+        }
+
+        if clique.len() + candidates.len() < k {
+            continue;
+        }
+
+        for (idx, &w) in candidates.iter().enumerate().rev() {
+            let mut next = clique.clone();
+            next.push(w);
+            let next_candidates = candidates[idx + 1..].iter()
+                .copied()
+                .filter(|u| adj[&w].contains(u))
+                .collect();
+            stack.push((next, next_candidates));
+        }
+    }
+
+    result
+}
+
+/// A degeneracy ordering of the undirected graph with adjacency lists `adj`, and its degeneracy.
+///
+/// The ordering comes from repeatedly removing a vertex of smallest remaining degree (the smallest
+/// such vertex, so it's deterministic). Each vertex then has at most `degeneracy` neighbours later
+/// in the order, which bounds the candidates when growing cliques from it. As with
+/// [`cliques_of_size`], edges should be listed in both directions.
+pub fn degeneracy_order<N>(adj: &HashMap<N, HashSet<N>>) -> (Vec<N>, usize)
+where
+    N: Copy + Ord + Hash
+{
+    let mut degrees: HashMap<N, usize> = adj.iter().map(|(&v, ns)| (v, ns.len())).collect();
+    let max_degree = degrees.values().copied().max().unwrap_or(0);
+    let mut buckets = vec![BTreeSet::new(); max_degree + 1];
+    for (&v, &degree) in &degrees {
+        buckets[degree].insert(v);
+    }
+
+    let mut order = Vec::with_capacity(adj.len());
+    let mut degeneracy = 0;
+    let mut lowest: usize = 0;
+    while order.len() < adj.len() {
+        // Removing a vertex lowers its neighbours' degrees by one, so the smallest nonempty bucket
+        // is at most one below the last.
+        lowest = lowest.saturating_sub(1);
+        while buckets[lowest].is_empty() {
+            lowest += 1;
+        }
+
+        let v = buckets[lowest].pop_first().unwrap();
+        degrees.remove(&v);
+        degeneracy = degeneracy.max(lowest);
+        order.push(v);
+
+        for w in &adj[&v] {
+            if let Some(degree) = degrees.get_mut(w) {
+                buckets[*degree].remove(w);
+                *degree -= 1;
+                buckets[*degree].insert(*w);
+            }
+        }
+    }
+
+    (order, degeneracy)
+}
+
+/// The canonical name of a clique: its members' names, sorted and comma separated, each listed
+/// once.
+///
+/// This doesn't depend on the order the clique was found in, so it's a stable key for comparing
+/// cliques, and it's the form of the password in day 23.
+pub fn clique_password<S: AsRef<str>>(clique: &[S]) -> String {
+    let mut names: Vec<&str> = clique.iter().map(|name| name.as_ref()).collect();
+    names.sort_unstable();
+    names.dedup();
+    names.join(",")
+}
+
+/// The error from [`count_paths_dag`] when the graph has a cycle, naming a node on it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CycleError<N> {
+    pub node: N
+}
+
+impl<N: Debug> Display for CycleError<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The graph has a cycle through {:?}", self.node)
+    }
+}
+
+/// The number of paths from each of `sources` to any of `sinks` in the DAG with adjacency lists
+/// `graph`, in the same order as `sources`.
+///
+/// A path may pass through a sink and end at a later one; each counts. The counts are built up in
+/// reverse topological order (the post-order of a depth-first search from the sources), so every
+/// node reachable from a source is visited once. If a cycle is reachable, there'd be infinitely
+/// many paths, so that's an error instead.
+pub fn count_paths_dag<N>(
+    graph: &HashMap<N, Vec<N>>,
+    sources: &[N],
+    sinks: &HashSet<N>
+) -> Result<Vec<usize>, CycleError<N>>
+where
+    N: Copy + Eq + Hash
+{
+    let successors = |node: &N| graph.get(node).map_or(&[][..], Vec::as_slice);
+
+    // Paths from each node to a sink, or `None` while the node is still on the stack.
+    let mut paths: HashMap<N, Option<usize>> = HashMap::default();
+
+    for &source in sources {
+        if paths.contains_key(&source) {
+            continue;
+        }
+
+        paths.insert(source, None);
+        // Each node being explored, with the index of its next successor to look at.
+        let mut stack = vec![(source, 0)];
+
+        while let Some(&(node, next)) = stack.last() {
+            match successors(&node).get(next) {
+                Some(&succ) => {
+                    stack.last_mut().unwrap().1 += 1;
+                    match paths.get(&succ) {
+                        Some(None) => return Err(CycleError { node: succ }),
+                        Some(Some(_)) => {},
+                        None => {
+                            paths.insert(succ, None);
+                            stack.push((succ, 0));
+                        }
+                    }
+                },
+                None => {
+                    stack.pop();
+                    let through: usize = successors(&node).iter().map(|s| paths[s].unwrap()).sum();
+                    paths.insert(node, Some(usize::from(sinks.contains(&node)) + through));
+                }
+            }
+        }
+    }
+
+    Ok(sources.iter().map(|source| paths[source].unwrap()).collect())
+}
+
+/// A grid cell together with a heading, as `((row, col), direction)`.
+pub type Heading = ((usize, usize), Direction);
+
+/// Weighted adjacency lists.
+pub type WeightedGraph<N> = HashMap<N, Vec<(N, usize)>>;
+
+/// Which turns [`grid_with_headings`] includes.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Turns {
+    /// Every 90 degree turn, including ones that leave the mover facing a wall.
+    All,
+    /// Only turns that leave the mover facing a passable cell. Turning to face a wall can only be
+    /// undone, so such turns are useless, except as half of a 180 degree turn out of a dead end;
+    /// that gets a direct edge costing two turns instead. States facing a wall are still there
+    /// (they can be stepped into, or started from), but no longer reachable by turning.
+    Useful
+}
+
+/// The state graph of something moving around `grid` with a heading.
+///
+/// There's a state for every heading on every cell where `passable` holds. From each one, moving
+/// forward onto a passable cell costs `step_cost`, and turning 90 degrees either way in place
+/// costs `turn_cost`; `turns` says whether to leave out turns that face a wall.
+///
+/// Day 16 searches this graph; day 6 builds its guard's jump tables over it.
+pub fn grid_with_headings<T, F>(
+    grid: &[Vec<T>],
+    step_cost: usize,
+    turn_cost: usize,
+    turns: Turns,
+    passable: F
+) -> WeightedGraph<Heading>
+where
+    F: Fn(&T) -> bool
+{
+    let bounds = (grid.len(), grid.first().map_or(0, |row| row.len()));
+    let faces_passable = |pos, direction: Direction| {
+        direction.step(pos, bounds).is_some_and(|(s, t)| passable(&grid[s][t]))
+    };
+    let mut adj = HashMap::default();
+
+    for (i, row) in grid.iter().enumerate() {
+        for (j, cell) in row.iter().enumerate() {
+            if !passable(cell) {
+                continue;
+            }
+
+            for direction in Direction::ALL {
+                let mut neighbors = Vec::with_capacity(3);
+
+                if let Some((s, t)) = direction.step((i, j), bounds) {
+                    if passable(&grid[s][t]) {
+                        neighbors.push((((s, t), direction), step_cost));
+                    }
+                }
+
+                let sideways = [direction.turn_left(), direction.turn_right()];
+                match turns {
+                    Turns::All => {
+                        for turned in sideways {
+                            neighbors.push((((i, j), turned), turn_cost));
+                        }
+                    },
+                    Turns::Useful => {
+                        for turned in sideways {
+                            if faces_passable((i, j), turned) {
+                                neighbors.push((((i, j), turned), turn_cost));
+                            }
+                        }
+
+                        // Facing a wall both ways is the only time it's worth turning to face one.
+                        let reversed = direction.turn_left().turn_left();
+                        if !sideways.iter().any(|&d| faces_passable((i, j), d))
+                            && faces_passable((i, j), reversed)
+                        {
+                            neighbors.push((((i, j), reversed), 2 * turn_cost));
+                        }
+                    }
+                }
+
+                adj.insert(((i, j), direction), neighbors);
+            }
+        }
+    }
+
+    adj
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::Direction::*;
+    use crate::hash::{HashMap, HashSet};
+    use super::{clique_password, cliques_of_size, count_paths_dag, degeneracy_order, to_dot};
+    use super::grid_with_headings;
+    use super::{CycleError, DotKind, Turns};
+
+    #[test]
+    fn test_directed() {
+        let edges = vec![(1, 2), (2, 3)];
+        let nodes = HashSet::from_iter([3]);
+        let highlighted = HashSet::from_iter([(2, 3)]);
+        let dot = to_dot(DotKind::Directed, edges, |n| n * 10, &nodes, &highlighted);
+
+        let expected = "digraph {
+    n0 [label=\"30\", color=red, style=bold];
+    n1 [label=\"10\"];
+    n2 [label=\"20\"];
+    n1 -> n2;
+    n2 -> n0 [color=red, penwidth=2];
+}";
+        assert_eq!(dot, expected);
+    }
+
+    #[test]
+    fn test_undirected_dedupes_edges() {
+        let edges = vec![("a", "b"), ("b", "a"), ("b", "c")];
+        let highlighted = HashSet::from_iter([("c", "b")]);
+        let label = |n: &&str| format!("\"{n}\"");
+        let dot = to_dot(DotKind::Undirected, edges, label, &HashSet::default(), &highlighted);
+
+        let expected = "graph {
+    n0 [label=\"\\\"a\\\"\"];
+    n1 [label=\"\\\"b\\\"\"];
+    n2 [label=\"\\\"c\\\"\"];
+    n0 -- n1;
+    n1 -- n2 [color=red, penwidth=2];
+}";
+        assert_eq!(dot, expected);
+    }
+
+    #[test]
+    fn test_cliques_of_size() {
+        // A 4-clique {1, 2, 3, 4}, plus a triangle {4, 5, 6} hanging off it.
+        let edges = [(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4), (4, 5), (4, 6), (5, 6)];
+        let mut adj: HashMap<usize, HashSet<usize>> = HashMap::default();
+        for (a, b) in edges {
+            adj.entry(a).or_default().insert(b);
+            adj.entry(b).or_default().insert(a);
+        }
+
+        assert_eq!(cliques_of_size(&adj, 1).len(), 6);
+        assert_eq!(cliques_of_size(&adj, 2).len(), edges.len());
+        assert_eq!(
+            cliques_of_size(&adj, 3),
+            vec![vec![1, 2, 3], vec![1, 2, 4], vec![1, 3, 4], vec![2, 3, 4], vec![4, 5, 6]]
+        );
+        assert_eq!(cliques_of_size(&adj, 4), vec![vec![1, 2, 3, 4]]);
+        assert!(cliques_of_size(&adj, 5).is_empty());
+        assert!(cliques_of_size(&adj, 0).is_empty());
+    }
+
+    #[test]
+    fn test_degeneracy_order() {
+        // A 4-clique {1, 2, 3, 4}, with a path 4 - 5 - 6 hanging off it.
+        let edges = [(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4), (4, 5), (5, 6)];
+        let mut adj: HashMap<usize, HashSet<usize>> = HashMap::default();
+        for (a, b) in edges {
+            adj.entry(a).or_default().insert(b);
+            adj.entry(b).or_default().insert(a);
+        }
+
+        let (order, degeneracy) = degeneracy_order(&adj);
+        assert_eq!(order, vec![6, 5, 1, 2, 3, 4]);
+        assert_eq!(degeneracy, 3);
+
+        // Every vertex has at most `degeneracy` neighbours after it.
+        for (i, v) in order.iter().enumerate() {
+            assert!(order[i + 1..].iter().filter(|w| adj[v].contains(w)).count() <= degeneracy);
+        }
+
+        assert_eq!(degeneracy_order(&HashMap::<usize, HashSet<usize>>::default()), (vec![], 0));
+        let isolated = HashMap::from_iter([(7, HashSet::default())]);
+        assert_eq!(degeneracy_order(&isolated), (vec![7], 0));
+    }
+
+    #[test]
+    fn test_clique_password() {
+        assert_eq!(clique_password(&["ta", "co", "ka", "de"]), "co,de,ka,ta");
+        assert_eq!(clique_password(&["co", "de", "ka", "ta"]), "co,de,ka,ta");
+        assert_eq!(clique_password(&["ka", "co", "ka", "de", "co", "ta"]), "co,de,ka,ta");
+        assert_eq!(clique_password(&["co".to_owned()]), "co");
+        assert_eq!(clique_password::<&str>(&[]), "");
+    }
+
+    #[test]
+    fn test_count_paths_dag() {
+        // A diamond 1 -> {2, 3} -> 4, then 4 -> 5, with 6 off on its own.
+        let graph =
+            HashMap::from_iter([(1, vec![2, 3]), (2, vec![4]), (3, vec![4]), (4, vec![5])]);
+
+        let sinks = HashSet::from_iter([5]);
+        assert_eq!(count_paths_dag(&graph, &[1, 2, 5, 6], &sinks), Ok(vec![2, 1, 1, 0]));
+
+        // Paths to 4 and on to 5 both count.
+        let sinks = HashSet::from_iter([4, 5]);
+        assert_eq!(count_paths_dag(&graph, &[1, 4], &sinks), Ok(vec![4, 2]));
+        assert_eq!(count_paths_dag(&graph, &[], &sinks), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_count_paths_dag_cycle() {
+        let graph = HashMap::from_iter([(1, vec![2]), (2, vec![3]), (3, vec![1, 4])]);
+        let sinks = HashSet::from_iter([4]);
+
+        assert_eq!(count_paths_dag(&graph, &[1], &sinks), Err(CycleError { node: 1 }));
+        assert_eq!(
+            count_paths_dag(&graph, &[2], &sinks).unwrap_err().to_string(),
+            "The graph has a cycle through 2"
+        );
+        // Unreachable cycles don't matter.
+        assert_eq!(count_paths_dag(&graph, &[4], &sinks), Ok(vec![1]));
+    }
+
+    #[test]
+    fn test_grid_with_headings() {
+        let grid: Vec<Vec<char>> = ["..#", "#.."].iter().map(|row| row.chars().collect()).collect();
+        let adj = grid_with_headings(&grid, 1, 10, Turns::All, |&c| c == '.');
+
+        // 4 passable cells, 4 headings each.
+        assert_eq!(adj.len(), 16);
+        assert!(!adj.contains_key(&((0, 2), Up)));
+
+        assert_eq!(
+            adj[&((0, 0), Right)],
+            vec![(((0, 1), Right), 1), (((0, 0), Up), 10), (((0, 0), Down), 10)]
+        );
+        // Blocked by a wall, and by the edge of the grid.
+        assert_eq!(adj[&((0, 1), Right)], vec![(((0, 1), Up), 10), (((0, 1), Down), 10)]);
+        assert_eq!(adj[&((1, 2), Right)], vec![(((1, 2), Up), 10), (((1, 2), Down), 10)]);
+    }
+
+    #[test]
+    fn test_grid_with_useful_turns() {
+        let grid: Vec<Vec<char>> = ["..#", "#.."].iter().map(|row| row.chars().collect()).collect();
+        let adj = grid_with_headings(&grid, 1, 10, Turns::Useful, |&c| c == '.');
+
+        // The same states as with every turn, but fewer ways between them.
+        assert_eq!(adj.len(), 16);
+        let all = grid_with_headings(&grid, 1, 10, Turns::All, |&c| c == '.');
+        let edges = |adj: &HashMap<_, Vec<_>>| adj.values().map(Vec::len).sum::<usize>();
+        assert!(edges(&adj) < edges(&all));
+
+        assert_eq!(adj[&((0, 0), Up)], vec![(((0, 0), Right), 10)]);
+        assert_eq!(adj[&((0, 1), Right)], vec![(((0, 1), Down), 10)]);
+        // A dead end: the only way out is back the way it came.
+        assert_eq!(adj[&((1, 2), Right)], vec![(((1, 2), Left), 20)]);
+        assert_eq!(adj[&((1, 2), Left)], vec![(((1, 1), Left), 1)]);
+    }
+}
@@ -0,0 +1,682 @@
+//! Helpers for 2D grids indexed by `(row, col)`.
+//!
+//! A [`Grid`] keeps its cells in one of three [`Layout`]s, chosen by its type: [`RowMajor`] (the
+//! default), [`ColumnMajor`], or [`Nested`] rows.
+//!
+//! Days 6, 16 and 20 each prepare one kernel in every layout ([`LayoutKernels`]), which
+//! `cargo bench --bench days` times against each other.
+
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+/// How a [`Grid`] stores its cells.
+///
+/// Positions passed to [`Layout::cell`] and [`Layout::cell_mut`] are in bounds.
+pub trait Layout {
+    /// A short name, for benchmark tables.
+    const NAME: &'static str;
+
+    /// The storage for a grid of `T`s.
+    type Cells<T>;
+
+    /// The cells of the given rows, which are all `cols` long.
+    fn from_rows<T>(rows: Vec<Vec<T>>, cols: usize) -> Self::Cells<T>;
+
+    /// The cells of a `rows` × `cols` grid whose cell at each position is `source` of it.
+    fn from_fn<T, F>(rows: usize, cols: usize, source: F) -> Self::Cells<T>
+    where
+        F: FnMut((usize, usize)) -> T;
+
+    fn cell<T>(cells: &Self::Cells<T>, bounds: (usize, usize), pos: (usize, usize)) -> &T;
+
+    fn cell_mut<T>(
+        cells: &mut Self::Cells<T>,
+        bounds: (usize, usize),
+        pos: (usize, usize)
+    ) -> &mut T;
+}
+
+/// Cells stored row by row in one `Vec`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct RowMajor;
+
+impl Layout for RowMajor {
+    const NAME: &'static str = "row-major";
+    type Cells<T> = Vec<T>;
+
+    fn from_rows<T>(rows: Vec<Vec<T>>, _cols: usize) -> Vec<T> {
+        rows.into_iter().flatten().collect()
+    }
+
+    fn from_fn<T, F>(rows: usize, cols: usize, mut source: F) -> Vec<T>
+    where
+        F: FnMut((usize, usize)) -> T
+    {
+        (0..rows * cols).map(|k| source((k / cols, k % cols))).collect()
+    }
+
+    fn cell<T>(cells: &Vec<T>, (_, cols): (usize, usize), (i, j): (usize, usize)) -> &T {
+        &cells[i * cols + j]
+    }
+
+    fn cell_mut<T>(cells: &mut Vec<T>, (_, cols): (usize, usize), pos: (usize, usize)) -> &mut T {
+        let (i, j) = pos;
+        &mut cells[i * cols + j]
+    }
+}
+
+/// Cells stored column by column in one `Vec`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ColumnMajor;
+
+impl Layout for ColumnMajor {
+    const NAME: &'static str = "column-major";
+    type Cells<T> = Vec<T>;
+
+    fn from_rows<T>(rows: Vec<Vec<T>>, cols: usize) -> Vec<T> {
+        let mut rows: Vec<_> = rows.into_iter().map(Vec::into_iter).collect();
+        let mut cells = Vec::with_capacity(rows.len() * cols);
+        for _ in 0..cols {
+            cells.extend(rows.iter_mut().map(|row| row.next().unwrap()));
+        }
+        cells
+    }
+
+    fn from_fn<T, F>(rows: usize, cols: usize, mut source: F) -> Vec<T>
+    where
+        F: FnMut((usize, usize)) -> T
+    {
+        (0..rows * cols).map(|k| source((k % rows, k / rows))).collect()
+    }
+
+    fn cell<T>(cells: &Vec<T>, (rows, _): (usize, usize), (i, j): (usize, usize)) -> &T {
+        &cells[j * rows + i]
+    }
+
+    fn cell_mut<T>(cells: &mut Vec<T>, (rows, _): (usize, usize), pos: (usize, usize)) -> &mut T {
+        let (i, j) = pos;
+        &mut cells[j * rows + i]
+    }
+}
+
+/// Cells stored as a `Vec` of rows, as most days parse their input.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Nested;
+
+impl Layout for Nested {
+    const NAME: &'static str = "nested";
+    type Cells<T> = Vec<Vec<T>>;
+
+    fn from_rows<T>(rows: Vec<Vec<T>>, _cols: usize) -> Vec<Vec<T>> {
+        rows
+    }
+
+    fn from_fn<T, F>(rows: usize, cols: usize, mut source: F) -> Vec<Vec<T>>
+    where
+        F: FnMut((usize, usize)) -> T
+    {
+        (0..rows).map(|i| (0..cols).map(|j| source((i, j))).collect()).collect()
+    }
+
+    fn cell<T>(cells: &Vec<Vec<T>>, _bounds: (usize, usize), (i, j): (usize, usize)) -> &T {
+        &cells[i][j]
+    }
+
+    fn cell_mut<T>(cells: &mut Vec<Vec<T>>, _: (usize, usize), (i, j): (usize, usize)) -> &mut T {
+        &mut cells[i][j]
+    }
+}
+
+/// The same kernel prepared in each [`Layout`], named by [`Layout::NAME`], for benchmarks to
+/// time against each other. Each returns the kernel's answer, which should be the same for all.
+pub type LayoutKernels = Vec<(&'static str, Box<dyn Fn() -> usize>)>;
+
+/// A rectangular grid of cells, stored in layout `L`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Grid<T, L: Layout = RowMajor> {
+    cells: L::Cells<T>,
+    rows: usize,
+    cols: usize,
+    layout: PhantomData<L>
+}
+
+impl<T> Grid<T> {
+    /// The grid with the given rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        Grid::from_rows_in(rows)
+    }
+
+    /// The grid with one row per line of `input` (ignoring trailing blank lines), converting each
+    /// character with `cell`.
+    pub fn parse<F: FnMut(char) -> T>(input: &str, cell: F) -> Grid<T> {
+        Grid::parse_in(input, cell)
+    }
+}
+
+impl<T, L: Layout> Grid<T, L> {
+    /// [`Grid::from_rows`], in layout `L`.
+    pub fn from_rows_in(rows: Vec<Vec<T>>) -> Grid<T, L> {
+        let cols = rows.first().map_or(0, |row| row.len());
+        assert!(rows.iter().all(|row| row.len() == cols), "Grid rows must all be the same length");
+
+        let num_rows = rows.len();
+        Grid { cells: L::from_rows(rows, cols), rows: num_rows, cols, layout: PhantomData }
+    }
+
+    /// [`Grid::parse`], in layout `L`.
+    pub fn parse_in<F: FnMut(char) -> T>(input: &str, mut cell: F) -> Grid<T, L> {
+        let lines = input.trim_end_matches(['\r', '\n']).lines();
+        let rows = lines.map(|line| line.chars().map(&mut cell).collect());
+        Grid::from_rows_in(rows.collect())
+    }
+
+    /// The `rows` × `cols` grid whose cell at each position is `source` of that position.
+    pub fn from_fn<F>(rows: usize, cols: usize, source: F) -> Grid<T, L>
+    where
+        F: FnMut((usize, usize)) -> T
+    {
+        Grid { cells: L::from_fn(rows, cols, source), rows, cols, layout: PhantomData }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// `(rows, cols)`, as taken by [`Direction::step`] and [`diamond`].
+    pub fn bounds(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// The cell at `pos`, if it's in bounds.
+    pub fn get(&self, (i, j): (usize, usize)) -> Option<&T> {
+        (i < self.rows && j < self.cols).then(|| L::cell(&self.cells, self.bounds(), (i, j)))
+    }
+
+    /// Every position, row by row, left to right.
+    pub fn positions(&self) -> impl Iterator<Item=(usize, usize)> {
+        let cols = self.cols;
+        (0..self.rows * cols).map(move |k| (k / cols, k % cols))
+    }
+
+    /// Every position along with its cell, row by row, left to right.
+    pub fn iter(&self) -> impl Iterator<Item=((usize, usize), &T)> {
+        let bounds = self.bounds();
+        self.positions().map(move |pos| (pos, L::cell(&self.cells, bounds, pos)))
+    }
+
+    /// The in-bounds positions one step away from `pos`, in the order of [`Direction::ALL`].
+    pub fn neighbors(&self, pos: (usize, usize)) -> impl Iterator<Item=(usize, usize)> {
+        let bounds = self.bounds();
+        Direction::ALL.into_iter().filter_map(move |d| d.step(pos, bounds))
+    }
+}
+
+impl<T: Clone, L: Layout> Grid<T, L> {
+    /// The grid reflected in its main diagonal, so row `i` becomes column `i`.
+    pub fn transpose(&self) -> Grid<T, L> {
+        Grid::from_fn(self.cols, self.rows, |(i, j)| self[(j, i)].clone())
+    }
+
+    /// The grid turned a quarter turn clockwise, so its first column becomes the first row.
+    pub fn rotate_cw(&self) -> Grid<T, L> {
+        Grid::from_fn(self.cols, self.rows, |(i, j)| self[(self.rows - 1 - j, i)].clone())
+    }
+
+    /// The grid turned a quarter turn counter-clockwise, so its last column becomes the first row.
+    pub fn rotate_ccw(&self) -> Grid<T, L> {
+        Grid::from_fn(self.cols, self.rows, |(i, j)| self[(j, self.cols - 1 - i)].clone())
+    }
+
+    /// The grid mirrored left to right.
+    pub fn flip_h(&self) -> Grid<T, L> {
+        Grid::from_fn(self.rows, self.cols, |(i, j)| self[(i, self.cols - 1 - j)].clone())
+    }
+
+    /// The grid mirrored top to bottom.
+    pub fn flip_v(&self) -> Grid<T, L> {
+        Grid::from_fn(self.rows, self.cols, |(i, j)| self[(self.rows - 1 - i, j)].clone())
+    }
+}
+
+impl<T, L: Layout> Index<(usize, usize)> for Grid<T, L> {
+    type Output = T;
+
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        assert!(i < self.rows && j < self.cols, "({i}, {j}) is outside the grid");
+        L::cell(&self.cells, self.bounds(), (i, j))
+    }
+}
+
+impl<T, L: Layout> IndexMut<(usize, usize)> for Grid<T, L> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        assert!(i < self.rows && j < self.cols, "({i}, {j}) is outside the grid");
+        let bounds = self.bounds();
+        L::cell_mut(&mut self.cells, bounds, (i, j))
+    }
+}
+
+/// A grid of bytes borrowed straight from the text of the input, one row per line.
+///
+/// Nothing is copied: cell `(i, j)` is byte `i * (cols + 1) + j` of the text, counting the `\n`
+/// ending each row (or byte `i * (cols + 2) + j`, if the rows end in `\r\n`). That keeps the
+/// whole grid contiguous, so [`ByteGrid::find`] can scan it for a byte with `memchr` rather than
+/// cell by cell.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ByteGrid<'a> {
+    bytes: &'a [u8],
+    rows: usize,
+    cols: usize,
+    /// The distance from each row's start to the next one's: `cols` plus the line ending.
+    stride: usize
+}
+
+impl<'a> ByteGrid<'a> {
+    /// The grid whose rows are the lines of `input`, which must all be the same length and all end
+    /// the same way, in `\n` or `\r\n`.
+    pub fn new(input: &'a str) -> ByteGrid<'a> {
+        let bytes = input.trim_end_matches(['\n', '\r']).as_bytes();
+        let first_end = memchr::memchr(b'\n', bytes);
+        let ending: &[u8] = match first_end {
+            Some(end) if end > 0 && bytes[end - 1] == b'\r' => b"\r\n",
+            _ => b"\n"
+        };
+        let cols = first_end.map_or(bytes.len(), |end| end + 1 - ending.len());
+        let stride = cols + ending.len();
+        let rows = if bytes.is_empty() { 0 } else { (bytes.len() + ending.len()) / stride };
+
+        let rectangular = bytes.is_empty() || (
+            rows * stride == bytes.len() + ending.len()
+                && (1..rows).all(|i| bytes[i * stride - ending.len()..].starts_with(ending))
+                && memchr::memchr_iter(b'\n', bytes).count() + 1 == rows
+        );
+        assert!(rectangular, "Grid rows must all be the same length");
+
+        ByteGrid { bytes, rows, cols, stride }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// `(rows, cols)`, as taken by [`Direction::step`] and [`diamond`].
+    pub fn bounds(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// The cell at `pos`, if it's in bounds.
+    pub fn get(&self, (i, j): (usize, usize)) -> Option<u8> {
+        (i < self.rows && j < self.cols).then(|| self.bytes[i * self.stride + j])
+    }
+
+    /// Row `i`, without its line ending.
+    pub fn row(&self, i: usize) -> &'a [u8] {
+        let start = i * self.stride;
+        &self.bytes[start..start + self.cols]
+    }
+
+    /// Every position holding `byte`, row by row, left to right. `byte` can't be `\n` or `\r`.
+    pub fn find(&self, byte: u8) -> impl Iterator<Item=(usize, usize)> + 'a {
+        assert!(byte != b'\n' && byte != b'\r', "Line endings aren't cells");
+        let stride = self.stride;
+        memchr::memchr_iter(byte, self.bytes).map(move |k| (k / stride, k % stride))
+    }
+}
+
+impl Index<(usize, usize)> for ByteGrid<'_> {
+    type Output = u8;
+
+    fn index(&self, (i, j): (usize, usize)) -> &u8 {
+        assert!(i < self.rows && j < self.cols, "({i}, {j}) is outside the grid");
+        &self.bytes[i * self.stride + j]
+    }
+}
+
+/// One of the four headings on a grid, with `Up` towards row 0 and `Left` towards column 0.
+#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction { Up, Down, Left, Right }
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    /// The heading after a 90 degree clockwise turn.
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up
+        }
+    }
+
+    /// The heading after a 90 degree counter-clockwise turn.
+    pub fn turn_left(self) -> Direction {
+        self.turn_right().turn_right().turn_right()
+    }
+
+    /// The opposite heading.
+    pub fn reverse(self) -> Direction {
+        self.turn_right().turn_right()
+    }
+
+    /// The neighbor of `pos` in this direction, if it's inside a grid with `bounds = (rows, cols)`.
+    pub fn step(self, pos: (usize, usize), bounds: (usize, usize)) -> Option<(usize, usize)> {
+        let (i, j) = pos;
+        let (rows, cols) = bounds;
+        match self {
+            Direction::Up => i.checked_sub(1).map(|i| (i, j)),
+            Direction::Down => Some(i + 1).filter(|&i| i < rows).map(|i| (i, j)),
+            Direction::Left => j.checked_sub(1).map(|j| (i, j)),
+            Direction::Right => Some(j + 1).filter(|&j| j < cols).map(|j| (i, j))
+        }
+    }
+}
+
+/// All in-bounds positions within Manhattan distance `r` of `pos` (including `pos` itself), for
+/// a grid with `bounds = (rows, cols)`.
+///
+/// Positions are produced row by row, left to right.
+pub fn diamond(
+    pos: (usize, usize),
+    r: usize,
+    bounds: (usize, usize)
+) -> impl Iterator<Item=(usize, usize)> {
+    let (i, j) = pos;
+    let (rows, cols) = bounds;
+
+    let first_row = i.saturating_sub(r);
+    let last_row = (i + r).min(rows.saturating_sub(1));
+
+    (first_row..=last_row)
+        .filter(move |_| rows > 0 && cols > 0)
+        .flat_map(move |s| {
+            let reach = r - s.abs_diff(i);
+            let first_col = j.saturating_sub(reach);
+            let last_col = (j + reach).min(cols - 1);
+            (first_col..=last_col).map(move |t| (s, t))
+        })
+}
+
+/// Visit every position reachable from `start` by orthogonal steps from a cell to one for which
+/// `same_region(from, to)` holds: `start` first, then the rest in breadth-first order.
+pub fn flood_fill<T, L, S, V>(grid: &Grid<T, L>, start: (usize, usize), same_region: S, visit: V)
+where
+    L: Layout,
+    S: FnMut(&T, &T) -> bool,
+    V: FnMut((usize, usize))
+{
+    let mut seen = Grid::from_rows(vec![vec![false; grid.cols]; grid.rows]);
+    fill(grid, start, &mut seen, same_region, visit);
+}
+
+/// The regions of `grid` whose orthogonally adjacent cells are `eq` (which should be an
+/// equivalence), ordered by their first position row by row; each lists its positions in the
+/// order [`flood_fill`] visits them.
+pub fn connected_components<T, L, E>(grid: &Grid<T, L>, mut eq: E) -> Vec<Vec<(usize, usize)>>
+where
+    L: Layout,
+    E: FnMut(&T, &T) -> bool
+{
+    let mut seen = Grid::from_rows(vec![vec![false; grid.cols]; grid.rows]);
+    let mut components = Vec::new();
+
+    for start in grid.positions() {
+        if !seen[start] {
+            let mut component = Vec::new();
+            fill(grid, start, &mut seen, &mut eq, |pos| component.push(pos));
+            components.push(component);
+        }
+    }
+
+    components
+}
+
+/// [`flood_fill`], skipping (and marking) positions already `seen`.
+fn fill<T, L, S, V>(
+    grid: &Grid<T, L>,
+    start: (usize, usize),
+    seen: &mut Grid<bool>,
+    mut same_region: S,
+    mut visit: V
+) where
+    L: Layout,
+    S: FnMut(&T, &T) -> bool,
+    V: FnMut((usize, usize))
+{
+    let mut queue = VecDeque::from([start]);
+    seen[start] = true;
+
+    while let Some(pos) = queue.pop_front() {
+        visit(pos);
+        for next in grid.neighbors(pos) {
+            if !seen[next] && same_region(&grid[pos], &grid[next]) {
+                seen[next] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{connected_components, diamond, flood_fill, ByteGrid, ColumnMajor, Direction};
+    use super::{Grid, Nested};
+
+    #[test]
+    fn test_grid() {
+        let mut grid = Grid::parse("123\n456", |c| c.to_digit(10).unwrap());
+        assert_eq!(grid.bounds(), (2, 3));
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(grid.get((0, 2)), Some(&3));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+
+        grid[(0, 1)] = 7;
+        let cells: Vec<((usize, usize), &u32)> = grid.iter().take(3).collect();
+        assert_eq!(cells, vec![((0, 0), &1), ((0, 1), &7), ((0, 2), &3)]);
+
+        assert_eq!(grid.neighbors((0, 0)).collect::<Vec<_>>(), vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors((1, 1)).count(), 3);
+        assert_eq!(Grid::<u8>::from_rows(vec![]).positions().count(), 0);
+    }
+
+    #[test]
+    fn test_byte_grid() {
+        let grid = ByteGrid::new("XMAS\nSAMX\nAXXA\n");
+        assert_eq!(grid.bounds(), (3, 4));
+        assert_eq!(grid[(1, 3)], b'X');
+        assert_eq!(grid.get((2, 1)), Some(b'X'));
+        assert_eq!(grid.get((0, 4)), None);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.row(1), b"SAMX");
+
+        let xs: Vec<(usize, usize)> = grid.find(b'X').collect();
+        assert_eq!(xs, vec![(0, 0), (1, 3), (2, 1), (2, 2)]);
+        assert_eq!(grid.find(b'Q').count(), 0);
+
+        assert_eq!(ByteGrid::new("").bounds(), (0, 0));
+        assert_eq!(ByteGrid::new("AB").bounds(), (1, 2));
+    }
+
+    #[test]
+    fn test_byte_grid_crlf() {
+        let grid = ByteGrid::new("XMAS\r\nSAMX\r\nAXXA\r\n\r\n");
+        assert_eq!(grid, ByteGrid::new("XMAS\r\nSAMX\r\nAXXA"));
+        assert_eq!(grid.bounds(), (3, 4));
+        assert_eq!(grid[(2, 1)], b'X');
+        assert_eq!(grid.row(1), b"SAMX");
+        let xs: Vec<(usize, usize)> = grid.find(b'X').collect();
+        assert_eq!(xs, vec![(0, 0), (1, 3), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Grid rows must all be the same length")]
+    fn test_byte_grid_mixed_endings() {
+        ByteGrid::new("XMAS\r\nSAMX\nAXXA");
+    }
+
+    #[test]
+    #[should_panic(expected = "Grid rows must all be the same length")]
+    fn test_split_row_byte_grid() {
+        // Right total length, and line endings where they'd be for 4 columns, but a split row.
+        ByteGrid::new("XMAS\nS\nAM\nAXXA");
+    }
+
+    #[test]
+    #[should_panic(expected = "Grid rows must all be the same length")]
+    fn test_ragged_byte_grid() {
+        ByteGrid::new("XMAS\nSAM\nAXXA");
+    }
+
+    #[test]
+    fn test_layouts() {
+        let input = "abcd\nefgh\nijkl";
+        let grid = Grid::parse(input, |c| c);
+        let by_columns: Grid<char, ColumnMajor> = Grid::parse_in(input, |c| c);
+        let nested: Grid<char, Nested> = Grid::parse_in(input, |c| c);
+
+        assert_eq!(by_columns.bounds(), (3, 4));
+        assert!(grid.iter().eq(by_columns.iter()));
+        assert!(grid.iter().eq(nested.iter()));
+        assert_eq!(by_columns.get((2, 1)), Some(&'j'));
+        assert_eq!(by_columns.get((1, 4)), None);
+
+        let mut rotated = by_columns.rotate_cw();
+        assert!(rotated.iter().eq(grid.rotate_cw().iter()));
+        rotated[(3, 2)] = 'z';
+        assert_eq!(rotated[(3, 2)], 'z');
+
+        let components = connected_components(&nested, |_, _| true);
+        assert_eq!(components, connected_components(&grid, |_, _| true));
+        assert_eq!(Grid::<u8, ColumnMajor>::from_rows_in(vec![]).iter().count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn test_grid_ragged() {
+        Grid::from_rows(vec![vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn test_direction() {
+        assert!(Direction::ALL.iter().all(|d| d.turn_right().turn_left() == *d));
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+
+        assert_eq!(Direction::Up.step((0, 3), (4, 4)), None);
+        assert_eq!(Direction::Down.step((2, 3), (4, 4)), Some((3, 3)));
+        assert_eq!(Direction::Down.step((3, 3), (4, 4)), None);
+        assert_eq!(Direction::Left.step((2, 0), (4, 4)), None);
+        assert_eq!(Direction::Right.step((2, 3), (4, 4)), None);
+        assert_eq!(Direction::Right.step((2, 2), (4, 4)), Some((2, 3)));
+    }
+
+    #[test]
+    fn test_diamond_interior() {
+        let cells: Vec<(usize, usize)> = diamond((2, 2), 1, (5, 5)).collect();
+        assert_eq!(cells, vec![(1, 2), (2, 1), (2, 2), (2, 3), (3, 2)]);
+
+        // 2r^2 + 2r + 1 cells when nothing is clipped.
+        assert_eq!(diamond((10, 10), 4, (21, 21)).count(), 41);
+        assert_eq!(diamond((3, 3), 0, (5, 5)).collect::<Vec<_>>(), vec![(3, 3)]);
+    }
+
+    #[test]
+    fn test_diamond_edges_and_corners() {
+        let cells: Vec<(usize, usize)> = diamond((0, 0), 2, (5, 5)).collect();
+        assert_eq!(cells, vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (2, 0)]);
+
+        let cells: Vec<(usize, usize)> = diamond((4, 4), 1, (5, 5)).collect();
+        assert_eq!(cells, vec![(3, 4), (4, 3), (4, 4)]);
+
+        let cells: Vec<(usize, usize)> = diamond((0, 2), 1, (1, 5)).collect();
+        assert_eq!(cells, vec![(0, 1), (0, 2), (0, 3)]);
+
+        // A radius bigger than the grid covers all of it.
+        assert_eq!(diamond((1, 1), 100, (3, 4)).count(), 12);
+    }
+
+    #[test]
+    fn test_diamond_empty_grid() {
+        assert_eq!(diamond((0, 0), 3, (0, 0)).count(), 0);
+        assert_eq!(diamond((0, 0), 3, (2, 0)).count(), 0);
+        assert_eq!(diamond((0, 0), 3, (0, 2)).count(), 0);
+    }
+
+    #[test]
+    fn test_flood_fill_single_cell() {
+        let grid = Grid::parse("ab
+ba", |c| c);
+        let mut visited = Vec::new();
+        flood_fill(&grid, (0, 1), |a, b| a == b, |pos| visited.push(pos));
+        assert_eq!(visited, vec![(0, 1)]);
+
+        let components = connected_components(&grid, |a, b| a == b);
+        assert_eq!(components, vec![vec![(0, 0)], vec![(0, 1)], vec![(1, 0)], vec![(1, 1)]]);
+    }
+
+    #[test]
+    fn test_flood_fill_donut() {
+        let grid = Grid::parse("OOOO\nOXXO\nOXXO\nOOOO", |c| c);
+
+        let mut ring = Vec::new();
+        flood_fill(&grid, (0, 0), |a, b| a == b, |pos| ring.push(pos));
+        assert_eq!(ring.len(), 12);
+        assert_eq!(ring[0], (0, 0));
+        assert!(ring.iter().all(|&pos| grid[pos] == 'O'));
+
+        let components = connected_components(&grid, |a, b| a == b);
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].len(), 12);
+        assert_eq!(components[1], vec![(1, 1), (2, 1), (1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn test_flood_fill_directed() {
+        // Regions needn't be symmetric: climb one step at a time, as on day 10's trails.
+        let grid = Grid::parse("0123\n9854", |c| c.to_digit(10).unwrap());
+        let mut visited = Vec::new();
+        flood_fill(&grid, (0, 0), |a, b| *b == a + 1, |pos| visited.push(grid[pos]));
+        assert_eq!(visited, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_rotations() {
+        let grid = Grid::parse("abc\ndef", |c| c);
+        let rows = |grid: Grid<char>| -> Vec<String> {
+            (0..grid.rows()).map(|i| (0..grid.cols()).map(|j| grid[(i, j)]).collect()).collect()
+        };
+
+        assert_eq!(rows(grid.transpose()), vec!["ad", "be", "cf"]);
+        assert_eq!(rows(grid.rotate_cw()), vec!["da", "eb", "fc"]);
+        assert_eq!(rows(grid.rotate_ccw()), vec!["cf", "be", "ad"]);
+        assert_eq!(rows(grid.flip_h()), vec!["cba", "fed"]);
+        assert_eq!(rows(grid.flip_v()), vec!["def", "abc"]);
+    }
+
+    #[test]
+    fn test_rotation_identities() {
+        let grid = Grid::parse("1234\n5678\n9abc", |c| c);
+
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), grid);
+        assert_eq!(grid.rotate_cw().rotate_cw(), grid.flip_h().flip_v());
+        assert_eq!(grid.transpose().transpose(), grid);
+        assert_eq!(grid.rotate_cw(), grid.transpose().flip_h());
+        assert_eq!(grid.rotate_ccw(), grid.transpose().flip_v());
+
+        let empty: Grid<char> = Grid::from_rows(vec![]);
+        assert_eq!(empty.rotate_cw().bounds(), (0, 0));
+    }
+}
@@ -0,0 +1,40 @@
+//! The hash containers for grid positions and graph nodes.
+//!
+//! By default these are the standard library's, hashed with SipHash. With the `fxhash` feature
+//! they use `rustc-hash`'s much faster (and not at all DoS-resistant) FxHash instead, which
+//! matters for the days whose hot loops hash `(usize, usize)` positions. Either way, build them
+//! with `default()` (or `collect()`) rather than `new()`, which only exists for SipHash.
+//!
+//! FxHash isn't randomly seeded, so these containers would iterate in the same order on every run,
+//! and the checks that run each part twice (`aoc run --repeat-check` and
+//! [`test_samples!`](crate::test_samples)) couldn't catch answers that depend on that order. So
+//! FxHash is only used in release builds; debug builds, tests included, stay with the standard
+//! library's randomly seeded `RandomState`.
+
+/// How the containers here hash their keys.
+#[cfg(all(feature = "fxhash", not(debug_assertions)))]
+pub type BuildHasher = rustc_hash::FxBuildHasher;
+
+/// How the containers here hash their keys.
+#[cfg(any(not(feature = "fxhash"), debug_assertions))]
+pub type BuildHasher = std::collections::hash_map::RandomState;
+
+pub type HashMap<K, V> = std::collections::HashMap<K, V, BuildHasher>;
+
+pub type HashSet<T> = std::collections::HashSet<T, BuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::{HashMap, HashSet};
+
+    #[test]
+    fn test_containers() {
+        let mut seen = HashSet::default();
+        assert!(seen.insert((1, 2)));
+        assert!(!seen.insert((1, 2)));
+
+        let counts: HashMap<char, usize> = "abca".chars().map(|c| (c, 1)).collect();
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts[&'a'], 1);
+    }
+}
@@ -0,0 +1,195 @@
+//! A log of every bulk run's answers and timings, one JSON object per line, so timings can be
+//! compared across refactors.
+//!
+//! `aoc run` and `aoc verify` append a [`Run`] per part to `runs.jsonl`, and `aoc trends` prints
+//! each part's timings over time with [`trends`].
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::Path;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use crate::runner::Outcome;
+
+/// One part's result from one bulk run, logged as a line of JSON like `{"time":1734000000,
+/// "commit":"a1836c4","year":2024,"day":6,"part":1,"answer":"41","micros":175}`.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Run {
+    /// When the run started, in seconds since the Unix epoch.
+    pub time: u64,
+    /// The commit that was checked out, if known.
+    pub commit: Option<String>,
+    pub year: u32,
+    pub day: u32,
+    pub part: usize,
+    /// The answer, or `None` if the part failed.
+    pub answer: Option<String>,
+    pub micros: u128
+}
+
+impl Run {
+    pub fn new(outcome: &Outcome, year: u32, time: u64, commit: Option<&str>) -> Run {
+        Run {
+            time,
+            commit: commit.map(str::to_owned),
+            year,
+            day: outcome.day,
+            part: outcome.part,
+            answer: outcome.result.as_ref().ok().map(|answer| answer.to_string()),
+            micros: outcome.micros
+        }
+    }
+}
+
+/// Append `runs` to the log at `path`, creating it if need be.
+pub fn append(path: &Path, runs: &[Run]) -> Result<(), String> {
+    let error = |e: std::io::Error| format!("Couldn't write {}: {e}", path.display());
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path).map_err(error)?;
+    for run in runs {
+        let line = serde_json::to_string(run).map_err(|e| e.to_string())?;
+        writeln!(file, "{line}").map_err(error)?;
+    }
+
+    Ok(())
+}
+
+/// Every run in the log at `path`, oldest first; none if there's no log yet.
+pub fn load(path: &Path) -> Result<Vec<Run>, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Couldn't read {}: {e}", path.display()))
+    };
+
+    text.lines().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| format!("{}:{}: {e}", path.display(), i + 1))
+        })
+        .collect()
+}
+
+/// `time` (seconds since the Unix epoch) as a UTC `YYYY-MM-DD HH:MM`.
+fn format_time(time: u64) -> String {
+    i64::try_from(time).ok()
+        .and_then(|time| DateTime::from_timestamp(time, 0))
+        .map_or_else(|| format!("@{time}"), |time| time.format("%Y-%m-%d %H:%M").to_string())
+}
+
+/// Timing history of each part of `days` of `year`, oldest run first, with the change from the
+/// previous successful run.
+pub fn trends(runs: &[Run], year: u32, days: &[u32]) -> String {
+    let mut by_part: BTreeMap<(u32, usize), Vec<&Run>> = BTreeMap::new();
+    runs.iter()
+        .filter(|run| run.year == year && days.contains(&run.day))
+        .for_each(|run| by_part.entry((run.day, run.part)).or_default().push(run));
+
+    let mut out = String::new();
+    for ((day, part), runs) in by_part {
+        writeln!(out, "Day {day:02} part {part}").unwrap();
+
+        let mut previous: Option<u128> = None;
+        for run in runs {
+            let commit = run.commit.as_deref().unwrap_or("-");
+            write!(out, "  {}  {commit:<8}", format_time(run.time)).unwrap();
+            if run.answer.is_none() {
+                writeln!(out, "{:>12}", "failed").unwrap();
+                continue;
+            }
+
+            write!(out, "{:>10}μs", run.micros).unwrap();
+            if let Some(before) = previous.filter(|&before| before > 0) {
+                let change = (run.micros as f64 - before as f64) / before as f64 * 100.0;
+                write!(out, "  {change:+.1}%").unwrap();
+            }
+            out.push('\n');
+            previous = Some(run.micros);
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answer::Answer;
+    use crate::runner::Outcome;
+    use super::{append, format_time, load, trends, Run};
+
+    fn run(time: u64, day: u32, part: usize, answer: Option<&str>, micros: u128) -> Run {
+        let answer = answer.map(str::to_owned);
+        Run { time, commit: Some("abc1234".to_owned()), year: 2024, day, part, answer, micros }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let json = |run: &Run| serde_json::to_string(run).unwrap();
+        let outcome = Outcome { day: 6, part: 1, result: Ok(Answer::UInt(41)), micros: 175 };
+        let day06 = Run::new(&outcome, 2024, 1734000000, Some("a1836c4"));
+        assert_eq!(
+            json(&day06),
+            concat!(
+                "{\"time\":1734000000,\"commit\":\"a1836c4\",\"year\":2024,\"day\":6,\"part\":1,",
+                "\"answer\":\"41\",\"micros\":175}"
+            )
+        );
+        assert_eq!(serde_json::from_str::<Run>(&json(&day06)).unwrap(), day06);
+
+        let failed = Outcome { day: 17, part: 2, result: Err("oops".to_owned()), micros: 3 };
+        let failed = Run::new(&failed, 2024, 0, None);
+        assert_eq!(failed.answer, None);
+        assert_eq!(serde_json::from_str::<Run>(&json(&failed)).unwrap(), failed);
+
+        let tricky = run(1, 17, 1, Some("4,6,\"3\"\n\u{1}"), 9);
+        assert_eq!(serde_json::from_str::<Run>(&json(&tricky)).unwrap(), tricky);
+    }
+
+    #[test]
+    fn test_load() {
+        let path = std::env::temp_dir().join(format!("aoc-history-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(load(&path), Ok(Vec::new()));
+
+        let runs = [run(0, 6, 1, Some("41"), 200), run(60, 6, 2, None, 5)];
+        append(&path, &runs[..1]).unwrap();
+        append(&path, &runs[1..]).unwrap();
+        assert_eq!(load(&path), Ok(runs.to_vec()));
+
+        std::fs::write(&path, "{\"time\":1}\n").unwrap();
+        assert!(load(&path).unwrap_err().contains(":1: missing field"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(0), "1970-01-01 00:00");
+        assert_eq!(format_time(1734000000), "2024-12-12 10:40");
+        assert_eq!(format_time(951782400), "2000-02-29 00:00");
+    }
+
+    #[test]
+    fn test_trends() {
+        let runs = [
+            run(0, 6, 1, Some("41"), 200),
+            run(60, 6, 2, Some("6"), 1000),
+            run(60, 6, 1, Some("41"), 150),
+            run(120, 6, 1, None, 5),
+            run(180, 6, 1, Some("41"), 300),
+            run(180, 7, 1, Some("3749"), 10)
+        ];
+
+        assert_eq!(
+            trends(&runs, 2024, &[6]),
+            "Day 06 part 1
+  1970-01-01 00:00  abc1234        200μs
+  1970-01-01 00:01  abc1234        150μs  -25.0%
+  1970-01-01 00:02  abc1234       failed
+  1970-01-01 00:03  abc1234        300μs  +100.0%
+Day 06 part 2
+  1970-01-01 00:01  abc1234       1000μs
+"
+        );
+        assert_eq!(trends(&runs, 2023, &[6, 7]), "");
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::VecDeque;
+use std::ops::Sub;
+use itertools::Itertools;
+
+/// Differences between consecutive elements: `[a, b, c]` gives `[b - a, c - b]`.
+///
+/// For unsigned data, map to a signed type first so that decreases don't underflow.
+pub fn diffs<I, T>(iter: I) -> impl Iterator<Item=T>
+where
+    I: IntoIterator<Item=T>,
+    T: Copy + Sub<Output=T>
+{
+    iter.into_iter().tuple_windows().map(|(a, b)| b - a)
+}
+
+/// Direction of a run of consecutive elements.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Trend { Increasing, Decreasing, Constant }
+
+/// A maximal stretch `items[start..=end]` over which every step has the same [`Trend`].
+///
+/// Adjacent runs share an endpoint: the element where the trend changes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Run { pub start: usize, pub end: usize, pub trend: Trend }
+
+/// Split `items` into maximal strictly increasing, strictly decreasing, and constant runs.
+pub fn monotonic_runs<T: Ord>(items: &[T]) -> impl Iterator<Item=Run> + '_ {
+    items.windows(2).enumerate()
+        .map(|(i, w)| {
+            let trend = match w[0].cmp(&w[1]) {
+                std::cmp::Ordering::Less => Trend::Increasing,
+                std::cmp::Ordering::Greater => Trend::Decreasing,
+                std::cmp::Ordering::Equal => Trend::Constant
+            };
+            Run { start: i, end: i + 1, trend }
+        })
+        .coalesce(|r1, r2| {
+            if r1.trend == r2.trend { Ok(Run { end: r2.end, ..r1 }) } else { Err((r1, r2)) }
+        })
+}
+
+/// The middle element of `items`.
+///
+/// Only odd-length lists have a middle element; an empty or even-length list is an error, rather
+/// than picking one of the two elements either side of the middle.
+pub fn middle<T>(items: &[T]) -> Result<&T, String> {
+    if items.len() % 2 == 1 {
+        Ok(&items[items.len() / 2])
+    }
+    else {
+        Err(format!("No middle element in a list of length {}", items.len()))
+    }
+}
+
+/// Apply `f` to every window of `N` consecutive elements of `iter`.
+pub fn windowed_map<I, T, U, F, const N: usize>(iter: I, mut f: F) -> impl Iterator<Item=U>
+where
+    I: IntoIterator<Item=T>,
+    T: Copy,
+    F: FnMut([T; N]) -> U
+{
+    let mut window = VecDeque::with_capacity(N + 1);
+
+    iter.into_iter().filter_map(move |x| {
+        window.push_back(x);
+        if window.len() > N {
+            window.pop_front();
+        }
+
+        if window.len() == N {
+            Some(f(std::array::from_fn(|k| window[k])))
+        }
+        else {
+            None
+        }
+    })
+}
+
+/// Tail and cycle length of the sequence `start, f(start), f(f(start)), ...`, using Brent's
+/// algorithm: after `tail` steps, the values repeat every `cycle` steps.
+///
+/// Only `O(1)` values are kept at a time, but `f` is called up to about three times per element of
+/// the tail and cycle. Never returns if the sequence doesn't repeat.
+pub fn detect_cycle<T, F>(mut f: F, start: T) -> (usize, usize)
+where
+    T: Clone + Eq,
+    F: FnMut(&T) -> T
+{
+    // Find the cycle length: the hare runs ahead while the tortoise teleports to it at each power
+    // of two, so once both are in the cycle, the hare meets it within the next power.
+    let mut power = 1;
+    let mut cycle = 1;
+    let mut tortoise = start.clone();
+    let mut hare = f(&start);
+    while tortoise != hare {
+        if power == cycle {
+            tortoise = hare.clone();
+            power *= 2;
+            cycle = 0;
+        }
+        hare = f(&hare);
+        cycle += 1;
+    }
+
+    // With the hare a full cycle ahead, the two first meet where the cycle starts.
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..cycle {
+        hare = f(&hare);
+    }
+
+    let mut tail = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        tail += 1;
+    }
+
+    (tail, cycle)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use super::{detect_cycle, diffs, middle, monotonic_runs, windowed_map, Run, Trend};
+
+    #[test]
+    fn test_diffs() {
+        let ds: Vec<i64> = diffs([3, 1, 4, 1, 5]).collect();
+        assert_eq!(ds, vec![-2, 3, -3, 4]);
+        assert_eq!(diffs([7]).count(), 0);
+    }
+
+    #[test]
+    fn test_middle() {
+        assert_eq!(middle(&[7]), Ok(&7));
+        assert_eq!(middle(&[75, 47, 61, 53, 29]), Ok(&61));
+        assert_eq!(middle::<u32>(&[]), Err("No middle element in a list of length 0".to_owned()));
+        assert_eq!(middle(&[1, 2]), Err("No middle element in a list of length 2".to_owned()));
+    }
+
+    #[test]
+    fn test_monotonic_runs() {
+        let runs: Vec<Run> = monotonic_runs(&[1, 3, 2, 4, 5, 5, 5]).collect();
+        assert_eq!(runs, vec![
+            Run { start: 0, end: 1, trend: Trend::Increasing },
+            Run { start: 1, end: 2, trend: Trend::Decreasing },
+            Run { start: 2, end: 4, trend: Trend::Increasing },
+            Run { start: 4, end: 6, trend: Trend::Constant }
+        ]);
+
+        assert_eq!(monotonic_runs(&[1]).count(), 0);
+        assert_eq!(monotonic_runs::<usize>(&[]).count(), 0);
+    }
+
+    #[test]
+    fn test_windowed_map() {
+        let sums: Vec<i32> = windowed_map(1..=5, |[a, b, c]| a + b + c).collect();
+        assert_eq!(sums, vec![6, 9, 12]);
+
+        let pairs: Vec<(char, char)> = windowed_map("abc".chars(), |[a, b]| (a, b)).collect();
+        assert_eq!(pairs, vec![('a', 'b'), ('b', 'c')]);
+
+        assert_eq!(windowed_map(1..3, |[a, b, c]: [i32; 3]| a + b + c).count(), 0);
+    }
+
+    /// Tail and cycle length by remembering every value seen.
+    fn naive_cycle(f: impl Fn(u64) -> u64, start: u64) -> (usize, usize) {
+        let mut seen: HashMap<u64, usize> = HashMap::new();
+        let mut x = start;
+        for i in 0.. {
+            if let Some(&first) = seen.get(&x) {
+                return (first, i - first);
+            }
+            seen.insert(x, i);
+            x = f(x);
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn test_detect_cycle() {
+        assert_eq!(detect_cycle(|&x| (x + 1) % 7, 0), (0, 7));
+        assert_eq!(detect_cycle(|&x: &u32| x.saturating_sub(1), 5), (5, 1));
+        assert_eq!(detect_cycle(|&x| if x < 10 { x + 1 } else { 4 }, 0), (4, 7));
+
+        for modulus in [2, 255, 1009, 65537] {
+            for start in 0..5 {
+                let f = |x: u64| (x * x + 1) % modulus;
+                let expected = naive_cycle(f, start);
+                assert_eq!(detect_cycle(|&x| f(x), start), expected, "mod {modulus} from {start}");
+            }
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Advent of Code utilities that don't depend on any one year's puzzles: grids, graphs, parsers
+//! and number theory, plus the machinery for running, checking and documenting a year's days.
+//!
+//! A year's solutions crate declares its days with [`Day`](day::Day) and gets each day's binary
+//! from [`build_main!`].
+
+pub mod answer;
+pub mod args;
+pub mod cli;
+pub mod columns;
+pub mod config;
+pub mod day;
+pub mod describe;
+pub mod dp;
+pub mod dump;
+pub mod gen;
+pub mod graph;
+pub mod grid;
+pub mod hash;
+pub mod history;
+pub mod iter;
+pub mod linalg;
+pub mod logging;
+pub mod numtheory;
+pub mod parse;
+pub mod prelude;
+pub mod ranges;
+pub mod runner;
+pub mod samples;
+pub mod sanitize;
+pub mod search;
+pub mod simulation;
+pub mod snapshot;
+pub mod strategy;
+pub mod strings;
+
+/// Build a `main` that runs each part against the embedded input and prints its [`Answer`] and
+/// timing.
+///
+/// An optional `;`-separated list of `"--flag" => function` pairs adds alternative output modes:
+/// if the flag is passed on the command line, the function's result is printed instead of the
+/// part answers.
+///
+/// A part may be given as a [`Strategies`] registry instead of a single function; then
+/// `--strategy NAME` picks which of its solvers to run.
+///
+/// The input file is embedded from the calling crate's `inputs` directory, e.g.
+/// `build_main!("2024/day06.txt", ...)` embeds `inputs/2024/day06.txt`.
+///
+/// An optional `args: Type,` (just after the input file) names the day's extra arguments, as
+/// declared with [`day_args!`]; parts and modes may then take `(input, &Args)` instead of just the
+/// input. The flags shared with `aoc` (`--input PATH`, `--json`, `--timeout SECS`, `--repeat N` and
+/// `--dump DIR`) are parsed by [`cli`], and `-v`/`-vv` show the solvers' log messages (see
+/// [`logging`]). Modes ignore `--json`, `--timeout` and `--repeat`. Any other argument is an
+/// error.
+///
+/// [`Answer`]: crate::answer::Answer
+/// [`Strategies`]: crate::strategy::Strategies
+#[macro_export]
+macro_rules! build_main {
+    (
+        $input:literal,
+        $( args: $args:ty, )?
+        $( $part:literal => $solver:expr ),+
+        $(; $( $flag:literal => $mode:expr ),+ )?
+    ) => {
+        $crate::__build_main!(
+            @plain, $input, ( $($args)? ),
+            $( $part => $solver ),+ ; $($( $flag => $mode ),+)?
+        );
+    };
+}
+
+/// Like [`build_main!`], but for solvers (and modes) that may return a `Result`.
+#[macro_export]
+macro_rules! build_main_res {
+    (
+        $input:literal,
+        $( args: $args:ty, )?
+        $( $part:literal => $solver:expr ),+
+        $(; $( $flag:literal => $mode:expr ),+ )?
+    ) => {
+        $crate::__build_main!(
+            @res, $input, ( $($args)? ),
+            $( $part => $solver ),+ ; $($( $flag => $mode ),+)?
+        );
+    };
+}
+
+/// Declare a day's extra command-line arguments as an `Args` struct, for [`build_main!`].
+///
+/// Each `name: Type = default` becomes a field that can be overridden by `name = VALUE` in the
+/// day's section of `config.toml` (see [`config`](crate::config)), or with `--name VALUE`.
+#[macro_export]
+macro_rules! day_args {
+    ( $( $arg:ident : $argty:ty = $default:expr ),* $(,)? ) => {
+        /// Extra command-line arguments for this day.
+        #[derive(Debug, Clone)]
+        pub struct Args { $( pub $arg: $argty ),* }
+
+        impl Default for Args {
+            fn default() -> Args {
+                Args { $( $arg: $default ),* }
+            }
+        }
+
+        impl $crate::args::DayArgs for Args {
+            const NAMES: &'static [&'static str] = &[ $( stringify!($arg) ),* ];
+
+            fn set(&mut self, name: &str, value: &str) -> Result<bool, String> {
+                match name {
+                    $(
+                    stringify!($arg) => {
+                        self.$arg = value.parse().map_err(|e| {
+                            format!("Invalid value {value:?} for {name}: {e}")
+                        })?;
+                    },
+                    )*
+                    _ => return Ok(false)
+                }
+                Ok(true)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __build_main {
+    // Days without extra arguments get `()`.
+    ( @$kind:ident, $input:literal, (), $( $rest:tt )* ) => {
+        $crate::__build_main!(@$kind, $input, (()), $( $rest )*);
+    };
+    (
+        @$kind:ident, $input:literal, ( $args:ty ),
+        $( $part:literal => $solver:expr ),+ ; $( $flag:literal => $mode:expr ),*
+    ) => {
+        fn main() {
+            let exit = |e: String| -> ! {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            };
+
+            $crate::logging::init();
+            let (flags, mut rest) = $crate::cli::Flags::from_env().unwrap_or_else(|e| exit(e));
+            if let Some(dir) = &flags.dump {
+                $crate::dump::init_with(dir);
+            }
+            let config = $crate::config::Config::load(std::path::Path::new(
+                concat!(env!("CARGO_MANIFEST_DIR"), "/config.toml")
+            )).unwrap_or_else(|e| exit(e));
+            let (year, number) = $crate::config::input_day($input)
+                .unwrap_or_else(|| exit(format!("{} isn't named YEAR/dayNN.txt", $input)));
+            let args = $crate::args::from_cli::<$args>(config.section(year, number), &mut rest)
+                .unwrap_or_else(|e| exit(e));
+            let mode = $crate::cli::mode(&rest, &[$($flag),*]).unwrap_or_else(|e| exit(e));
+            let input_override = flags.read_input().unwrap_or_else(|e| exit(e));
+            // Parts may run on a worker thread (with `--timeout`), which shares the input.
+            let input: std::sync::Arc<str> = $crate::parse::normalize(
+                input_override.as_deref().unwrap_or(include_str!(
+                    concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/", $input)
+                ))
+            ).into();
+            $(
+            if mode.as_deref() == Some($flag) {
+                let output = $crate::strategy::Solver::solve(&$mode, &input, None, &args)
+                    .unwrap_or_else(|e| exit(e));
+                println!("{}", $crate::__finish!(@$kind, output, exit));
+                return;
+            }
+            )*
+            let strategy = flags.strategy.as_deref();
+            $(
+            let label = match $crate::strategy::Solver::<_, $args, _>::strategy_name(&$solver, strategy) {
+                Some(name) if strategy.is_some() => format!("{} [{}]", $part, name),
+                _ => $part.to_owned()
+            };
+            let (solver, part_args, runs) = ($solver, args.clone(), flags.clone());
+            let part_input = std::sync::Arc::clone(&input);
+            let (result, micros) = $crate::cli::with_timeout(flags.timeout, move || {
+                let strategy = runs.strategy.as_deref();
+                runs.repeat(|| {
+                    let solved = $crate::strategy::Solver::solve(
+                        &solver, &part_input, strategy, &part_args
+                    );
+                    $crate::answer::IntoAnswer::into_answer(solved?)
+                }, Result::is_err)
+            }).unwrap_or_else(|e| exit(format!("{}: {}", $part, e)));
+            let result = result.unwrap_or_else(|e| exit(format!("{}: {}", $part, e)));
+            println!("{}", flags.report(&label, &Ok(result), micros));
+            )+
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __finish {
+    (@plain, $result:expr, $exit:expr) => { $result };
+    (@res, $result:expr, $exit:expr) => {
+        $crate::answer::IntoAnswer::into_answer($result).unwrap_or_else(|e| $exit(e))
+    };
+}
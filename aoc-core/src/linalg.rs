@@ -0,0 +1,245 @@
+use std::ops::{Add, Div, Index, IndexMut, Mul, Neg, Rem, Sub};
+
+/// A 2-dimensional vector.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Vec2<T> { pub x: T, pub y: T }
+
+impl<T> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Vec2<T> {
+        Vec2 { x, y }
+    }
+}
+
+impl<T: Add<Output=T>> Add for Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Vec2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Sub<Output=T>> Sub for Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Neg<Output=T>> Neg for Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn neg(self) -> Self::Output {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Mul<Output=T> + Copy> Mul<T> for Vec2<T> {
+    type Output = Vec2<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Vec2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+/// A 2×2 matrix `[[a, b], [c, d]]`.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Mat2<T> { pub a: T, pub b: T, pub c: T, pub d: T }
+
+impl<T> Mat2<T> {
+    pub const fn new(a: T, b: T, c: T, d: T) -> Mat2<T> {
+        Mat2 { a, b, c, d }
+    }
+
+    /// The matrix whose columns are `u` and `v`.
+    pub fn from_cols(u: Vec2<T>, v: Vec2<T>) -> Mat2<T> {
+        Mat2::new(u.x, v.x, u.y, v.y)
+    }
+}
+
+impl<T: Copy + Mul<Output=T> + Sub<Output=T> + Neg<Output=T>> Mat2<T> {
+    pub fn det(&self) -> T {
+        self.a * self.d - self.b * self.c
+    }
+
+    /// The adjugate `[[d, -b], [-c, a]]`, which satisfies `M * adj(M) = det(M) * I`.
+    pub fn adjugate(&self) -> Mat2<T> {
+        Mat2::new(self.d, -self.b, -self.c, self.a)
+    }
+}
+
+impl<T: Copy + Mul<Output=T> + Add<Output=T>> Mul<Vec2<T>> for Mat2<T> {
+    type Output = Vec2<T>;
+
+    fn mul(self, rhs: Vec2<T>) -> Self::Output {
+        Vec2::new(self.a * rhs.x + self.b * rhs.y, self.c * rhs.x + self.d * rhs.y)
+    }
+}
+
+impl<T> Mat2<T>
+where
+    T: Copy + Default + Eq + Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Neg<Output=T>
+        + Div<Output=T> + Rem<Output=T>
+{
+    /// The unique integer solution `v` to `M * v = rhs`.
+    ///
+    /// Returns `None` if `M` is singular (so there is no unique solution), or if the unique
+    /// solution `adj(M) * rhs / det(M)` isn't integral.
+    pub fn solve_exact(&self, rhs: Vec2<T>) -> Option<Vec2<T>> {
+        let zero = T::default();
+        let det = self.det();
+        if det == zero {
+            return None;
+        }
+
+        let scaled = self.adjugate() * rhs;
+        if scaled.x % det == zero && scaled.y % det == zero {
+            Some(Vec2::new(scaled.x / det, scaled.y / det))
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// A square matrix of `u64`s with arithmetic modulo 2^64, so that high powers wrap around rather
+/// than overflow; results are exact as long as they fit in 64 bits.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WrappingMatrix {
+    size: usize,
+    /// Entries row by row.
+    cells: Vec<u64>
+}
+
+impl WrappingMatrix {
+    pub fn zero(size: usize) -> WrappingMatrix {
+        WrappingMatrix { size, cells: vec![0; size * size] }
+    }
+
+    pub fn identity(size: usize) -> WrappingMatrix {
+        let mut result = WrappingMatrix::zero(size);
+        (0..size).for_each(|i| result[(i, i)] = 1);
+        result
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The matrix product `self * rhs`.
+    pub fn mul(&self, rhs: &WrappingMatrix) -> WrappingMatrix {
+        assert_eq!(self.size, rhs.size, "Matrix sizes differ");
+        let mut result = WrappingMatrix::zero(self.size);
+
+        for i in 0..self.size {
+            for k in 0..self.size {
+                let a = self[(i, k)];
+                if a == 0 {
+                    continue;
+                }
+                for j in 0..self.size {
+                    result[(i, j)] = result[(i, j)].wrapping_add(a.wrapping_mul(rhs[(k, j)]));
+                }
+            }
+        }
+
+        result
+    }
+
+    /// The matrix-vector product `self * v`.
+    pub fn mul_vec(&self, v: &[u64]) -> Vec<u64> {
+        assert_eq!(self.size, v.len(), "Vector length differs from matrix size");
+        (0..self.size)
+            .map(|i| {
+                v.iter().enumerate()
+                    .fold(0u64, |acc, (j, &x)| acc.wrapping_add(self[(i, j)].wrapping_mul(x)))
+            })
+            .collect()
+    }
+
+    /// `self` to the power `exp`, by repeated squaring.
+    pub fn pow(&self, mut exp: usize) -> WrappingMatrix {
+        let mut result = WrappingMatrix::identity(self.size);
+        let mut base = self.clone();
+
+        while exp > 0 {
+            if exp % 2 == 1 {
+                result = result.mul(&base);
+            }
+            exp /= 2;
+            if exp > 0 {
+                base = base.mul(&base);
+            }
+        }
+
+        result
+    }
+}
+
+impl Index<(usize, usize)> for WrappingMatrix {
+    type Output = u64;
+
+    fn index(&self, (i, j): (usize, usize)) -> &u64 {
+        &self.cells[i * self.size + j]
+    }
+}
+
+impl IndexMut<(usize, usize)> for WrappingMatrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut u64 {
+        &mut self.cells[i * self.size + j]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Mat2, Vec2, WrappingMatrix};
+
+    #[test]
+    fn test_det_and_adjugate() {
+        let m: Mat2<i64> = Mat2::new(94, 22, 34, 67);
+        assert_eq!(m.det(), 94 * 67 - 22 * 34);
+        assert_eq!(m.adjugate(), Mat2::new(67, -22, -34, 94));
+
+        let v = Vec2::new(3, -5);
+        assert_eq!(m.adjugate() * (m * v), v * m.det());
+    }
+
+    #[test]
+    fn test_from_cols() {
+        let m = Mat2::from_cols(Vec2::new(1, 2), Vec2::new(3, 4));
+        assert_eq!(m, Mat2::new(1, 3, 2, 4));
+        assert_eq!(m * Vec2::new(1, 0), Vec2::new(1, 2));
+    }
+
+    #[test]
+    fn test_solve_exact() {
+        let m: Mat2<i64> = Mat2::from_cols(Vec2::new(94, 34), Vec2::new(22, 67));
+        assert_eq!(m.solve_exact(Vec2::new(8400, 5400)), Some(Vec2::new(80, 40)));
+        assert_eq!(m.solve_exact(Vec2::new(8401, 5400)), None);
+
+        let negative: Mat2<i64> = Mat2::new(1, 1, 1, -1);
+        assert_eq!(negative.solve_exact(Vec2::new(-2, 4)), Some(Vec2::new(1, -3)));
+
+        let singular: Mat2<i64> = Mat2::new(2, 4, 1, 2);
+        assert_eq!(singular.solve_exact(Vec2::new(2, 1)), None);
+    }
+
+    #[test]
+    fn test_wrapping_matrix() {
+        // Fibonacci: [[1, 1], [1, 0]]^n = [[F(n+1), F(n)], [F(n), F(n-1)]].
+        let mut fib = WrappingMatrix::zero(2);
+        fib[(0, 0)] = 1;
+        fib[(0, 1)] = 1;
+        fib[(1, 0)] = 1;
+
+        assert_eq!(fib.pow(0), WrappingMatrix::identity(2));
+        assert_eq!(fib.pow(10)[(0, 1)], 55);
+        assert_eq!(fib.pow(93)[(0, 1)], 12_200_160_415_121_876_738);
+        assert_eq!(fib.pow(10).mul_vec(&[1, 0]), vec![89, 55]);
+
+        // F(94) doesn't fit in 64 bits, so it wraps around.
+        let f94 = 19_740_274_219_868_223_167u128;
+        assert_eq!(fib.pow(94)[(0, 1)], f94 as u64);
+        assert_eq!(fib.pow(50).mul(&fib.pow(44)), fib.pow(94));
+    }
+}
@@ -0,0 +1,77 @@
+//! Debug traces from solvers, shown on stderr with `-v` (debug) or `-vv` (trace).
+//!
+//! Solvers log with the [`log`] macros rather than printing, so traces never end up mixed in with
+//! the answers on stdout. Info messages (like a rendering that's nice to see but isn't the answer)
+//! also need `-v`; by default only warnings and errors are shown.
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Writes every record it's given to stderr, leaving the filtering to [`log::max_level`].
+struct Stderr;
+
+impl Log for Stderr {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        eprintln!("[{} {}] {}", record.level(), record.target(), record.args());
+    }
+
+    fn flush(&self) {}
+}
+
+/// The level requested by the `-v` flags in `args`: each `-v` (or `--verbose`) raises it one step
+/// from warnings to debug to trace, and `-vv` counts as two.
+pub fn verbosity_in<I: IntoIterator<Item=String>>(args: I) -> LevelFilter {
+    let count: usize = args.into_iter()
+        .map(|arg| match arg.as_str() {
+            "-v" | "--verbose" => 1,
+            "-vv" => 2,
+            _ => 0
+        })
+        .sum();
+
+    match count {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Debug,
+        _ => LevelFilter::Trace
+    }
+}
+
+/// Whether `arg` is one of the flags read by [`verbosity_in`].
+pub fn is_verbosity_flag(arg: &str) -> bool {
+    matches!(arg, "-v" | "-vv" | "--verbose")
+}
+
+/// Send log records to stderr, at the level requested on the command line.
+pub fn init() {
+    init_with(verbosity_in(std::env::args()));
+}
+
+/// Send log records up to `level` to stderr. Only the first call installs the logger, but every
+/// call sets the level.
+pub fn init_with(level: LevelFilter) {
+    let _ = log::set_logger(&Stderr);
+    log::set_max_level(level);
+}
+
+#[cfg(test)]
+mod tests {
+    use log::LevelFilter;
+    use super::{is_verbosity_flag, verbosity_in};
+
+    fn args(s: &str) -> Vec<String> {
+        s.split(' ').map(|a| a.to_owned()).collect()
+    }
+
+    #[test]
+    fn test_verbosity_in() {
+        assert_eq!(verbosity_in(args("day06 --bench")), LevelFilter::Warn);
+        assert_eq!(verbosity_in(args("day06 -v")), LevelFilter::Debug);
+        assert_eq!(verbosity_in(args("day06 --verbose --bench")), LevelFilter::Debug);
+        assert_eq!(verbosity_in(args("day06 -vv")), LevelFilter::Trace);
+        assert_eq!(verbosity_in(args("day06 -v -v")), LevelFilter::Trace);
+        assert!(is_verbosity_flag("-vv") && !is_verbosity_flag("-x"));
+    }
+}
@@ -0,0 +1,67 @@
+//! Decimal digit manipulation: counting, splitting and concatenating numbers.
+
+/// Number of decimal digits in `n`; 0 has one digit.
+pub fn num_digits(n: u64) -> u32 {
+    n.checked_ilog10().unwrap_or(0) + 1
+}
+
+/// `n` split before its last `k` digits: `(n / 10^k, n % 10^k)`, so `split_at_digit(1234, 1)` is
+/// `(123, 4)`. Splitting off more digits than `n` has gives `(0, n)`.
+pub fn split_at_digit(n: u64, k: u32) -> (u64, u64) {
+    match 10u64.checked_pow(k) {
+        Some(shift) => (n / shift, n % shift),
+        None => (0, n)
+    }
+}
+
+/// The digits of `a` followed by those of `b`, like `12 || 345 = 12345`; `None` on overflow.
+pub fn concat(a: u64, b: u64) -> Option<u64> {
+    a.checked_mul(10u64.checked_pow(num_digits(b))?)?.checked_add(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{concat, num_digits, split_at_digit};
+
+    #[test]
+    fn test_num_digits() {
+        let cases = [(0, 1), (9, 1), (10, 2), (99, 2), (999, 3), (1000, 4), (u64::MAX, 20)];
+        for (n, digits) in cases {
+            assert_eq!(num_digits(n), digits, "{n}");
+        }
+        assert_eq!(num_digits(10u64.pow(19) - 1), 19);
+        assert_eq!(num_digits(10u64.pow(19)), 20);
+    }
+
+    #[test]
+    fn test_split_at_digit() {
+        assert_eq!(split_at_digit(1234, 1), (123, 4));
+        assert_eq!(split_at_digit(1000, 3), (1, 0));
+        assert_eq!(split_at_digit(999, 3), (0, 999));
+        assert_eq!(split_at_digit(999, 0), (999, 0));
+        assert_eq!(split_at_digit(u64::MAX, 19), (1, 8_446_744_073_709_551_615));
+        assert_eq!(split_at_digit(u64::MAX, 20), (0, u64::MAX));
+        assert_eq!(split_at_digit(u64::MAX, 25), (0, u64::MAX));
+    }
+
+    #[test]
+    fn test_concat() {
+        assert_eq!(concat(12, 345), Some(12345));
+        assert_eq!(concat(999, 1000), Some(9991000));
+        assert_eq!(concat(1000, 999), Some(1000999));
+        assert_eq!(concat(5, 0), Some(50));
+        assert_eq!(concat(0, 5), Some(5));
+        assert_eq!(concat(1, 8_446_744_073_709_551_615), Some(u64::MAX));
+        assert_eq!(concat(1, 8_446_744_073_709_551_616), None);
+        assert_eq!(concat(2, 0), Some(20));
+        assert_eq!(concat(u64::MAX / 10 + 1, 0), None);
+
+        for a in [1, 9, 10, 99, 100, 999, 1000] {
+            for b in [0, 1, 9, 10, 99, 100, 999, 1000] {
+                let joined = concat(a, b).unwrap();
+                assert_eq!(joined.to_string(), format!("{a}{b}"));
+                assert_eq!(split_at_digit(joined, num_digits(b)), (a, b));
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Small number-theoretic helpers shared between days.
+
+use std::ops::{Add, Div, Mul};
+
+pub mod digits;
+
+/// The `n`th triangular number, `0 + 1 + ... + n`.
+pub fn triangular<T>(n: T) -> T
+where
+    T: Copy + From<u8> + Add<Output=T> + Mul<Output=T> + Div<Output=T>
+{
+    n * (n + T::from(1)) / T::from(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::triangular;
+
+    #[test]
+    fn test_triangular() {
+        let expected: Vec<usize> = (0..50).scan(0, |t, n| { *t += n; Some(*t) }).collect();
+        let actual: Vec<usize> = (0..50).map(triangular).collect();
+        assert_eq!(actual, expected);
+        assert_eq!(triangular(1_000_000u64), 500_000_500_000);
+    }
+}
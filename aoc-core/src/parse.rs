@@ -0,0 +1,248 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use nom::character::complete::{digit1, line_ending};
+use nom::character::complete::char;
+use nom::combinator::{all_consuming, map_res, opt, recognize, value};
+use nom::IResult;
+use nom::multi::{many1_count, separated_list1};
+use nom::sequence::{pair, separated_pair};
+
+/// A line of input that didn't match the expected format, and where and why, if known.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    /// 1-based line number of the offending line.
+    pub line: usize,
+    /// 1-based column (counting characters) where the line goes wrong.
+    pub column: Option<usize>,
+    pub text: String,
+    /// What's wrong with the line, e.g. `unexpected 'x'`.
+    pub reason: Option<String>
+}
+
+impl ParseError {
+    /// Line number `line` (1-based), reading `text`, failed to parse.
+    pub fn new(line: usize, text: &str) -> ParseError {
+        ParseError { line, column: None, text: text.to_owned(), reason: None }
+    }
+
+    /// The same error, because of `reason`.
+    pub fn because(self, reason: impl Into<String>) -> ParseError {
+        ParseError { reason: Some(reason.into()), ..self }
+    }
+
+    /// The same error, at 1-based `column`, because of `reason`.
+    pub fn at(self, column: usize, reason: impl Into<String>) -> ParseError {
+        ParseError { column: Some(column), ..self.because(reason) }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to parse line {}", self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ", column {column}")?;
+        }
+        match &self.reason {
+            Some(reason) => write!(f, ": {reason} in {:?}", self.text),
+            None => write!(f, ": {:?}", self.text)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type Result<T> = std::result::Result<T, ParseError>;
+
+/// `input` the way the parsers expect it: without a byte order mark, with `\n` line endings, and
+/// without trailing blank lines or whitespace.
+///
+/// Inputs saved on Windows or by some editors differ in these ways, so the binaries and the day
+/// registry normalize every input before solving it.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input).trim_end();
+    if input.contains("\r\n") {
+        Cow::Owned(input.replace("\r\n", "\n"))
+    }
+    else {
+        Cow::Borrowed(input)
+    }
+}
+
+/// Unsigned decimal number.
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse::<T>)(input)
+}
+
+/// Decimal number with an optional leading `-`.
+///
+/// The sign and digits are parsed together, so the most negative value of `T` parses without
+/// overflowing.
+pub fn signed<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse::<T>)(input)
+}
+
+/// The break between two sections of input: the end of the last line of the first section, then
+/// one or more blank lines. Either `\n` or `\r\n` line endings are accepted.
+pub fn section_break(input: &str) -> IResult<&str, ()> {
+    value((), pair(line_ending, many1_count(line_ending)))(input)
+}
+
+/// Input made of two differently-formatted sections separated by a blank line, such as rules
+/// followed by updates.
+pub fn two_sections<'a, A, B, PA, PB>(
+    first: PA,
+    second: PB
+) -> impl FnMut(&'a str) -> IResult<&'a str, (A, B)>
+where
+    PA: FnMut(&'a str) -> IResult<&'a str, A>,
+    PB: FnMut(&'a str) -> IResult<&'a str, B>
+{
+    separated_pair(first, section_break, second)
+}
+
+/// Input made of one or more sections in the same format, separated by blank lines.
+pub fn sections<'a, T, P>(section: P) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<T>>
+where
+    P: FnMut(&'a str) -> IResult<&'a str, T>
+{
+    separated_list1(section_break, section)
+}
+
+/// Lazily parse each line of `input` with `parser`, which must consume the entire line. Trailing
+/// blank lines are skipped.
+///
+/// This lets days aggregate over their input as it is parsed, rather than first collecting every
+/// line into a `Vec`.
+pub fn parse_lines<'a, T, P>(input: &'a str, mut parser: P) -> impl Iterator<Item=Result<T>> + 'a
+where
+    P: FnMut(&'a str) -> IResult<&'a str, T> + 'a
+{
+    input.trim_end_matches(['\r', '\n']).lines().enumerate().map(move |(i, line)| {
+        all_consuming(&mut parser)(line)
+            .map(|(_, t)| t)
+            .map_err(|e| match e {
+                nom::Err::Error(e) | nom::Err::Failure(e) => unexpected(i + 1, line, e.input),
+                nom::Err::Incomplete(_) => ParseError::new(i + 1, line)
+            })
+    })
+}
+
+/// The error for line number `line`, reading `text`, whose parser gave up with `rest` left over.
+pub fn unexpected(line: usize, text: &str, rest: &str) -> ParseError {
+    let column = text[..text.len() - rest.len()].chars().count() + 1;
+    let error = ParseError::new(line, text);
+    match rest.chars().next() {
+        Some(c) => error.at(column, format!("unexpected {c:?}")),
+        None => error.at(column, "unexpected end of line")
+    }
+}
+
+/// The error for the line of `input` where a parser of all of it gave up, with `rest` left over.
+pub fn unexpected_in(input: &str, rest: &str) -> ParseError {
+    let offset = input.len() - rest.len();
+    let start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let end = input[offset..].find('\n').map_or(input.len(), |i| offset + i);
+    let line = input[..start].matches('\n').count() + 1;
+    unexpected(line, &input[start..end], &input[offset..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::character::complete::{char, newline, space1};
+    use nom::multi::separated_list1;
+    use nom::sequence::separated_pair;
+    use super::{
+        normalize, parse_lines, sections, signed, two_sections, unexpected_in, unsigned, ParseError
+    };
+
+    #[test]
+    fn test_parse_lines() {
+        let parsed: Vec<(u32, u32)> = parse_lines("1 2\n30   4\n", separated_pair(unsigned, space1, unsigned))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(parsed, vec![(1, 2), (30, 4)]);
+    }
+
+    #[test]
+    fn test_unexpected_in() {
+        let input = "12\n3x4\n5";
+        let error = ParseError::new(2, "3x4").at(2, "unexpected 'x'");
+        assert_eq!(unexpected_in(input, "x4\n5"), error);
+        let error = ParseError::new(1, "12").at(1, "unexpected '1'");
+        assert_eq!(unexpected_in(input, input), error);
+        assert_eq!(
+            unexpected_in(input, "\n5"),
+            ParseError::new(2, "3x4").at(4, "unexpected end of line")
+        );
+        assert_eq!(unexpected_in("", ""), ParseError::new(1, "").at(1, "unexpected end of line"));
+    }
+
+    #[test]
+    fn test_parse_lines_reports_bad_line() {
+        let mut lines = parse_lines("1\n2x\n3", unsigned::<u32>);
+        assert_eq!(lines.next(), Some(Ok(1)));
+        let error = ParseError::new(2, "2x").at(2, "unexpected 'x'");
+        assert_eq!(lines.next(), Some(Err(error.clone())));
+        assert_eq!(lines.next(), Some(Ok(3)));
+        assert_eq!(lines.next(), None);
+        assert_eq!(error.to_string(), "Failed to parse line 2, column 2: unexpected 'x' in \"2x\"");
+
+        let pair = separated_pair(unsigned::<u32>, space1, unsigned::<u32>);
+        let mut pairs = parse_lines("1 2\n3", pair);
+        let error = ParseError::new(2, "3").at(2, "unexpected end of line");
+        assert_eq!(pairs.nth(1), Some(Err(error)));
+        assert_eq!(ParseError::new(4, "ab").to_string(), "Failed to parse line 4: \"ab\"");
+    }
+
+    #[test]
+    fn test_signed() {
+        assert_eq!(signed::<i32>("42,"), Ok((",", 42)));
+        assert_eq!(signed::<i32>("-42"), Ok(("", -42)));
+        assert_eq!(signed::<i32>("-0"), Ok(("", 0)));
+        assert_eq!(signed::<i32>("007"), Ok(("", 7)));
+        assert_eq!(signed::<i32>("-007"), Ok(("", -7)));
+        assert_eq!(signed::<i64>("-9223372036854775808"), Ok(("", i64::MIN)));
+        assert_eq!(signed::<i64>("9223372036854775807"), Ok(("", i64::MAX)));
+
+        assert!(signed::<i64>("9223372036854775808").is_err());
+        assert!(signed::<i64>("-9223372036854775809").is_err());
+        assert!(signed::<i32>("-").is_err());
+        assert!(signed::<i32>("--1").is_err());
+        assert!(signed::<i32>("+1").is_err());
+        // Like `str::parse`, unsigned types reject any sign at all.
+        assert!(signed::<u32>("-0").is_err());
+        assert_eq!(signed::<u32>("12"), Ok(("", 12)));
+    }
+
+    #[test]
+    fn test_two_sections() {
+        let mut parser = two_sections(
+            separated_list1(newline, unsigned::<u32>),
+            separated_list1(char(','), unsigned::<u32>)
+        );
+
+        assert_eq!(parser("1\n2\n\n3,4"), Ok(("", (vec![1, 2], vec![3, 4]))));
+        // Extra blank lines are fine, but a single line break doesn't end a section.
+        assert_eq!(parser("1\n\n\n\n3,4\n"), Ok(("\n", (vec![1], vec![3, 4]))));
+        assert!(parser("1\n2\n3,4").is_err());
+        // The break may use CRLF line endings.
+        assert_eq!(parser("1\r\n\r\n3,4"), Ok(("", (vec![1], vec![3, 4]))));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("1 2\n3 4\n"), "1 2\n3 4");
+        assert_eq!(normalize("\u{feff}1 2\r\n3 4\r\n\r\n  \r\n"), "1 2\n3 4");
+        assert_eq!(normalize("a\n\nb"), "a\n\nb");
+        assert_eq!(normalize("a\r\n\r\nb"), "a\n\nb");
+        assert_eq!(normalize(""), "");
+    }
+
+    #[test]
+    fn test_sections() {
+        let mut parser = sections(separated_list1(newline, unsigned::<u32>));
+        assert_eq!(parser("1\n2\n\n3\n\n4\n5"), Ok(("", vec![vec![1, 2], vec![3], vec![4, 5]])));
+        assert_eq!(parser("1"), Ok(("", vec![vec![1]])));
+    }
+}
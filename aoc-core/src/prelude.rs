@@ -0,0 +1,13 @@
+//! The types and helpers most days reach for, for `use aoc_core::prelude::*` (re-exported as
+//! `adventofcode2024::prelude` by the 2024 solutions).
+//!
+//! This is kept deliberately small, so that a glob import doesn't shadow anything unexpectedly:
+//! the grid and graph types, [`Vec2`] and [`Mat2`], the common parsers, and [`Answer`].
+//! `parse::Result` is left out, since it would hide `std::result::Result`. More specialised
+//! helpers (Graphviz output, cliques, iterator adapters, ...) are imported from their modules.
+
+pub use crate::answer::Answer;
+pub use crate::graph::{grid_with_headings, Heading, Turns, WeightedGraph};
+pub use crate::grid::{diamond, Direction, Grid};
+pub use crate::linalg::{Mat2, Vec2};
+pub use crate::parse::{parse_lines, sections, signed, two_sections, unsigned, ParseError};
@@ -0,0 +1,155 @@
+//! Sets of values stored as disjoint half-open ranges.
+
+use std::collections::BTreeMap;
+use std::iter::Sum;
+use std::ops::{Range, Sub};
+
+/// A set of values, stored as disjoint, non-adjacent half-open ranges: inserting `1..3` and then
+/// `3..5` leaves the single range `1..5`.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct RangeSet<T: Ord> {
+    /// Start of each range, mapped to its end.
+    ranges: BTreeMap<T, T>
+}
+
+impl<T: Copy + Ord> RangeSet<T> {
+    pub fn new() -> RangeSet<T> {
+        RangeSet { ranges: BTreeMap::new() }
+    }
+
+    /// Add every value in `range`, merging it with any ranges it overlaps or touches.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let Range { mut start, mut end } = range;
+        if let Some((&s, &e)) = self.ranges.range(..=start).next_back() {
+            if e >= start {
+                start = s;
+                end = end.max(e);
+            }
+        }
+
+        while let Some((&s, &e)) = self.ranges.range(start..=end).next() {
+            self.ranges.remove(&s);
+            end = end.max(e);
+        }
+
+        self.ranges.insert(start, end);
+    }
+
+    /// Remove every value in `range`, splitting any range it falls in the middle of.
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let overlapping: Vec<(T, T)> = self.ranges.range(..range.end).rev()
+            .map(|(&s, &e)| (s, e))
+            .take_while(|&(_, e)| e > range.start)
+            .collect();
+
+        for (s, e) in overlapping {
+            self.ranges.remove(&s);
+            if s < range.start {
+                self.ranges.insert(s, range.start);
+            }
+            if e > range.end {
+                self.ranges.insert(range.end, e);
+            }
+        }
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.ranges.range(..=value).next_back().is_some_and(|(_, e)| value < e)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The ranges, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item=Range<T>> + '_ {
+        self.ranges.iter().map(|(&s, &e)| s..e)
+    }
+
+    /// Total number of values in the set.
+    pub fn len(&self) -> T
+    where
+        T: Sub<Output=T> + Sum
+    {
+        self.iter().map(|r| r.end - r.start).sum()
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item=Range<T>>>(iter: I) -> RangeSet<T> {
+        let mut set = RangeSet::new();
+        iter.into_iter().for_each(|range| set.insert(range));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::ops::Range;
+    use crate::gen::Rng;
+    use super::RangeSet;
+
+    #[test]
+    fn test_insert() {
+        let mut set: RangeSet<i64> = [1..3, 5..7, 3..4].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![1..4, 5..7]);
+
+        set.insert(4..5);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![1..7]);
+
+        set.insert(-3..0);
+        set.insert(10..10);
+        set.insert(0..20);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![-3..20]);
+        assert_eq!(set.len(), 23);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut set: RangeSet<u32> = [0..10, 20..30].into_iter().collect();
+        set.remove(3..5);
+        set.remove(8..22);
+        set.remove(40..50);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0..3, 5..8, 22..30]);
+        assert!(set.contains(&2) && !set.contains(&3) && set.contains(&5) && !set.contains(&30));
+        assert_eq!(set.len(), 14);
+
+        set.remove(0..100);
+        assert!(set.is_empty());
+        assert_eq!(set.len(), 0);
+    }
+
+    #[test]
+    fn test_against_values() {
+        let mut rng = Rng::new(41);
+        let mut set = RangeSet::new();
+        let mut values = BTreeSet::new();
+
+        for _ in 0..2000 {
+            let start = rng.below(100);
+            let range: Range<usize> = start..start + rng.below(10);
+            if rng.chance(2, 3) {
+                values.extend(range.clone());
+                set.insert(range);
+            }
+            else {
+                range.clone().for_each(|v| { values.remove(&v); });
+                set.remove(range);
+            }
+
+            assert_eq!(set.len(), values.len());
+            assert!((0..110).all(|v| set.contains(&v) == values.contains(&v)));
+            // Ranges stay disjoint and never touch, or they'd have been merged.
+            assert!(set.iter().zip(set.iter().skip(1)).all(|(a, b)| a.end < b.start));
+        }
+    }
+}
@@ -0,0 +1,326 @@
+//! Running days' parts in bulk, for `aoc run` and `aoc verify`, with failures reported as JSON so
+//! that scripts can act on them.
+
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use crate::answer::Answer;
+use crate::cli::with_timeout;
+use crate::config::Section;
+use crate::day::Day;
+
+/// The result of running one part.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Outcome {
+    pub day: u32,
+    pub part: usize,
+    /// The answer, or why there isn't one: the solver's error, or its panic message.
+    pub result: Result<Answer, String>,
+    pub micros: u128
+}
+
+/// Something that should make a run fail.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Failure {
+    /// The solver returned an error or panicked.
+    Error { day: u32, part: usize, message: String },
+    /// The solver's answer isn't the expected one.
+    Mismatch { day: u32, part: usize, expected: String, actual: String },
+    /// There's no expected answer to check the solver's against.
+    Unverified { day: u32, part: usize, actual: String },
+    /// Running the solver again gave a different result, e.g. because it depends on the iteration
+    /// order of a `HashMap`, which is seeded afresh for every map.
+    Nondeterministic { day: u32, part: usize, first: String, second: String }
+}
+
+/// Where the input for day `number` of `year` lives, under a solutions crate's directory `root`:
+/// `inputs/YEAR/dayNN.txt`, the same file its binary embeds.
+pub fn input_path(root: &Path, year: u32, number: u32) -> PathBuf {
+    root.join("inputs").join(year.to_string()).join(format!("day{number:02}.txt"))
+}
+
+/// Run part `part` (1 or 2) of `day` on `input`, with the named strategy (or its default) and the
+/// settings in `config`, turning a panic into an error.
+pub fn run_part(
+    day: &Day,
+    part: usize,
+    input: &str,
+    strategy: Option<&str>,
+    config: &Section
+) -> Outcome {
+    let solve = day.parts[part - 1];
+    let start = Instant::now();
+    let result = catch_unwind(AssertUnwindSafe(|| solve(input, strategy, config)));
+    let result = result.unwrap_or_else(|payload| {
+        let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_owned());
+        Err(format!("panicked: {message}"))
+    });
+
+    Outcome { day: day.number, part, result, micros: start.elapsed().as_micros() }
+}
+
+/// [`run_part`] on a worker thread, giving up with an error if it takes longer than `timeout`; see
+/// [`with_timeout`].
+pub fn run_part_with_timeout(
+    day: &Day,
+    part: usize,
+    input: &str,
+    strategy: Option<&str>,
+    config: &Section,
+    timeout: Duration
+) -> Outcome {
+    let (day, input, config) = (*day, input.to_owned(), config.to_vec());
+    let strategy = strategy.map(str::to_owned);
+    let outcome = with_timeout(Some(timeout), move || {
+        run_part(&day, part, &input, strategy.as_deref(), &config)
+    });
+
+    outcome.unwrap_or_else(|e| {
+        Outcome { day: day.number, part, result: Err(e), micros: timeout.as_micros() }
+    })
+}
+
+/// Expected answers, keyed by `(year, day, part)`.
+///
+/// Each line is a year and day number followed by the day's expected answers, separated by
+/// whitespace, e.g. `2024 17 4,6,3,5,6,3,5,2,1,0 117440`; a day may list just its part 1 answer
+/// (though [`check_verified`] then fails its part 2). Blank lines and lines starting with `#` are
+/// skipped.
+pub fn parse_answers(text: &str) -> Result<HashMap<(u32, u32, usize), String>, String> {
+    let mut answers = HashMap::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let mut number = |what: &str| {
+            fields.next().and_then(|n| n.parse::<u32>().ok())
+                .ok_or_else(|| format!("Line {}: expected a {what} number, got {line:?}", i + 1))
+        };
+        let (year, day) = (number("year")?, number("day")?);
+        let expected: Vec<&str> = fields.collect();
+        if expected.is_empty() || expected.len() > 2 {
+            return Err(format!("Line {}: expected one or two answers, got {line:?}", i + 1));
+        }
+
+        for (part, answer) in expected.into_iter().enumerate() {
+            answers.insert((year, day, part + 1), answer.to_owned());
+        }
+    }
+
+    Ok(answers)
+}
+
+/// What's wrong with `outcome`, if anything, given the expected answer (if known).
+pub fn check(outcome: &Outcome, expected: Option<&str>) -> Option<Failure> {
+    let Outcome { day, part, .. } = *outcome;
+    match (&outcome.result, expected) {
+        (Err(message), _) => Some(Failure::Error { day, part, message: message.clone() }),
+        (Ok(answer), Some(expected)) if answer.to_string() != expected => {
+            let (expected, actual) = (expected.to_owned(), answer.to_string());
+            Some(Failure::Mismatch { day, part, expected, actual })
+        },
+        _ => None
+    }
+}
+
+/// Like [`check`], but an answer with nothing to check it against is a failure too.
+pub fn check_verified(outcome: &Outcome, expected: Option<&str>) -> Option<Failure> {
+    let Outcome { day, part, .. } = *outcome;
+    match (&outcome.result, expected) {
+        (Ok(answer), None) => Some(Failure::Unverified { day, part, actual: answer.to_string() }),
+        _ => check(outcome, expected)
+    }
+}
+
+/// The failure, if `first` and `second` are outcomes of the same part with different results.
+pub fn check_repeat(first: &Outcome, second: &Outcome) -> Option<Failure> {
+    let Outcome { day, part, .. } = *first;
+    let describe = |outcome: &Outcome| match &outcome.result {
+        Ok(answer) => answer.to_string(),
+        Err(message) => format!("error: {message}")
+    };
+
+    (first.result != second.result).then(|| {
+        Failure::Nondeterministic { day, part, first: describe(first), second: describe(second) }
+    })
+}
+
+/// A JSON report of `failures`, on one line:
+/// `{"failures":[{"kind":"mismatch","day":5,"part":2,"expected":"123","actual":"124"}, ...]}`.
+pub fn json_report(failures: &[Failure]) -> String {
+    serde_json::json!({ "failures": failures }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answer::Answer;
+    use crate::day::Day;
+    use std::path::Path;
+    use std::time::Duration;
+    use super::{
+        check, check_repeat, check_verified, input_path, json_report, parse_answers, run_part,
+        run_part_with_timeout, Failure, Outcome
+    };
+
+    type Config = [(String, String)];
+
+    fn answer(input: &str, strategy: Option<&str>, config: &Config) -> Result<Answer, String> {
+        match (strategy, config.first()) {
+            (Some("lines"), _) => Ok(Answer::UInt(input.lines().count() as u64)),
+            (Some(name), _) => Err(format!("Unknown strategy {name:?}")),
+            (None, Some((_, value))) => value.parse().map(Answer::UInt).map_err(|_| "Bad".into()),
+            (None, None) => Ok(Answer::UInt(input.len() as u64))
+        }
+    }
+
+    fn broken(input: &str, _strategy: Option<&str>, _config: &Config) -> Result<Answer, String> {
+        if input.is_empty() { Err("Empty input".to_owned()) } else { panic!("Can't solve {input}") }
+    }
+
+    fn forever(_input: &str, _strategy: Option<&str>, _config: &Config) -> Result<Answer, String> {
+        loop {
+            std::thread::sleep(Duration::from_secs(1));
+        }
+    }
+
+    const DAY: Day = Day { number: 99, source: "", parts: [answer, broken] };
+
+    #[test]
+    fn test_run_part() {
+        assert_eq!(run_part(&DAY, 1, "abc", None, &[]).result, Ok(Answer::UInt(3)));
+        assert_eq!(run_part(&DAY, 2, "", None, &[]).result, Err("Empty input".to_owned()));
+        assert_eq!(
+            run_part(&DAY, 2, "abc", None, &[]).result,
+            Err("panicked: Can't solve abc".to_owned())
+        );
+
+        let config = [("answer".to_owned(), "7".to_owned())];
+        assert_eq!(run_part(&DAY, 1, "abc", None, &config).result, Ok(Answer::UInt(7)));
+        assert_eq!(run_part(&DAY, 1, "a\nb", Some("lines"), &[]).result, Ok(Answer::UInt(2)));
+    }
+
+    #[test]
+    fn test_run_part_with_timeout() {
+        let timeout = Duration::from_millis(50);
+        let outcome = run_part_with_timeout(&DAY, 1, "abc", None, &[], timeout);
+        assert_eq!(outcome.result, Ok(Answer::UInt(3)));
+        let outcome = run_part_with_timeout(&DAY, 1, "a\nb", Some("lines"), &[], timeout);
+        assert_eq!(outcome.result, Ok(Answer::UInt(2)));
+        let outcome = run_part_with_timeout(&DAY, 2, "abc", None, &[], timeout);
+        assert_eq!(outcome.result, Err("panicked: Can't solve abc".to_owned()));
+
+        let stuck = Day { number: 6, source: "", parts: [answer, forever] };
+        let outcome = run_part_with_timeout(&stuck, 2, "abc", None, &[], timeout);
+        assert_eq!(outcome.result, Err("timed out after 0.05s".to_owned()));
+        assert_eq!((outcome.day, outcome.part, outcome.micros), (6, 2, 50_000));
+    }
+
+    #[test]
+    fn test_input_path() {
+        assert_eq!(input_path(Path::new("aoc"), 2024, 6), Path::new("aoc/inputs/2024/day06.txt"));
+        assert_eq!(input_path(Path::new("aoc"), 2015, 25), Path::new("aoc/inputs/2015/day25.txt"));
+    }
+
+    #[test]
+    fn test_parse_answers() {
+        let text = "# year day part1 part2\n2024 1 11 31\n\n2024 17 4,6,3\n2023 1 142 281\n";
+        let answers = parse_answers(text).unwrap();
+        assert_eq!(answers.len(), 5);
+        assert_eq!(answers[&(2024, 1, 2)], "31");
+        assert_eq!(answers[&(2024, 17, 1)], "4,6,3");
+        assert_eq!(answers[&(2023, 1, 1)], "142");
+
+        assert!(parse_answers("2024 one 11").unwrap_err().starts_with("Line 1"));
+        assert!(parse_answers("2024 1 11 31\n2024 2").unwrap_err().starts_with("Line 2"));
+        assert!(parse_answers("2024 1 2 3 4").is_err());
+        // Without a year, the day is taken for one, and the first answer doesn't parse as a day.
+        let error = parse_answers("17 4,6,3 117440").unwrap_err();
+        assert_eq!(error, "Line 1: expected a day number, got \"17 4,6,3 117440\"");
+    }
+
+    #[test]
+    fn test_check() {
+        let outcome = |result| Outcome { day: 7, part: 1, result, micros: 0 };
+        assert_eq!(check(&outcome(Ok(Answer::UInt(3))), Some("3")), None);
+        assert_eq!(check(&outcome(Ok(Answer::UInt(3))), None), None);
+        assert_eq!(
+            check(&outcome(Ok(Answer::UInt(3))), Some("4")),
+            Some(Failure::Mismatch {
+                day: 7, part: 1, expected: "4".to_owned(), actual: "3".to_owned()
+            })
+        );
+        assert_eq!(
+            check(&outcome(Err("oops".to_owned())), Some("4")),
+            Some(Failure::Error { day: 7, part: 1, message: "oops".to_owned() })
+        );
+    }
+
+    #[test]
+    fn test_check_verified() {
+        let outcome = |result| Outcome { day: 7, part: 2, result, micros: 0 };
+        assert_eq!(check_verified(&outcome(Ok(Answer::UInt(3))), Some("3")), None);
+        assert_eq!(
+            check_verified(&outcome(Ok(Answer::UInt(3))), None),
+            Some(Failure::Unverified { day: 7, part: 2, actual: "3".to_owned() })
+        );
+        assert_eq!(
+            check_verified(&outcome(Ok(Answer::UInt(3))), Some("4")),
+            Some(Failure::Mismatch {
+                day: 7, part: 2, expected: "4".to_owned(), actual: "3".to_owned()
+            })
+        );
+        assert_eq!(
+            check_verified(&outcome(Err("oops".to_owned())), None),
+            Some(Failure::Error { day: 7, part: 2, message: "oops".to_owned() })
+        );
+    }
+
+    #[test]
+    fn test_check_repeat() {
+        let outcome = |result| Outcome { day: 16, part: 2, result, micros: 0 };
+        let three = outcome(Ok(Answer::UInt(3)));
+        assert_eq!(check_repeat(&three, &three), None);
+        assert_eq!(
+            check_repeat(&three, &outcome(Err("oops".to_owned()))),
+            Some(Failure::Nondeterministic {
+                day: 16, part: 2, first: "3".to_owned(), second: "error: oops".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_json_report() {
+        assert_eq!(json_report(&[]), "{\"failures\":[]}");
+
+        let failures = [
+            Failure::Error { day: 5, part: 2, message: "bad \"line\"\n\tat 3".to_owned() },
+            Failure::Mismatch { day: 7, part: 1, expected: "4".to_owned(), actual: "3".to_owned() },
+            Failure::Unverified { day: 7, part: 2, actual: "9".to_owned() },
+            Failure::Nondeterministic {
+                day: 16, part: 2, first: "45".to_owned(), second: "44".to_owned()
+            }
+        ];
+        assert_eq!(
+            json_report(&failures),
+            concat!(
+                "{\"failures\":[",
+                "{\"kind\":\"error\",\"day\":5,\"part\":2,",
+                "\"message\":\"bad \\\"line\\\"\\n\\tat 3\"},",
+                "{\"kind\":\"mismatch\",\"day\":7,\"part\":1,\"expected\":\"4\",\"actual\":\"3\"},",
+                "{\"kind\":\"unverified\",\"day\":7,\"part\":2,\"actual\":\"9\"},",
+                "{\"kind\":\"nondeterministic\",\"day\":16,\"part\":2,\"first\":\"45\",",
+                "\"second\":\"44\"}",
+                "]}"
+            )
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! Checking a day's parts against the examples published with its puzzle.
+//!
+//! Several days have more than one example, and some examples only come with an answer for one
+//! of the parts. [`test_samples!`](crate::test_samples) turns a table of them into a single test
+//! that runs every part on every example and reports all the mismatches at once.
+//!
+//! Every example is also run as it would be saved on Windows, with `\r\n` line endings and a
+//! trailing blank line, straight into the part's parser. Days whose examples are checked by their
+//! own tests instead use [`test_line_endings!`](crate::test_line_endings) for that.
+
+use crate::answer::{Answer, IntoAnswer};
+use crate::parse::normalize;
+use crate::strategy::Solver;
+
+/// Run `part` on `input`, and check that it gives the `expected` answer: as is, with
+/// [`windows`] line endings, and when [normalized](normalize) from a Windows copy with a byte
+/// order mark. The first is run twice, to catch answers that depend on the iteration order of a
+/// freshly seeded `HashMap` or `HashSet`.
+///
+/// Answers are compared as they're printed, so an unsuffixed integer literal (an `i32`) matches a
+/// part that returns a `usize`.
+pub fn check<T, F>(part: F, input: &str, expected: impl Into<Answer>) -> Result<(), String>
+where
+    T: IntoAnswer,
+    F: Fn(&str) -> T
+{
+    let expected = expected.into();
+    let windows = windows(input);
+    let marked = format!("\u{feff}{windows}");
+    let variants = [
+        (input, ""),
+        (input, " (on a second run)"),
+        (&windows, " (with Windows line endings)"),
+        (&normalize(&marked), " (normalized from a byte order mark and Windows line endings)")
+    ];
+
+    for (input, variant) in variants {
+        match part(input).into_answer() {
+            Ok(actual) if actual.to_string() == expected.to_string() => {},
+            Ok(actual) => return Err(format!("expected {expected}, got {actual}{variant}")),
+            Err(e) => return Err(format!("expected {expected}, got error{variant}: {e}"))
+        }
+    }
+
+    Ok(())
+}
+
+/// `input` as it might be saved on Windows: with `\r\n` line endings, and a trailing blank line.
+pub fn windows(input: &str) -> String {
+    format!("{}\r\n\r\n", input.replace('\n', "\r\n"))
+}
+
+/// Run `part` (with its default strategy) on `input` as is and with [`windows`] line endings, and
+/// check that it gives the same result both times.
+pub fn check_line_endings<T, M, S>(part: &S, input: &str) -> Result<(), String>
+where
+    T: IntoAnswer,
+    S: Solver<T, (), M>
+{
+    let answer = |input: &str| {
+        part.solve(input, None, &()).and_then(IntoAnswer::into_answer).map(|a| a.to_string())
+    };
+
+    match (answer(input), answer(&windows(input))) {
+        (unix, windows) if unix == windows => Ok(()),
+        (unix, windows) => Err(format!("{unix:?} as is, but {windows:?} with Windows line endings"))
+    }
+}
+
+/// Define a `test_samples` test that runs a day's two parts on each of its published examples.
+///
+/// The parts are given first, as functions of the input; days with extra arguments can pass a
+/// closure that supplies them. Then comes one `(input, part 1 answer, part 2 answer)` tuple per
+/// example, with `_` for a part the example has no answer for:
+///
+/// ```ignore
+/// test_samples! {
+///     part1, part2;
+///     (SMALL_INPUT, 2028, _),
+///     (LARGE_INPUT, 10092, 9021),
+/// }
+/// ```
+#[macro_export]
+macro_rules! test_samples {
+    (@check $failures:ident, $n:literal, $part:expr, $input:expr, _) => {};
+    (@check $failures:ident, $n:literal, $part:expr, $input:expr, $expected:tt) => {
+        if let Err(e) = $crate::samples::check($part, $input, $expected) {
+            $failures.push(format!("Part {} on {}: {e}", $n, stringify!($input)));
+        }
+    };
+    (
+        $part1:expr, $part2:expr;
+        $( ($input:expr, $expected1:tt, $expected2:tt) ),+ $(,)?
+    ) => {
+        #[test]
+        fn test_samples() {
+            let mut failures: Vec<String> = Vec::new();
+            $(
+            $crate::test_samples!(@check failures, 1, $part1, $input, $expected1);
+            $crate::test_samples!(@check failures, 2, $part2, $input, $expected2);
+            )+
+            assert!(failures.is_empty(), "{}", failures.join("\n"));
+        }
+    };
+}
+
+/// Check that each of the parts gives the same results on each of the inputs when they have
+/// [`windows`](crate::samples::windows) line endings: a test for days whose examples don't go
+/// through [`test_samples!`]. The parts are anything that can
+/// [`solve`](crate::strategy::Solver::solve) without arguments, like a part function or a
+/// [`Strategies`](crate::strategy::Strategies) registry; days with arguments can pass a closure
+/// that supplies them.
+///
+/// ```ignore
+/// test_line_endings! { part1, PART2; TEST_INPUT, LARGE_INPUT }
+/// ```
+#[macro_export]
+macro_rules! test_line_endings {
+    ( $( $part:expr ),+ ; $( $input:expr ),+ $(,)? ) => {
+        #[test]
+        fn test_line_endings() {
+            let inputs: &[(&str, &str)] = &[ $( (stringify!($input), $input) ),+ ];
+            let mut failures: Vec<String> = Vec::new();
+            $(
+            for &(name, input) in inputs {
+                if let Err(e) = $crate::samples::check_line_endings(&$part, input) {
+                    failures.push(format!("{} on {name}: {e}", stringify!($part)));
+                }
+            }
+            )+
+            assert!(failures.is_empty(), "{}", failures.join("\n"));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use super::{check, check_line_endings};
+
+    fn lines(input: &str) -> usize {
+        input.lines().filter(|line| !line.is_empty()).count()
+    }
+
+    fn first_line(input: &str) -> Result<String, String> {
+        input.lines().next().map(|line| line.to_owned()).ok_or("empty input".to_owned())
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check(lines, "a\nb", 2), Ok(()));
+        assert_eq!(check(lines, "a\nb", 3), Err("expected 3, got 2".to_owned()));
+        assert_eq!(check(first_line, "a\nb", "a"), Ok(()));
+        let error = "expected a, got error: empty input".to_owned();
+        assert_eq!(check(first_line, "", "a"), Err(error));
+    }
+
+    #[test]
+    fn test_check_repeat() {
+        let runs = AtomicUsize::new(0);
+        let counter = |_: &str| runs.fetch_add(1, Ordering::Relaxed);
+        assert_eq!(check(counter, "a", 0), Err("expected 0, got 1 (on a second run)".to_owned()));
+    }
+
+    #[test]
+    fn test_check_line_endings() {
+        assert_eq!(check_line_endings(&lines, "a\nb"), Ok(()));
+        let bytes = |input: &str| input.len();
+        let error = "Ok(\"3\") as is, but Ok(\"8\") with Windows line endings".to_owned();
+        assert_eq!(check_line_endings(&bytes, "a\nb"), Err(error));
+    }
+
+    const ONE: &str = "x";
+    const THREE: &str = "x\ny\nz";
+
+    crate::test_samples! {
+        lines, first_line;
+        (ONE, 1, "x"),
+        (THREE, 3, _),
+        (THREE, _, "x"),
+    }
+}
@@ -0,0 +1,246 @@
+//! Checks of an input's basic format, made before parsing it, so that a malformed input fails
+//! with where and why rather than a parser's bare "Failed to parse".
+//!
+//! A [`Format`] says which characters each line may contain, whether the lines must all be the
+//! same length (as in a grid), which characters must appear exactly once (like a map's start),
+//! and what range the numbers in them must lie in. Inputs made of
+//! sections separated by blank lines check each section against its own format, with
+//! [`check_sections`]. Problems are reported as [`ParseError`]s, with line and column numbers
+//! counted from the start of the whole input.
+
+use std::ops::RangeInclusive;
+use crate::parse::{ParseError, Result};
+
+/// What every line of an input (or of one section of it) should look like.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Format {
+    /// The characters lines may contain; any at all if `None`.
+    allowed: Option<&'static str>,
+    /// Whether every line must have as many characters as the first.
+    rectangular: bool,
+    /// Characters that must appear exactly once.
+    once: &'static str,
+    /// The range every number must lie in, if there is one. A number is a run of digits,
+    /// including a `-` just before it.
+    numbers: Option<RangeInclusive<i64>>
+}
+
+impl Format {
+    /// Lines of any characters.
+    pub const fn new() -> Format {
+        Format { allowed: None, rectangular: false, once: "", numbers: None }
+    }
+
+    /// A grid of the characters in `allowed`, with every row the same length.
+    pub const fn grid(allowed: &'static str) -> Format {
+        Format { allowed: Some(allowed), rectangular: true, once: "", numbers: None }
+    }
+
+    /// The same format, with lines made of only the characters in `allowed`.
+    pub const fn chars(self, allowed: &'static str) -> Format {
+        Format { allowed: Some(allowed), ..self }
+    }
+
+    /// The same format, with each of the characters in `once` appearing exactly once.
+    pub const fn once(self, once: &'static str) -> Format {
+        Format { once, ..self }
+    }
+
+    /// The same format, with every number in `range`.
+    pub const fn numbers(self, range: RangeInclusive<i64>) -> Format {
+        Format { numbers: Some(range), ..self }
+    }
+
+    /// Check every line of `input`, ignoring any trailing blank lines.
+    pub fn check(&self, input: &str) -> Result<()> {
+        self.check_from(input, 1)
+    }
+
+    /// Check every line of `input`, whose first line is line `first` of the whole input.
+    fn check_from(&self, input: &str, first: usize) -> Result<()> {
+        let mut width = None;
+        let mut seen = String::new();
+
+        for (i, text) in input.trim_end_matches(['\r', '\n']).lines().enumerate() {
+            let error = || ParseError::new(first + i, text);
+
+            for (j, c) in text.chars().enumerate().filter(|&(_, c)| self.once.contains(c)) {
+                if seen.contains(c) {
+                    return Err(error().at(j + 1, format!("a second {c:?}")));
+                }
+                seen.push(c);
+            }
+
+            if let Some(allowed) = self.allowed {
+                let stray = text.chars().enumerate().find(|&(_, c)| !allowed.contains(c));
+                if let Some((j, c)) = stray {
+                    return Err(error().at(j + 1, format!("unexpected {c:?}")));
+                }
+            }
+
+            if self.rectangular {
+                let len = text.chars().count();
+                let &mut expected = width.get_or_insert(len);
+                if len != expected {
+                    let reason = format!("expected {expected} characters, found {len}");
+                    return Err(error().at(len.min(expected) + 1, reason));
+                }
+            }
+
+            if let Some(range) = &self.numbers {
+                for (column, number) in numbers(text) {
+                    match number.parse::<i64>() {
+                        Ok(n) if range.contains(&n) => {},
+                        _ => {
+                            let reason = format!(
+                                "{number} is outside {}..={}", range.start(), range.end()
+                            );
+                            return Err(error().at(column, reason));
+                        }
+                    }
+                }
+            }
+        }
+
+        match self.once.chars().find(|&c| !seen.contains(c)) {
+            Some(c) => {
+                // Empty input has no last line, but line numbers start at 1.
+                let last = input.lines().count().max(1);
+                let text = input.lines().last().unwrap_or("");
+                let reason = format!("found no {c:?} by the end");
+                Err(ParseError::new(first + last - 1, text).because(reason))
+            },
+            None => Ok(())
+        }
+    }
+}
+
+/// Check each section of `input` (separated by blank lines) against the format for it in
+/// `formats`, and that there are as many sections as formats.
+pub fn check_sections(input: &str, formats: &[Format]) -> Result<()> {
+    let lines: Vec<&str> = input.lines().collect();
+    let mut start = 0;
+
+    for (n, format) in formats.iter().enumerate() {
+        if start >= lines.len() {
+            // Empty input has no last line, but line numbers start at 1.
+            let last = lines.len().max(1);
+            return Err(ParseError::new(last, lines.last().unwrap_or(&""))
+                .because(format!("expected {} sections, found {n}", formats.len())));
+        }
+
+        let len = lines[start..].iter()
+            .position(|line| line.is_empty())
+            .unwrap_or(lines.len() - start);
+        format.check_from(&lines[start..start + len].join("\n"), start + 1)?;
+
+        start += len;
+        while start < lines.len() && lines[start].is_empty() {
+            start += 1;
+        }
+    }
+
+    match lines.get(start) {
+        Some(text) => Err(ParseError::new(start + 1, text)
+            .because(format!("expected {} sections, found more", formats.len()))),
+        None => Ok(())
+    }
+}
+
+/// Each number in `text`, with the 1-based column where it starts.
+fn numbers(text: &str) -> impl Iterator<Item=(usize, &str)> + '_ {
+    let starts = text.char_indices()
+        .filter(move |&(i, c)| {
+            let before = text[..i].chars().next_back();
+            let is_digit = |c: Option<char>| c.is_some_and(|c| c.is_ascii_digit());
+            (c.is_ascii_digit() && !is_digit(before) && before != Some('-'))
+                || (c == '-' && is_digit(text[i + 1..].chars().next()))
+        });
+
+    starts.map(move |(i, _)| {
+        let len = text[i + 1..].find(|c: char| !c.is_ascii_digit())
+            .map_or(text.len() - i, |n| n + 1);
+        (text[..i].chars().count() + 1, &text[i..i + len])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::ParseError;
+    use super::{check_sections, numbers, Format};
+
+    #[test]
+    fn test_grid() {
+        let grid = Format::grid("#.SE");
+        assert_eq!(grid.check("#S.\n#.E"), Ok(()));
+        assert_eq!(
+            grid.check("#S.\n#.x"),
+            Err(ParseError::new(2, "#.x").at(3, "unexpected 'x'"))
+        );
+        assert_eq!(
+            grid.check("#S.\n#."),
+            Err(ParseError::new(2, "#.").at(3, "expected 3 characters, found 2"))
+        );
+        assert_eq!(
+            grid.check("#S.\n#.x").unwrap_err().to_string(),
+            "Failed to parse line 2, column 3: unexpected 'x' in \"#.x\""
+        );
+    }
+
+    #[test]
+    fn test_once() {
+        let map = Format::grid("#.SE").once("SE");
+        assert_eq!(map.check("#S.\n#.E"), Ok(()));
+        assert_eq!(
+            map.check("#S.\n#.."),
+            Err(ParseError::new(2, "#..").because("found no 'E' by the end"))
+        );
+        assert_eq!(
+            map.check("#S.\n#SE"),
+            Err(ParseError::new(2, "#SE").at(2, "a second 'S'"))
+        );
+        assert_eq!(
+            map.check(""),
+            Err(ParseError::new(1, "").because("found no 'S' by the end"))
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(
+            numbers("p=0,4 v=-3,12").collect::<Vec<_>>(),
+            vec![(3, "0"), (5, "4"), (9, "-3"), (12, "12")]
+        );
+
+        let digits = Format::new().numbers(0..=7);
+        assert_eq!(digits.check("Program: 2,4,1,7"), Ok(()));
+        assert_eq!(
+            digits.check("Program: 2,4,1,8"),
+            Err(ParseError::new(1, "Program: 2,4,1,8").at(16, "8 is outside 0..=7"))
+        );
+        assert!(digits.check("-1").is_err());
+        assert!(Format::new().numbers(0..=i64::MAX).check("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn test_check_sections() {
+        let formats = [Format::grid("#.O@"), Format::new().chars("<>^v")];
+        assert_eq!(check_sections("#@\n.O\n\n<^\nv>", &formats), Ok(()));
+        assert_eq!(
+            check_sections("#@\n.O\n\n<^\nvx", &formats),
+            Err(ParseError::new(5, "vx").at(2, "unexpected 'x'"))
+        );
+        assert_eq!(
+            check_sections("#@\n.O", &formats),
+            Err(ParseError::new(2, ".O").because("expected 2 sections, found 1"))
+        );
+        assert_eq!(
+            check_sections("#@\n\n<\n\n>", &formats),
+            Err(ParseError::new(5, ">").because("expected 2 sections, found more"))
+        );
+        assert_eq!(
+            check_sections("", &formats),
+            Err(ParseError::new(1, "").because("expected 2 sections, found 0"))
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Searching for the input that makes a computation produce a target output.
+//!
+//! Puzzles like day 17's ask which input makes a program print something in particular. Most
+//! candidates go wrong early, so [`Expect`] lets a run stop at the first wrong output, and
+//! [`first_match`] tries the candidates in turn (on all cores, with the `parallel` feature).
+
+use std::ops::ControlFlow;
+
+/// The target output of a run, checked one value at a time as the run produces it.
+///
+/// [`Expect::check`] fits output sinks that return a [`ControlFlow`], so the run can be stopped as
+/// soon as the output goes wrong.
+#[derive(Debug, Clone)]
+pub struct Expect<'a, T> {
+    remaining: std::slice::Iter<'a, T>
+}
+
+impl<'a, T: PartialEq> Expect<'a, T> {
+    pub fn new(target: &'a [T]) -> Expect<'a, T> {
+        Expect { remaining: target.iter() }
+    }
+
+    /// Check the next output: continue if it's the expected one, and break otherwise.
+    pub fn check(&mut self, output: &T) -> ControlFlow<()> {
+        match self.remaining.next() {
+            Some(expected) if expected == output => ControlFlow::Continue(()),
+            _ => ControlFlow::Break(())
+        }
+    }
+
+    /// Whether all of the target has been output.
+    pub fn is_complete(&self) -> bool {
+        self.remaining.len() == 0
+    }
+}
+
+/// The result of `run` for the first of `candidates`, in order, on which it succeeds.
+///
+/// `run` returns `None` for a candidate that doesn't match, and should give up on it as early as
+/// it can (e.g. with [`Expect`]). With the `parallel` feature, candidates are run on all cores;
+/// the result is still the one for the earliest matching candidate, so put them in order of
+/// preference (e.g. smallest first).
+#[cfg(feature = "parallel")]
+pub fn first_match<C, T, F>(candidates: Vec<C>, run: F) -> Option<T>
+where
+    C: Send,
+    T: Send,
+    F: Fn(C) -> Option<T> + Send + Sync
+{
+    use rayon::prelude::*;
+    candidates.into_par_iter().find_map_first(run)
+}
+
+/// The result of `run` for the first of `candidates`, in order, on which it succeeds.
+///
+/// `run` returns `None` for a candidate that doesn't match, and should give up on it as early as
+/// it can (e.g. with [`Expect`]). With the `parallel` feature, candidates are run on all cores;
+/// the result is still the one for the earliest matching candidate, so put them in order of
+/// preference (e.g. smallest first).
+#[cfg(not(feature = "parallel"))]
+pub fn first_match<C, T, F>(candidates: Vec<C>, run: F) -> Option<T>
+where
+    C: Send,
+    T: Send,
+    F: Fn(C) -> Option<T> + Send + Sync
+{
+    candidates.into_iter().find_map(run)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+    use super::{first_match, Expect};
+
+    /// The Collatz sequence from `n` down to 1, passed to `sink` until it breaks.
+    fn collatz(mut n: u64, mut sink: impl FnMut(&u64) -> ControlFlow<()>) -> ControlFlow<()> {
+        loop {
+            sink(&n)?;
+            if n == 1 {
+                return ControlFlow::Continue(());
+            }
+            n = if n.is_multiple_of(2) { n / 2 } else { 3 * n + 1 };
+        }
+    }
+
+    #[test]
+    fn test_expect() {
+        let target = [3, 10, 5, 16, 8, 4, 2, 1];
+
+        let mut expect = Expect::new(&target);
+        assert_eq!(collatz(3, |x| expect.check(x)), ControlFlow::Continue(()));
+        assert!(expect.is_complete());
+
+        let mut seen = 0;
+        let mut expect = Expect::new(&target);
+        let flow = collatz(6, |x| {
+            seen += 1;
+            expect.check(x)
+        });
+        assert_eq!((flow, seen), (ControlFlow::Break(()), 1));
+
+        // Output that stops short of the target doesn't complete it.
+        let mut expect = Expect::new(&[4, 2, 1, 4]);
+        assert_eq!(collatz(4, |x| expect.check(x)), ControlFlow::Continue(()));
+        assert!(!expect.is_complete());
+    }
+
+    #[test]
+    fn test_first_match() {
+        // Starting points whose sequences take exactly 111 steps to reach 1.
+        let steps = |n: u64| {
+            let mut count = 0;
+            let _ = collatz(n, |_| {
+                count += 1;
+                ControlFlow::Continue(())
+            });
+            count - 1
+        };
+
+        let candidates: Vec<u64> = (1..10_000).collect();
+        let found = first_match(candidates.clone(), |n| (steps(n) == 111).then_some(n));
+        assert_eq!(found, candidates.into_iter().find(|&n| steps(n) == 111));
+        assert_eq!(found, Some(27));
+
+        assert_eq!(first_match((1..100).collect(), |n: u64| (n > 1000).then_some(n)), None);
+    }
+}
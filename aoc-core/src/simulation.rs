@@ -0,0 +1,100 @@
+//! Puzzles that play something out one step at a time: a guard on patrol, robots wandering a
+//! grid, a robot pushing boxes, a program running.
+//!
+//! They share the [`Simulation`] trait, so that whatever drives them (stopping one early, capping
+//! how long it can run, showing it step by step) only needs writing once. Each such day has a
+//! public `simulation` function starting one from its input.
+
+/// How a [`Simulation`] stands after a step.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum StepResult {
+    /// There's more to do.
+    Running,
+    /// It's over; further steps change nothing.
+    Finished,
+    /// It can't carry on, for the given reason.
+    Failed(String)
+}
+
+/// Something that advances one step at a time.
+pub trait Simulation {
+    /// Advance by one step, if it isn't over.
+    fn step(&mut self) -> StepResult;
+
+    /// Step until `done` holds (checked before every step) or the simulation stops by itself.
+    ///
+    /// Returns the number of steps taken, and how the last one left the simulation: `Running` if
+    /// it was `done` that stopped it.
+    fn run_until<P>(&mut self, mut done: P) -> (usize, StepResult)
+    where
+        Self: Sized,
+        P: FnMut(&Self) -> bool
+    {
+        let mut steps = 0;
+        while !done(self) {
+            steps += 1;
+            match self.step() {
+                StepResult::Running => {},
+                stopped => return (steps, stopped)
+            }
+        }
+
+        (steps, StepResult::Running)
+    }
+
+    /// Step until the simulation stops by itself, but at most `limit` times; for simulations
+    /// that might never finish.
+    fn run_for(&mut self, limit: usize) -> (usize, StepResult)
+    where
+        Self: Sized
+    {
+        let mut checks = 0;
+        self.run_until(|_| {
+            checks += 1;
+            checks > limit
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Simulation, StepResult};
+
+    /// Counts down to zero, failing on 13.
+    struct Countdown(usize);
+
+    impl Simulation for Countdown {
+        fn step(&mut self) -> StepResult {
+            match self.0 {
+                0 => StepResult::Finished,
+                13 => StepResult::Failed("Unlucky".to_owned()),
+                n => {
+                    self.0 = n - 1;
+                    if self.0 == 0 { StepResult::Finished } else { StepResult::Running }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_until() {
+        let mut countdown = Countdown(10);
+        assert_eq!(countdown.run_until(|c| c.0 == 4), (6, StepResult::Running));
+        assert_eq!(countdown.run_until(|_| false), (4, StepResult::Finished));
+        assert_eq!(countdown.0, 0);
+        assert_eq!(countdown.run_until(|_| false), (1, StepResult::Finished));
+
+        let mut countdown = Countdown(15);
+        assert_eq!(countdown.run_until(|_| false), (3, StepResult::Failed("Unlucky".to_owned())));
+        assert_eq!(countdown.0, 13);
+    }
+
+    #[test]
+    fn test_run_for() {
+        let mut countdown = Countdown(10);
+        assert_eq!(countdown.run_for(3), (3, StepResult::Running));
+        assert_eq!(countdown.0, 7);
+        assert_eq!(countdown.run_for(100), (7, StepResult::Finished));
+        assert_eq!(countdown.run_for(0), (0, StepResult::Running));
+    }
+}
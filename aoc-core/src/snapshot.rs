@@ -0,0 +1,89 @@
+//! Golden-file snapshot tests for rendered output.
+//!
+//! [`assert_snapshot!`](crate::assert_snapshot) compares a rendered string against
+//! `snapshots/<name>.snap` in the calling crate. Run the tests with `UPDATE_SNAPSHOTS=1` to
+//! (re)write the files after an intentional change, then review the diff before committing.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Path of the snapshot file `name` under `manifest_dir`.
+pub fn path(manifest_dir: &str, name: &str) -> PathBuf {
+    Path::new(manifest_dir).join("snapshots").join(format!("{name}.snap"))
+}
+
+/// Compare `actual` against the snapshot at `path`, or overwrite it if `update` is set.
+pub fn compare(path: &Path, actual: &str, update: bool) -> Result<(), String> {
+    if update {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        return fs::write(path, actual).map_err(|e| e.to_string());
+    }
+
+    let expected = fs::read_to_string(path).map_err(|e| {
+        format!("Couldn't read snapshot {}: {e}; run with UPDATE_SNAPSHOTS=1 to create it", path.display())
+    })?;
+
+    if expected == actual {
+        return Ok(());
+    }
+
+    let mismatch = expected.lines().map(Some).chain(std::iter::repeat(None))
+        .zip(actual.lines().map(Some).chain(std::iter::repeat(None)))
+        .take_while(|pair| *pair != (None, None))
+        .enumerate()
+        .find(|(_, (e, a))| e != a);
+
+    let detail = match mismatch {
+        Some((i, (e, a))) => format!(
+            "first difference at line {}:\n  expected: {:?}\n    actual: {:?}",
+            i + 1, e.unwrap_or("<end of snapshot>"), a.unwrap_or("<end of output>")
+        ),
+        None => "contents differ only in trailing newlines".to_owned()
+    };
+
+    Err(format!("Snapshot {} doesn't match; {detail}\n\nActual output:\n{actual}", path.display()))
+}
+
+/// Compare `actual` against `snapshots/<name>.snap` under `manifest_dir`, honouring
+/// `UPDATE_SNAPSHOTS`.
+pub fn check(manifest_dir: &str, name: &str, actual: &str) -> Result<(), String> {
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some_and(|v| v != "0");
+    compare(&path(manifest_dir, name), actual, update)
+}
+
+/// Assert that a rendered value matches the snapshot `snapshots/<name>.snap` in the calling crate.
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $actual:expr) => {
+        if let Err(e) = $crate::snapshot::check(env!("CARGO_MANIFEST_DIR"), $name, &$actual) {
+            panic!("{}", e);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compare;
+
+    #[test]
+    fn test_compare() {
+        let dir = std::env::temp_dir().join(format!("aoc-snapshot-test-{}", std::process::id()));
+        let path = dir.join("render.snap");
+
+        assert!(compare(&path, "#.\n.#\n", false).unwrap_err().contains("UPDATE_SNAPSHOTS=1"));
+
+        compare(&path, "#.\n.#\n", true).unwrap();
+        assert_eq!(compare(&path, "#.\n.#\n", false), Ok(()));
+
+        let err = compare(&path, "#.\n##\n", false).unwrap_err();
+        assert!(err.contains("line 2"), "{err}");
+        assert!(err.contains("\".#\""), "{err}");
+
+        let err = compare(&path, "#.\n.#\n..\n", false).unwrap_err();
+        assert!(err.contains("<end of snapshot>"), "{err}");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+//! Alternative algorithms for the same part, selectable at run time with `--strategy NAME`.
+
+/// A named solver for one part.
+pub type Strategy<T> = (&'static str, fn(&str) -> T);
+
+/// A registry of named solvers for one part, the first of which is the default.
+pub struct Strategies<T: 'static>(pub &'static [Strategy<T>]);
+
+impl<T> Strategies<T> {
+    pub fn names(&self) -> impl Iterator<Item=&'static str> + '_ {
+        self.0.iter().map(|&(name, _)| name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<fn(&str) -> T> {
+        self.0.iter().find(|&&(n, _)| n == name).map(|&(_, solve)| solve)
+    }
+}
+
+/// A named solver for one part that also takes the day's `Args`.
+pub type ArgStrategy<T, A> = (&'static str, fn(&str, &A) -> T);
+
+/// Like [`Strategies`], for solvers that also take the day's `Args` of type `A`.
+pub struct ArgStrategies<T: 'static, A: 'static>(pub &'static [ArgStrategy<T, A>]);
+
+/// The solver named `strategy` from `registry`, or its first if `strategy` is `None`.
+fn choose<F: Copy>(registry: &[(&'static str, F)], strategy: Option<&str>) -> Result<F, String> {
+    match strategy {
+        None => Ok(registry[0].1),
+        Some(name) => registry.iter().find(|&&(n, _)| n == name).map(|&(_, f)| f).ok_or_else(|| {
+            let names: Vec<&str> = registry.iter().map(|&(n, _)| n).collect();
+            format!("Unknown strategy {name:?}; choose from: {}", names.join(", "))
+        })
+    }
+}
+
+/// Name of the solver that [`choose`] picks, if any.
+fn chosen_name<F>(registry: &[(&'static str, F)], strategy: Option<&str>) -> Option<&'static str> {
+    match strategy {
+        None => Some(registry[0].0),
+        Some(name) => registry.iter().map(|&(n, _)| n).find(|&n| n == name)
+    }
+}
+
+/// Marker for solvers that only take the input.
+pub struct InputOnly;
+
+/// Marker for solvers that also take the day's parsed `Args`.
+pub struct WithArgs;
+
+/// Anything [`build_main!`](crate::build_main) can run as a part or mode: a plain function of
+/// the input, a function of the input and the day's `Args` of type `A`, or a set of
+/// [`Strategies`].
+///
+/// The `M` parameter only exists to keep the implementations for different function shapes apart;
+/// it's always inferred.
+pub trait Solver<T, A, M> {
+    /// Solve `input` using the named strategy, or the default if `strategy` is `None`.
+    ///
+    /// Plain functions have only one strategy, so ignore the name.
+    fn solve(&self, input: &str, strategy: Option<&str>, args: &A) -> Result<T, String>;
+
+    /// Name of the strategy that [`Solver::solve`] will use, if there's a choice.
+    fn strategy_name(&self, _strategy: Option<&str>) -> Option<&'static str> {
+        None
+    }
+}
+
+impl<T, A, F: Fn(&str) -> T> Solver<T, A, InputOnly> for F {
+    fn solve(&self, input: &str, _strategy: Option<&str>, _args: &A) -> Result<T, String> {
+        Ok(self(input))
+    }
+}
+
+impl<T, A, F: Fn(&str, &A) -> T> Solver<T, A, WithArgs> for F {
+    fn solve(&self, input: &str, _strategy: Option<&str>, args: &A) -> Result<T, String> {
+        Ok(self(input, args))
+    }
+}
+
+impl<T, A> Solver<T, A, InputOnly> for Strategies<T> {
+    fn solve(&self, input: &str, strategy: Option<&str>, _args: &A) -> Result<T, String> {
+        Ok(choose(self.0, strategy)?(input))
+    }
+
+    fn strategy_name(&self, strategy: Option<&str>) -> Option<&'static str> {
+        chosen_name(self.0, strategy)
+    }
+}
+
+impl<T, A> Solver<T, A, WithArgs> for ArgStrategies<T, A> {
+    fn solve(&self, input: &str, strategy: Option<&str>, args: &A) -> Result<T, String> {
+        Ok(choose(self.0, strategy)?(input, args))
+    }
+
+    fn strategy_name(&self, strategy: Option<&str>) -> Option<&'static str> {
+        chosen_name(self.0, strategy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArgStrategies, Solver, Strategies};
+
+    fn len(input: &str) -> usize { input.len() }
+    fn lines(input: &str) -> usize { input.lines().count() }
+
+    const BOTH: Strategies<usize> = Strategies(&[("len", len), ("lines", lines)]);
+
+    #[test]
+    fn test_strategies() {
+        assert_eq!(BOTH.solve("a\nbc", None, &()), Ok(4));
+        assert_eq!(BOTH.solve("a\nbc", Some("lines"), &()), Ok(2));
+        assert_eq!(Solver::<_, (), _>::strategy_name(&BOTH, None), Some("len"));
+        assert_eq!(
+            BOTH.solve("a\nbc", Some("bytes"), &()),
+            Err("Unknown strategy \"bytes\"; choose from: len, lines".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_strategies_with_args() {
+        let nth: ArgStrategies<usize, usize> = ArgStrategies(&[
+            ("line", |input, n| input.lines().nth(*n).unwrap().len()),
+            ("char", |input, n| input.chars().nth(*n).unwrap() as usize)
+        ]);
+        assert_eq!(nth.solve("a\nbc", None, &1), Ok(2));
+        assert_eq!(nth.solve("a\nbc", Some("char"), &0), Ok(97));
+        assert_eq!(nth.strategy_name(Some("char")), Some("char"));
+        assert!(nth.solve("a\nbc", Some("byte"), &0).is_err());
+    }
+
+    #[test]
+    fn test_plain_function() {
+        assert_eq!(lines.solve("a\nbc", Some("anything"), &()), Ok(2));
+        assert_eq!(Solver::<_, (), _>::strategy_name(&lines, None), None);
+    }
+
+    #[test]
+    fn test_function_with_args() {
+        let nth_line = |input: &str, n: &usize| input.lines().nth(*n).unwrap().len();
+        assert_eq!(nth_line.solve("a\nbc", None, &1), Ok(2));
+    }
+}
@@ -0,0 +1,151 @@
+//! Matching many patterns against a text at once, for puzzles about building strings out of
+//! pieces (like 2024's day 19 towels).
+//!
+//! Matching is byte by byte, and positions and lengths are in bytes, so a text needn't split into
+//! patterns on `char` boundaries (and slicing it never panics on one that isn't ASCII).
+
+use crate::dp::count_compositions;
+
+/// A set of patterns, stored as a trie so that every pattern matching at a given position is
+/// found in one walk.
+#[derive(Debug, Clone)]
+pub struct MultiPattern {
+    nodes: Vec<Node>
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// Child nodes by their next byte; tries for puzzle alphabets are small, so a list is enough.
+    children: Vec<(u8, usize)>,
+    /// Whether some pattern ends here.
+    terminal: bool
+}
+
+impl MultiPattern {
+    /// The set of `patterns`. Empty patterns are ignored, since they'd give any text infinitely
+    /// many decompositions.
+    pub fn new<I, S>(patterns: I) -> MultiPattern
+    where
+        I: IntoIterator<Item=S>,
+        S: AsRef<str>
+    {
+        let mut trie = MultiPattern { nodes: vec![Node::default()] };
+
+        for pattern in patterns {
+            let pattern = pattern.as_ref().as_bytes();
+            if pattern.is_empty() {
+                continue;
+            }
+
+            let mut node = 0;
+            for &b in pattern {
+                node = match trie.child(node, b) {
+                    Some(child) => child,
+                    None => {
+                        trie.nodes.push(Node::default());
+                        let child = trie.nodes.len() - 1;
+                        trie.nodes[node].children.push((b, child));
+                        child
+                    }
+                };
+            }
+            trie.nodes[node].terminal = true;
+        }
+
+        trie
+    }
+
+    fn child(&self, node: usize, b: u8) -> Option<usize> {
+        self.nodes[node].children.iter().find(|&&(c, _)| c == b).map(|&(_, child)| child)
+    }
+
+    /// The lengths of the patterns that `text` starts with, shortest first.
+    pub fn prefix_lengths<'a>(&'a self, text: &'a [u8]) -> impl Iterator<Item=usize> + 'a {
+        text.iter()
+            .scan(0, move |node, &b| {
+                *node = self.child(*node, b)?;
+                Some(self.nodes[*node].terminal)
+            })
+            .enumerate()
+            .filter(|&(_, terminal)| terminal)
+            .map(|(i, _)| i + 1)
+    }
+
+    /// Number of ways to write `text` as a sequence of the patterns (each usable any number of
+    /// times); an empty text has one, the empty sequence.
+    pub fn count_decompositions(&self, text: &str) -> usize {
+        let text = text.as_bytes();
+        count_compositions(text.len(), |n| self.prefix_lengths(&text[n..]))
+    }
+
+    /// Whether `text` can be written as a sequence of the patterns at all.
+    pub fn can_decompose(&self, text: &str) -> bool {
+        let text = text.as_bytes();
+        let mut possible = vec![false; text.len() + 1];
+        possible[text.len()] = true;
+
+        for n in (0..text.len()).rev() {
+            possible[n] = self.prefix_lengths(&text[n..]).any(|len| possible[n + len]);
+        }
+
+        possible[0]
+    }
+
+    /// Every occurrence of a pattern in `text`, as `(start, length)`, ordered by start and then
+    /// length; occurrences may overlap.
+    pub fn find_all(&self, text: &str) -> Vec<(usize, usize)> {
+        let text = text.as_bytes();
+        (0..text.len())
+            .flat_map(|start| self.prefix_lengths(&text[start..]).map(move |len| (start, len)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiPattern;
+
+    const TOWELS: [&str; 8] = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
+
+    #[test]
+    fn test_count_decompositions() {
+        let towels = MultiPattern::new(TOWELS);
+        let designs = ["brwrr", "bggr", "gbbr", "rrbgbr", "ubwu", "bwurrg", "brgr", "bbrgwb"];
+        let ways: Vec<usize> = designs.iter().map(|d| towels.count_decompositions(d)).collect();
+        assert_eq!(ways, vec![2, 1, 4, 6, 0, 1, 2, 0]);
+        assert_eq!(towels.count_decompositions(""), 1);
+    }
+
+    #[test]
+    fn test_can_decompose() {
+        let towels = MultiPattern::new(TOWELS);
+        assert!(towels.can_decompose("brwrr"));
+        assert!(!towels.can_decompose("ubwu"));
+        assert!(!towels.can_decompose("bbrgwb"));
+        assert!(towels.can_decompose(""));
+
+        let empty = MultiPattern::new(["", "a"]);
+        assert_eq!(empty.count_decompositions("aa"), 1);
+        assert!(!MultiPattern::new(Vec::<String>::new()).can_decompose("a"));
+    }
+
+    #[test]
+    fn test_find_all() {
+        let patterns = MultiPattern::new(["a", "ab", "bab", "c"]);
+        assert_eq!(patterns.prefix_lengths(b"abab").collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(
+            patterns.find_all("abab"),
+            vec![(0, 1), (0, 2), (1, 3), (2, 1), (2, 2)]
+        );
+        assert_eq!(patterns.find_all("xyz"), vec![]);
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        // "é" and "ü" are two bytes each, so not every byte offset is a char boundary.
+        let patterns = MultiPattern::new(["é", "a", "ü"]);
+        assert_eq!(patterns.find_all("aéü"), vec![(0, 1), (1, 2), (3, 2)]);
+        assert_eq!(patterns.count_decompositions("éaü"), 1);
+        assert!(!patterns.can_decompose("èa"));
+    }
+}
@@ -0,0 +1,161 @@
+//! Timings for the days with alternative strategies, or that are worth watching scale, on inputs
+//! from [`adventofcode2024::gen`]. Every strategy for a part is checked against the first on
+//! each input before it's timed, and likewise each grid layout's kernel for days 6, 16 and 20.
+//!
+//! The run checking each strategy also counts the bytes it allocates (through [`Counting`]),
+//! which are printed alongside criterion's timings.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Debug;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use adventofcode2024::days::{day01, day04, day06, day09, day12, day16, day20, day22};
+use adventofcode2024::gen::{
+    day01_lists, day04_letters, day06_board, day09_disk_map, day12_garden, day16_maze,
+    day20_track, day22_seeds, Rng
+};
+use adventofcode2024::grid::LayoutKernels;
+use adventofcode2024::strategy::Strategies;
+
+/// The system allocator, keeping a running total of the bytes allocated through it.
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// The result of `f`, and the bytes it allocated (including any it freed again).
+fn allocated<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let result = f();
+    (result, ALLOCATED.load(Ordering::Relaxed) - before)
+}
+
+/// Time each of `strategies` on each of the labelled `inputs`, after checking they all agree and
+/// printing how many bytes each allocates.
+fn strategies<T: PartialEq + Debug>(
+    c: &mut Criterion, name: &str, strategies: &Strategies<T>, inputs: &[(usize, String)]
+) {
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+
+    for (size, input) in inputs {
+        let (first, solve) = strategies.0[0];
+        let expected = solve(input);
+        for &(strategy, solve) in strategies.0 {
+            let (answer, bytes) = allocated(|| solve(input));
+            assert_eq!(answer, expected, "{name}: {strategy} disagrees with {first} at {size}");
+            println!("{name}/{strategy}/{size}: {bytes} bytes allocated");
+            group.bench_with_input(BenchmarkId::new(strategy, size), input, |b, input| {
+                b.iter(|| solve(black_box(input)))
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Time `solve` on each of the labelled `inputs`.
+fn part<T>(c: &mut Criterion, name: &str, solve: fn(&str) -> T, inputs: &[(usize, String)]) {
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+
+    for (size, input) in inputs {
+        group.bench_with_input(BenchmarkId::from_parameter(size), input, |b, input| {
+            b.iter(|| solve(black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+/// Time the kernel that `prepare` lays out in each grid layout, on each of the labelled `inputs`,
+/// after checking they all agree.
+fn layouts(
+    c: &mut Criterion,
+    name: &str,
+    prepare: impl Fn(&str) -> LayoutKernels,
+    inputs: &[(usize, String)]
+) {
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+
+    for (size, input) in inputs {
+        let kernels = prepare(input);
+        let (first, expected) = (kernels[0].0, kernels[0].1());
+        for (layout, kernel) in &kernels {
+            assert_eq!(kernel(), expected, "{name}: {layout} disagrees with {first} at {size}");
+            let id = BenchmarkId::new(*layout, size);
+            group.bench_function(id, |b| b.iter(|| black_box(kernel)()));
+        }
+    }
+    group.finish();
+}
+
+/// Generated inputs of each size, seeded by the size.
+fn inputs(sizes: &[usize], generate: impl Fn(&mut Rng, usize) -> String) -> Vec<(usize, String)> {
+    sizes.iter().map(|&size| (size, generate(&mut Rng::new(size as u64), size))).collect()
+}
+
+fn day01(c: &mut Criterion) {
+    let lists = inputs(&[1_000, 10_000, 100_000, 1_000_000], day01_lists);
+    strategies(c, "day01/part2", &day01::PART2, &lists);
+}
+
+fn day04(c: &mut Criterion) {
+    let letters = |rng: &mut Rng, size| day04_letters(rng, size, size);
+    // The rotations strategy keeps four rotated copies of the grid, too many at 10000×10000.
+    strategies(c, "day04/part1", &day04::PART1, &inputs(&[140, 1000, 2000], letters));
+    part(c, "day04/part2", day04::part2, &inputs(&[140, 1000, 2000, 10000], letters));
+}
+
+fn day06(c: &mut Criterion) {
+    let boards = inputs(&[50, 100, 200, 500], |rng, size| day06_board(rng, size, size, 5));
+    part(c, "day06/part1", day06::part1, &boards);
+    strategies(c, "day06/part2", &day06::PART2, &boards);
+    layouts(c, "day06/layouts", day06::layouts, &boards);
+}
+
+fn day09(c: &mut Criterion) {
+    let disk_maps = inputs(&[1_000, 5_000, 20_000], |rng, files| day09_disk_map(rng, files, 50));
+    part(c, "day09/part1", day09::part1, &disk_maps);
+    part(c, "day09/part2", day09::part2, &disk_maps);
+}
+
+fn day12(c: &mut Criterion) {
+    let gardens = inputs(&[500, 1000, 2000], |rng, size| day12_garden(rng, size, size, 20));
+    part(c, "day12/part1", day12::part1, &gardens);
+    part(c, "day12/part2", day12::part2, &gardens);
+    part(c, "day12/fences", day12::fences, &gardens);
+}
+
+fn day16(c: &mut Criterion) {
+    let mazes = inputs(&[51, 101, 201, 501], |rng, size| day16_maze(rng, size, 10));
+    strategies(c, "day16/part1", &day16::PART1, &mazes);
+    strategies(c, "day16/part2", &day16::PART2, &mazes);
+    layouts(c, "day16/layouts", day16::layouts, &mazes);
+}
+
+fn day20(c: &mut Criterion) {
+    let tracks = inputs(&[51, 141, 501], day20_track);
+    layouts(c, "day20/layouts", |input| day20::layouts(input, 100).unwrap(), &tracks);
+}
+
+fn day22(c: &mut Criterion) {
+    let seeds = inputs(&[500, 2200, 10_000], day22_seeds);
+    strategies(c, "day22/part2", &day22::PART2, &seeds);
+}
+
+criterion_group!(days, day01, day04, day06, day09, day12, day16, day20, day22);
+criterion_main!(days);
@@ -0,0 +1,304 @@
+//! Tools that work across every day compiled into this build.
+//!
+//! - `aoc describe [DIR]` writes a Markdown writeup of each day, generated from its doc comments,
+//!   to `DIR/dayNN.md` (by default under `docs/`).
+//! - `aoc run [--year YEAR] [--day DAY]... [DAY...]` runs both parts of the given days of `YEAR`
+//!   (by default, all days of the latest year) against their inputs in `inputs/YEAR/dayNN.txt`.
+//! - `aoc verify [--answers FILE] [--year YEAR] [--day DAY]... [DAY...]` does the same, and checks
+//!   the answers against `FILE` (by default `answers.txt`); see [`parse_answers`] for its format.
+//!   A part with no answer in `FILE` fails, rather than passing unchecked.
+//! - `aoc trends [--year YEAR] [--day DAY]... [DAY...]` prints the timing history of the given
+//!   days' parts, from the `runs.jsonl` log that `run` and `verify` append to.
+//!
+//! There's no input fetcher: inputs are downloaded by hand into `inputs/YEAR/`.
+//!
+//! `run` and `verify` pass each day the settings in its section of `config.toml` (e.g.
+//! `[2024.day18]`), if there is one; see `aoc_core::config`.
+//!
+//! `-v` or `-vv`, anywhere on the command line, shows the solvers' log messages on stderr.
+//!
+//! `--repeat-check`, with `run` or `verify`, runs every part a second time and fails it if the two
+//! results differ. Each `HashMap` and `HashSet` gets freshly seeded hashing, so this catches
+//! answers that depend on their iteration order. The second run parses afresh rather than reading
+//! the parse cache, so the parsers are checked too. The exception is the `aoc_core::hash`
+//! containers in release builds with the `fxhash` feature, which hash the same way every time.
+//!
+//! `run` and `verify` also take the flags that the days' binaries do, parsed by `aoc_core::cli`:
+//!
+//! - `--input PATH` replaces the input file, for a single day.
+//! - `--json` prints each part's result as a line of JSON.
+//! - `--strategy NAME` runs that strategy of each part. Parts without a choice of strategies
+//!   ignore it, but those with a choice that doesn't include it fail.
+//! - `--timeout SECS` runs each part on a worker thread and fails it if it hasn't finished after
+//!   `SECS` seconds, so that one stuck in a loop doesn't hang the whole run. The stuck thread
+//!   carries on in the background until the run is over.
+//! - `--repeat N` runs each part `N` times, and reports (and logs) the fastest.
+//! - `--dump DIR` has the solvers write their intermediate artifacts under `DIR`; see
+//!   `aoc_core::dump`.
+//!
+//! `run` and `verify` exit with status 1 if any part fails (it returns an error or panics, or its
+//! answer doesn't match, or `verify` has nothing to match it against), after printing a JSON
+//! report of the failures to stderr. Usage errors and unreadable files exit with status 2.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+use adventofcode2024::cache;
+use adventofcode2024::cli::Flags;
+use adventofcode2024::config::{Config, Section};
+use adventofcode2024::days::{self, Day, Year};
+use adventofcode2024::describe::markdown;
+use adventofcode2024::dump;
+use adventofcode2024::history::{self, Run};
+use adventofcode2024::logging::{self, is_verbosity_flag};
+use adventofcode2024::runner::{
+    check, check_repeat, check_verified, input_path, json_report, parse_answers, run_part,
+    run_part_with_timeout, Outcome
+};
+
+const USAGE: &str = "Usage: aoc describe [DIR]
+       aoc run [--year YEAR] [--day DAY]... [DAY...]
+       aoc verify [--answers FILE] [--year YEAR] [--day DAY]... [DAY...]
+       aoc trends [--year YEAR] [--day DAY]... [DAY...]
+Add -v or -vv to show the solvers' log messages. With run and verify, --repeat-check runs every part
+twice to check it gives the same result; they also take the days' --input PATH, --json,
+--strategy NAME, --timeout SECS, --repeat N and --dump DIR.";
+
+/// How `run` and `verify` run each part.
+#[derive(Debug, Clone, Default)]
+struct Options {
+    /// Run each part twice, and fail it if the results differ.
+    repeat_check: bool,
+    /// The flags shared with the days' binaries.
+    flags: Flags
+}
+
+impl Options {
+    /// The options in `args`, and the rest of the arguments.
+    fn parse(args: &[&str]) -> Result<(Options, Vec<String>), String> {
+        let (flags, mut rest) = Flags::parse(args)?;
+        let repeat_check = rest.iter().any(|arg| arg == "--repeat-check");
+        rest.retain(|arg| arg != "--repeat-check");
+
+        Ok((Options { repeat_check, flags }, rest))
+    }
+
+    /// Run part `part` of `day` on `input` as many times as asked, within the timeout if there is
+    /// one, stopping at the first failure.
+    fn run_part(&self, day: &Day, part: usize, input: &str, config: &Section) -> Outcome {
+        let strategy = self.flags.strategy.as_deref();
+        let run_once = || match self.flags.timeout {
+            Some(timeout) => run_part_with_timeout(day, part, input, strategy, config, timeout),
+            None => run_part(day, part, input, strategy, config)
+        };
+
+        let mut outcome = run_once();
+        for _ in 1..self.flags.repeat {
+            if outcome.result.is_err() {
+                break;
+            }
+            let next = run_once();
+            outcome = Outcome { micros: outcome.micros.min(next.micros), ..next };
+        }
+
+        outcome
+    }
+}
+
+/// Every year compiled into this build, in order.
+const YEARS: &[Year] = &[days::YEAR];
+
+fn describe(dir: &Path) -> Result<bool, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Couldn't create {}: {e}", dir.display()))?;
+
+    for day in days::ALL {
+        let path = dir.join(format!("day{:02}.md", day.number));
+        std::fs::write(&path, markdown(day))
+            .map_err(|e| format!("Couldn't write {}: {e}", path.display()))?;
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(true)
+}
+
+/// The year and days named by `args` (`--year YEAR`, then days given either as `--day DAY` or
+/// bare numbers): by default the latest year, and every day of it.
+fn select<S: AsRef<str>>(args: &[S]) -> Result<(u32, Vec<&'static Day>), String> {
+    let mut year = YEARS.last().ok_or("No years in this build")?;
+    let mut numbers = Vec::new();
+
+    let mut args = args.iter().map(AsRef::as_ref);
+    while let Some(arg) = args.next() {
+        match arg {
+            "--year" => {
+                let y = args.next().ok_or_else(|| USAGE.to_owned())?;
+                year = y.parse().ok()
+                    .and_then(|y: u32| YEARS.iter().find(|year| year.year == y))
+                    .ok_or_else(|| format!("No year {y:?} in this build"))?;
+            },
+            "--day" => numbers.push(args.next().ok_or_else(|| USAGE.to_owned())?),
+            n if n.starts_with('-') => return Err(USAGE.to_owned()),
+            n => numbers.push(n)
+        }
+    }
+
+    if numbers.is_empty() {
+        return Ok((year.year, year.days.iter().collect()));
+    }
+
+    let days = numbers.iter()
+        .map(|n| {
+            n.parse().ok()
+                .and_then(|n| year.get(n))
+                .ok_or_else(|| format!("No day {n:?} of {} in this build", year.year))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok((year.year, days))
+}
+
+/// The log of past runs' answers and timings.
+fn history_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("runs.jsonl")
+}
+
+/// The abbreviated hash of the checked-out commit, if git can tell us.
+fn current_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Run both parts of each of `days` of `year` as set by `options`, checking them against `answers`
+/// (if verifying, in which case every part needs one) and logging them to the run history; returns
+/// whether they all succeeded.
+fn run(
+    (year, days): (u32, Vec<&Day>),
+    answers: Option<&HashMap<(u32, u32, usize), String>>,
+    options: Options
+) -> Result<bool, String> {
+    // Panics are reported as failures, so don't also print them as they happen.
+    std::panic::set_hook(Box::new(|_| {}));
+    let flags = &options.flags;
+    if let Some(dir) = &flags.dump {
+        dump::init_with(dir);
+    }
+    if flags.input.is_some() && days.len() != 1 {
+        return Err("--input needs exactly one day".to_owned());
+    }
+
+    let time = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |t| t.as_secs());
+    let commit = current_commit();
+    let config = Config::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("config.toml"))?;
+
+    let mut failures = Vec::new();
+    let mut runs = Vec::new();
+    for day in days {
+        let input = match flags.read_input()? {
+            Some(input) => input,
+            None => {
+                let path = input_path(Path::new(env!("CARGO_MANIFEST_DIR")), year, day.number);
+                std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Couldn't read input {}: {e}", path.display()))?
+            }
+        };
+
+        let section = config.section(year, day.number);
+        for part in 1..=2 {
+            let outcome = options.run_part(day, part, &input, section);
+            let expected = answers
+                .and_then(|answers| answers.get(&(year, day.number, part)))
+                .map(String::as_str);
+            let status = match (&outcome.result, expected) {
+                (Err(e), _) => format!("FAILED: {e}"),
+                (Ok(answer), Some(expected)) if answer.to_string() != expected => {
+                    format!("{answer} (expected {expected})")
+                },
+                (Ok(answer), Some(_)) => format!("{answer} (correct)"),
+                (Ok(answer), None) if answers.is_some() => format!("{answer} (no expected answer)"),
+                (Ok(answer), None) => answer.to_string()
+            };
+            let label = format!("Day {:02} part {part}", day.number);
+            if flags.json {
+                println!("{}", flags.report(&label, &outcome.result, outcome.micros));
+            }
+            else {
+                println!("{label}: {status} (Time: {}μs)", outcome.micros);
+            }
+
+            let check = if answers.is_some() { check_verified } else { check };
+            failures.extend(check(&outcome, expected));
+            if options.repeat_check {
+                let again = cache::uncached(|| options.run_part(day, part, &input, section));
+                let repeat = check_repeat(&outcome, &again);
+                if let Some(failure) = repeat {
+                    if !flags.json {
+                        println!("{label}: FAILED: changed on a second run");
+                    }
+                    failures.push(failure);
+                }
+            }
+            runs.push(Run::new(&outcome, year, time, commit.as_deref()));
+        }
+    }
+
+    history::append(&history_path(), &runs)?;
+
+    if !failures.is_empty() {
+        eprintln!("{}", json_report(&failures));
+    }
+
+    Ok(failures.is_empty())
+}
+
+fn verify(answers_path: &Path, args: &[String], options: Options) -> Result<bool, String> {
+    let text = std::fs::read_to_string(answers_path)
+        .map_err(|e| format!("Couldn't read answers {}: {e}", answers_path.display()))?;
+    let answers = parse_answers(&text)
+        .map_err(|e| format!("{}: {e}", answers_path.display()))?;
+
+    run(select(args)?, Some(&answers), options)
+}
+
+fn trends((year, days): (u32, Vec<&Day>)) -> Result<bool, String> {
+    let numbers: Vec<u32> = days.iter().map(|day| day.number).collect();
+    print!("{}", history::trends(&history::load(&history_path())?, year, &numbers));
+    Ok(true)
+}
+
+fn main() {
+    logging::init();
+    let args: Vec<String> = std::env::args().skip(1)
+        .filter(|arg| !is_verbosity_flag(arg))
+        .collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let result = match args[..] {
+        ["describe"] => describe(Path::new("docs")),
+        ["describe", dir] => describe(Path::new(dir)),
+        ["run", ref args @ ..] => Options::parse(args).and_then(|(options, args)| {
+            run(select(&args)?, None, options)
+        }),
+        ["verify", "--answers", path, ref args @ ..] => Options::parse(args)
+            .and_then(|(options, args)| verify(Path::new(path), &args, options)),
+        ["verify", ref args @ ..] => Options::parse(args)
+            .and_then(|(options, args)| verify(Path::new("answers.txt"), &args, options)),
+        ["trends", ref args @ ..] => select(args).and_then(trends),
+        _ => Err(USAGE.to_owned())
+    };
+
+    match result {
+        Ok(true) => {},
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    }
+}
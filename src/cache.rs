@@ -0,0 +1,189 @@
+//! Opt-in on-disk cache of parsed inputs, so repeated runs of heavy-parse days can skip parsing.
+//!
+//! With the `parse-cache` feature enabled, [`parse_cached`] stores the bincode-serialized result
+//! of parsing under `target/parse-cache` (or `$AOC_PARSE_CACHE_DIR`), keyed by a hash of the input,
+//! the parsed type and the crate version. Without it, [`parse_cached`] just parses.
+//!
+//! The key doesn't cover the parser's code: after changing how a day parses into the same type,
+//! clear the cache directory. Parsing inside [`uncached`] skips reading the cache, as
+//! `aoc run --repeat-check` does for its second run.
+
+#[cfg(feature = "parse-cache")]
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether parses are skipping the cache; see [`uncached`].
+static UNCACHED: AtomicBool = AtomicBool::new(false);
+
+/// Run `f`, with [`parse_cached`] and [`try_parse_cached`] parsing afresh rather than reading
+/// previous results from the cache. (They still store what they parse.)
+pub fn uncached<T>(f: impl FnOnce() -> T) -> T {
+    let before = UNCACHED.swap(true, Ordering::SeqCst);
+    let result = f();
+    UNCACHED.store(before, Ordering::SeqCst);
+    result
+}
+
+/// Types that [`parse_cached`] can store: anything serializable when the cache is enabled, and
+/// anything at all when it isn't.
+#[cfg(feature = "parse-cache")]
+pub trait Cacheable: serde::Serialize + serde::de::DeserializeOwned {}
+
+#[cfg(feature = "parse-cache")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> Cacheable for T {}
+
+/// Types that [`parse_cached`] can store: anything serializable when the cache is enabled, and
+/// anything at all when it isn't.
+#[cfg(not(feature = "parse-cache"))]
+pub trait Cacheable {}
+
+#[cfg(not(feature = "parse-cache"))]
+impl<T> Cacheable for T {}
+
+/// 64-bit FNV-1a hash; unlike `DefaultHasher`, it's stable across runs and Rust versions.
+pub fn fingerprint(parts: &[&[u8]]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        // Hash the length too, so that ("ab", "c") and ("a", "bc") differ.
+        for &byte in part.len().to_le_bytes().iter().chain(part.iter()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    hash
+}
+
+/// Where cached parses are stored.
+pub fn cache_dir() -> PathBuf {
+    match std::env::var_os("AOC_PARSE_CACHE_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target").join("parse-cache")
+    }
+}
+
+/// Cache file for parsing `input` into a `T`.
+pub fn cache_path<T>(input: &str) -> PathBuf {
+    let key = fingerprint(&[
+        env!("CARGO_PKG_VERSION").as_bytes(),
+        std::any::type_name::<T>().as_bytes(),
+        input.as_bytes()
+    ]);
+
+    cache_dir().join(format!("{key:016x}.bin"))
+}
+
+/// Parse `input` with `parse`, reusing a previous result from the on-disk cache if there is one.
+///
+/// The cache is best-effort: if it can't be read or written, this just parses.
+#[cfg(feature = "parse-cache")]
+pub fn parse_cached<T: Cacheable, F: FnOnce(&str) -> T>(input: &str, parse: F) -> T {
+    let parsed = try_parse_cached(input, |input| Ok::<T, Infallible>(parse(input)));
+    match parsed {
+        Ok(parsed) => parsed,
+        Err(never) => match never {}
+    }
+}
+
+/// Parse `input` with `parse`, which may fail, reusing a previous result from the on-disk cache
+/// if there is one. Only successful parses are cached.
+#[cfg(feature = "parse-cache")]
+pub fn try_parse_cached<T, E, F>(input: &str, parse: F) -> Result<T, E>
+where
+    T: Cacheable,
+    F: FnOnce(&str) -> Result<T, E>
+{
+    let path = cache_path::<T>(input);
+
+    let cached = std::fs::read(&path).ok()
+        .filter(|_| !UNCACHED.load(Ordering::SeqCst))
+        .and_then(|bytes| bincode::deserialize(&bytes).ok());
+    if let Some(parsed) = cached {
+        return Ok(parsed);
+    }
+
+    let parsed = parse(input)?;
+    if let Ok(bytes) = bincode::serialize(&parsed) {
+        let _ = std::fs::create_dir_all(cache_dir()).and_then(|_| std::fs::write(&path, bytes));
+    }
+
+    Ok(parsed)
+}
+
+/// Parse `input` with `parse`; the `parse-cache` feature is disabled, so there's no caching.
+#[cfg(not(feature = "parse-cache"))]
+pub fn parse_cached<T: Cacheable, F: FnOnce(&str) -> T>(input: &str, parse: F) -> T {
+    parse(input)
+}
+
+/// Parse `input` with `parse`, which may fail; the `parse-cache` feature is disabled, so there's
+/// no caching.
+#[cfg(not(feature = "parse-cache"))]
+pub fn try_parse_cached<T, E, F>(input: &str, parse: F) -> Result<T, E>
+where
+    T: Cacheable,
+    F: FnOnce(&str) -> Result<T, E>
+{
+    parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use super::{cache_path, fingerprint, parse_cached, try_parse_cached, uncached};
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(fingerprint(&[]), 0xcbf29ce484222325);
+        assert_eq!(fingerprint(&[b"abc"]), fingerprint(&[b"abc"]));
+        assert_ne!(fingerprint(&[b"ab", b"c"]), fingerprint(&[b"a", b"bc"]));
+    }
+
+    #[test]
+    fn test_cache_path() {
+        assert_eq!(cache_path::<Vec<usize>>("1\n2"), cache_path::<Vec<usize>>("1\n2"));
+        assert_ne!(cache_path::<Vec<usize>>("1\n2"), cache_path::<Vec<usize>>("1\n3"));
+        assert_ne!(cache_path::<Vec<usize>>("1\n2"), cache_path::<Vec<u32>>("1\n2"));
+    }
+
+    #[test]
+    fn test_parse_cached() {
+        let parse = |input: &str| -> Vec<usize> {
+            input.lines().map(|line| line.parse().unwrap()).collect()
+        };
+        let input = "3\n1\n4\n1\n5";
+
+        assert_eq!(parse_cached(input, parse), vec![3, 1, 4, 1, 5]);
+        // A second parse gives the same result, whether or not it came from the cache.
+        assert_eq!(parse_cached(input, parse), vec![3, 1, 4, 1, 5]);
+    }
+
+    #[test]
+    fn test_uncached() {
+        let parses = Cell::new(0);
+        let parse = |input: &str| -> Vec<u16> {
+            parses.set(parses.get() + 1);
+            input.lines().map(|line| line.parse().unwrap()).collect()
+        };
+        let input = "27\n18\n28";
+
+        assert_eq!(parse_cached(input, parse), vec![27, 18, 28]);
+        assert_eq!(uncached(|| parse_cached(input, parse)), vec![27, 18, 28]);
+        assert_eq!(parses.get(), 2);
+    }
+
+    #[test]
+    fn test_try_parse_cached() {
+        let parse = |input: &str| -> Result<Vec<u8>, String> {
+            input.lines().map(|line| line.parse().map_err(|_| format!("Bad line {line:?}"))).collect()
+        };
+
+        // A failed parse isn't cached, so it fails again rather than reading back something else.
+        let input = "2\n7\nx";
+        assert_eq!(try_parse_cached(input, parse), Err("Bad line \"x\"".to_owned()));
+        assert_eq!(try_parse_cached(input, parse), Err("Bad line \"x\"".to_owned()));
+        assert_eq!(try_parse_cached("2\n7", parse), Ok(vec![2, 7]));
+        assert_eq!(try_parse_cached("2\n7", parse), Ok(vec![2, 7]));
+    }
+}
@@ -0,0 +1,92 @@
+//! Day 1: Historian Hysteria
+//!
+//! Two columns of location IDs, parsed line by line into a pair of lists. The comparisons are the
+//! general ones from `columns`, which work with any number of lists.
+//!
+//! # Approach
+//!
+//! Both parts start by sorting the two lists. Part 1 then just pairs them up in order. For part 2,
+//! each list is condensed into runs of equal IDs, and the two run lists are merged like in merge
+//! sort, so every ID is compared at most once.
+//!
+//! The "counts" strategy for part 2 skips the sorting: it counts the right list's IDs in a
+//! `HashMap`, then looks up each left ID. `cargo bench --bench days` compares the two on generated
+//! lists of up to a million lines.
+//!
+//! # Complexity
+//!
+//! `O(n log n)` for the sorts; everything after them is linear. Counting is expected linear.
+
+use std::collections::HashMap;
+use crate::columns::{parse_columns, similarity, sorted_distance};
+use crate::strategy::Strategies;
+
+fn parse_input(input: &str) -> Vec<Vec<usize>> {
+    let columns = parse_columns(input).unwrap();
+    assert_eq!(columns.len(), 2, "Expected two lists");
+    columns
+}
+
+/// Total distance between the lists, pairing the smallest with the smallest and so on.
+pub fn part1(input: &str) -> usize {
+    sorted_distance(&parse_input(input), |a: usize, b| a.abs_diff(b))
+}
+
+/// Similarity score of the two lists, by merging their sorted runs.
+fn merge_similarity(lists: &[Vec<usize>]) -> usize {
+    similarity(lists, |id| id)
+}
+
+/// Similarity score of the two lists, by counting the right list's IDs.
+fn counted_similarity(lists: &[Vec<usize>]) -> usize {
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for &id in &lists[1] {
+        *counts.entry(id).or_default() += 1;
+    }
+
+    lists[0].iter().map(|id| id * counts.get(id).unwrap_or(&0)).sum()
+}
+
+fn part2_merge(input: &str) -> usize {
+    merge_similarity(&parse_input(input))
+}
+
+fn part2_counts(input: &str) -> usize {
+    counted_similarity(&parse_input(input))
+}
+
+/// Similarity score: each left ID, times how often it appears on the right.
+pub const PART2: Strategies<usize> = Strategies(&[
+    ("merge", part2_merge),
+    ("counts", part2_counts)
+]);
+
+#[cfg(test)]
+mod tests {
+    use crate::gen::{day01_lists, Rng};
+    use super::{counted_similarity, merge_similarity, parse_input, part1, part2_counts};
+    use super::{part2_merge, PART2};
+
+    const INPUT: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+
+    crate::test_line_endings! { part1, PART2; INPUT }
+
+    #[test]
+    fn test_part_1() {
+        assert_eq!(part1(INPUT), 11);
+    }
+
+    #[test]
+    fn test_part_2() {
+        assert_eq!(part2_merge(INPUT), 31);
+        assert_eq!(part2_counts(INPUT), 31);
+    }
+
+    #[test]
+    fn test_strategies_agree() {
+        for seed in 0..5 {
+            let lists = parse_input(&day01_lists(&mut Rng::new(seed), 5000));
+            assert_eq!(counted_similarity(&lists), merge_similarity(&lists));
+        }
+    }
+}
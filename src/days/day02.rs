@@ -0,0 +1,213 @@
+//! Day 2: Red-Nosed Reports
+//!
+//! Each report is safe if it is strictly monotonic with steps of 1 to 3.
+//!
+//! # Approach
+//!
+//! Both parts are [`Report::is_safe_with`], which handles any range of step sizes and any number
+//! of removals: part 1 allows none, and part 2 one. Rather than trying every way of removing
+//! levels, it works out, for each direction, the fewest removals that leave the kept levels safe,
+//! by dynamic programming over which level was kept last. `--thresholds` counts the reports that
+//! are safe under `--min-step`, `--max-step` and `--removals`.
+//!
+//! # Complexity
+//!
+//! `O(len × removals)` for each report, so linear for both parts.
+
+use crate::parse::{parse_lines, unsigned};
+use nom::character::complete::space1;
+use nom::multi::separated_list1;
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+
+crate::day_args! { min_step: usize = 1, max_step: usize = 3, removals: usize = 2 }
+
+/// The step sizes the puzzle allows.
+const STEPS: RangeInclusive<usize> = 1..=3;
+
+/// One report's levels.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Report {
+    levels: Vec<usize>
+}
+
+impl Report {
+    /// Whether the levels, after removing at most `max_removals` of them, strictly increase or
+    /// strictly decrease, with every step's size in `tolerance`.
+    ///
+    /// The puzzle's reports are safe with `1..=3` and no removals (part 1) or one (part 2).
+    fn is_safe_with(&self, tolerance: RangeInclusive<usize>, max_removals: usize) -> bool {
+        [Ordering::Less, Ordering::Greater].into_iter()
+            .any(|direction| self.removals_needed(direction, &tolerance, max_removals).is_some())
+    }
+
+    /// Fewest removals, if at most `max_removals`, that leave every step going in `direction`
+    /// (comparing each level with the next) with its size in `tolerance`.
+    fn removals_needed(
+        &self,
+        direction: Ordering,
+        tolerance: &RangeInclusive<usize>,
+        max_removals: usize
+    ) -> Option<usize> {
+        let levels = &self.levels;
+        let n = levels.len();
+        if n <= max_removals + 1 {
+            return Some(n.saturating_sub(1));
+        }
+
+        let step_ok = |a: usize, b: usize| {
+            a.cmp(&b) == direction && tolerance.contains(&a.abs_diff(b))
+        };
+
+        // fewest[i]: fewest removals among the first `i + 1` levels that keep level `i` and leave
+        // the kept ones so far safe. Only the `max_removals + 1` levels before `i` can be the
+        // previous one kept, and before that, everything may have been removed.
+        let mut fewest: Vec<Option<usize>> = Vec::with_capacity(n);
+        for i in 0..n {
+            let after_kept = (i.saturating_sub(max_removals + 1)..i)
+                .filter(|&j| step_ok(levels[j], levels[i]))
+                .filter_map(|j| fewest[j].map(|removed| removed + (i - j - 1)));
+            let best = after_kept.chain(Some(i)).min().filter(|&removed| removed <= max_removals);
+            fewest.push(best);
+        }
+
+        // Everything after the last level kept is removed.
+        (0..n)
+            .filter_map(|i| fewest[i].map(|removed| removed + (n - 1 - i)))
+            .min()
+            .filter(|&removed| removed <= max_removals)
+    }
+}
+
+/// Reports, parsed lazily one line at a time.
+fn parse_input(input: &str) -> impl Iterator<Item=Report> + '_ {
+    parse_lines(input, separated_list1(space1, unsigned))
+        .map(|levels| Report { levels: levels.unwrap() })
+}
+
+/// Number of reports that are safe with steps in `tolerance`, after removing at most
+/// `max_removals` levels.
+fn count_safe(input: &str, tolerance: RangeInclusive<usize>, max_removals: usize) -> usize {
+    parse_input(input)
+        .filter(|report| report.is_safe_with(tolerance.clone(), max_removals))
+        .count()
+}
+
+/// Number of safe reports.
+pub fn part1(input: &str) -> usize {
+    count_safe(input, STEPS, 0)
+}
+
+/// Number of reports that are safe, or would be with one level removed.
+pub fn part2(input: &str) -> usize {
+    count_safe(input, STEPS, 1)
+}
+
+/// Number of reports that are safe with steps of `--min-step` to `--max-step`, after removing at
+/// most `--removals` levels (by default 1 to 3, and 2).
+pub fn thresholds(input: &str, args: &Args) -> usize {
+    count_safe(input, args.min_step..=args.max_step, args.removals)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gen::{day02_reports, Rng};
+    use super::{part1, part2, thresholds, Args, Report, STEPS};
+    const TEST_INPUT: &str = "7 6 4 2 1\n1 2 7 8 9\n9 7 6 2 1\n1 3 2 4 5\n8 6 4 4 1\n1 3 6 7 9";
+
+    crate::test_line_endings! { part1, part2; TEST_INPUT }
+
+    #[test]
+    fn test_part_one() {
+        assert_eq!(part1(TEST_INPUT), 2);
+    }
+
+    #[test]
+    fn test_part_two() {
+        assert_eq!(part2(TEST_INPUT), 4);
+        // Spikes in the middle, and at the end of a short report.
+        assert_eq!(part2("65 63 60 11 57 56\n1 2 9 3 4\n9 10 4"), 3);
+    }
+
+    fn almost_safe(levels: &[usize]) -> bool {
+        Report { levels: levels.to_vec() }.is_safe_with(STEPS, 1)
+    }
+
+    #[test]
+    fn test_almost_safe_regressions() {
+        // A spike is two gaps either side of one level; removing it fixes both.
+        assert!(almost_safe(&[65, 63, 60, 11, 57, 56]));
+        assert!(almost_safe(&[1, 2, 9, 3, 4]));
+        // But not when there's a third gap, or the spike's neighbours are too far apart.
+        assert!(!almost_safe(&[1, 2, 9, 3, 9]));
+        assert!(!almost_safe(&[1, 2, 9, 7, 8]));
+
+        // In three levels, a direction change is at both the start and the end, and only
+        // removing the last level fixes this one.
+        assert!(almost_safe(&[9, 10, 4]));
+        assert!(almost_safe(&[4, 10, 9]));
+        assert!(!almost_safe(&[1, 9, 2, 10]));
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let args = |min_step, max_step, removals| Args { min_step, max_step, removals };
+        assert_eq!(thresholds(TEST_INPUT, &args(1, 3, 0)), part1(TEST_INPUT));
+        assert_eq!(thresholds(TEST_INPUT, &args(1, 3, 1)), part2(TEST_INPUT));
+        // With two removals, 1 2 7 8 9 becomes 7 8 9, and 8 6 4 4 1 becomes 8 6 4.
+        assert_eq!(thresholds(TEST_INPUT, &args(1, 3, 2)), 6);
+        assert_eq!(thresholds(TEST_INPUT, &args(2, 2, 2)), 3);
+        // The gaps of 4 and 5 are fine if steps can be that big.
+        assert_eq!(thresholds(TEST_INPUT, &args(1, 5, 0)), 4);
+    }
+
+    /// Whether some way of removing at most `removals` levels leaves a safe report, trying
+    /// every subset of levels to keep.
+    fn brute_force(levels: &[usize], min_step: usize, max_step: usize, removals: usize) -> bool {
+        (0..1usize << levels.len())
+            .filter(|kept: &usize| levels.len() - kept.count_ones() as usize <= removals)
+            .any(|kept| {
+                let kept: Vec<usize> = (0..levels.len())
+                    .filter(|&i| kept & (1 << i) != 0)
+                    .map(|i| levels[i])
+                    .collect();
+                let steps_ok = |up: bool| kept.windows(2).all(|w| {
+                    (w[0] < w[1]) == up && w[0] != w[1]
+                        && (min_step..=max_step).contains(&w[0].abs_diff(w[1]))
+                });
+                steps_ok(true) || steps_ok(false)
+            })
+    }
+
+    #[test]
+    fn test_is_safe_with() {
+        let mut rng = Rng::new(2);
+        for _ in 0..5000 {
+            let len = rng.between(1, 8);
+            let levels: Vec<usize> = (0..len).map(|_| rng.between(1, 20)).collect();
+            let report = Report { levels: levels.clone() };
+
+            for (min_step, max_step) in [(1, 3), (2, 5), (1, 1)] {
+                for removals in 0..=3 {
+                    assert_eq!(
+                        report.is_safe_with(min_step..=max_step, removals),
+                        brute_force(&levels, min_step, max_step, removals),
+                        "{levels:?} {min_step}..={max_step} {removals}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parts_against_brute_force() {
+        let input = day02_reports(&mut Rng::new(2), 500);
+        let reports: Vec<Vec<usize>> = input.lines()
+            .map(|line| line.split(' ').map(|n| n.parse().unwrap()).collect())
+            .collect();
+
+        let count = |removals| reports.iter().filter(|r| brute_force(r, 1, 3, removals)).count();
+        assert_eq!(part1(&input), count(0));
+        assert_eq!(part2(&input), count(1));
+    }
+}
@@ -0,0 +1,177 @@
+//! Day 3: Mull It Over
+//!
+//! A nom parser pulls the valid `mul(a,b)`, `do()` and `don't()` instructions out of the corrupted
+//! memory, skipping one character at a time past anything else. Each instruction keeps the byte
+//! range it was read from, so `--highlight` can show exactly what the scanner recognised.
+
+use std::ops::Range;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::{anychar, char, digit1};
+use nom::combinator::{consumed, map, map_res};
+use nom::{IResult, Offset};
+use nom::multi::many1;
+use nom::sequence::{preceded, separated_pair, terminated};
+
+#[derive(Copy, Clone, Debug)]
+enum Instruction {
+    Mul(usize, usize),
+    Do,
+    Dont,
+    Invalid
+}
+
+use Instruction::*;
+
+fn number(input: &str) -> IResult<&str, usize> {
+    map_res(digit1, |d: &str| d.parse::<usize>())(input)
+}
+
+fn mul(input: &str) -> IResult<&str, Instruction> {
+    map(
+        preceded(
+            tag("mul("),
+            terminated(
+                separated_pair(number, char(','), number),
+                char(')')
+            )
+        ),
+        |(a, b)| Mul(a, b)
+    )(input)
+}
+
+fn instruction(input: &str) -> IResult<&str, Instruction> {
+    alt(
+        (
+            mul,
+            map(tag("do()"), |_| Do),
+            map(tag("don't()"), |_| Dont),
+            map(anychar, |_| Invalid)
+        )
+    )(input)
+}
+
+/// Every instruction in `input`, with the byte range it was read from.
+fn parse_spanned(input: &str) -> Vec<(Range<usize>, Instruction)> {
+    many1(consumed(instruction))(input).unwrap().1
+        .into_iter()
+        .map(|(text, instr)| {
+            let start = input.offset(text);
+            (start..start + text.len(), instr)
+        })
+        .collect()
+}
+
+fn parse_input(input: &str) -> Vec<Instruction> {
+    parse_spanned(input).into_iter().map(|(_, instr)| instr).collect()
+}
+
+/// Sum of the products of every valid `mul` instruction.
+pub fn part1(input: &str) -> usize {
+    parse_input(input).into_iter().filter_map(|p| {
+        match p {
+            Mul(a, b) => Some(a * b),
+            _ => None
+        }
+    }).sum()
+}
+
+/// Sum of the products of the `mul` instructions that are enabled, folding over the instructions
+/// with `do()` and `don't()` toggling a flag.
+pub fn part2(input: &str) -> usize {
+    parse_input(input).into_iter().fold(
+        (0, true),
+        |(total, is_enabled), instr| {
+            match (instr, is_enabled) {
+                (Mul(x, y), true) => (total + x * y, true),
+                (Do, false) => (total, true),
+                (Dont, true) => (total, false),
+                _ => (total, is_enabled)
+            }
+        }
+    ).0
+}
+
+const GREEN: &str = "\x1b[1;32m";
+const YELLOW: &str = "\x1b[33m";
+const CYAN: &str = "\x1b[36m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// The input with every valid instruction highlighted: `mul`s in green while enabled and yellow
+/// while disabled, `do()` in cyan and `don't()` in red.
+pub fn highlight(input: &str) -> String {
+    let mut result = String::new();
+    let mut enabled = true;
+
+    for (span, instr) in parse_spanned(input) {
+        let color = match instr {
+            Mul(_, _) if enabled => GREEN,
+            Mul(_, _) => YELLOW,
+            Do => CYAN,
+            Dont => RED,
+            Invalid => ""
+        };
+
+        match instr {
+            Do => enabled = true,
+            Dont => enabled = false,
+            _ => {}
+        }
+
+        if color.is_empty() {
+            result.push_str(&input[span]);
+        }
+        else {
+            result.push_str(&format!("{color}{}{RESET}", &input[span]));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{highlight, parse_spanned, part1, part2, Instruction, GREEN, RED, RESET, YELLOW};
+
+    const TEST_INPUT1: &str =
+        "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+
+    const TEST_INPUT2: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    crate::test_line_endings! { part1, part2; TEST_INPUT1, TEST_INPUT2 }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT1), 161);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT2), 48);
+    }
+
+    #[test]
+    fn test_spans() {
+        let spans: Vec<_> = parse_spanned(TEST_INPUT2).into_iter()
+            .filter(|(_, instr)| !matches!(instr, Instruction::Invalid))
+            .map(|(span, _)| &TEST_INPUT2[span])
+            .collect();
+        assert_eq!(spans, vec!["mul(2,4)", "don't()", "mul(5,5)", "mul(11,8)", "do()", "mul(8,5)"]);
+
+        // Scanning restarts one character after a failed match, so an inner `mul` is still found.
+        let nested = parse_spanned("mul(mul(2,3),4)");
+        assert!(matches!(nested[4], (ref span, Instruction::Mul(2, 3)) if *span == (4..12)));
+    }
+
+    #[test]
+    fn test_highlight() {
+        assert_eq!(highlight("mul(mul(2,3),4)"), format!("mul({GREEN}mul(2,3){RESET},4)"));
+        assert_eq!(
+            highlight("don't()mul(1,2)"),
+            format!("{RED}don't(){RESET}{YELLOW}mul(1,2){RESET}")
+        );
+        assert_eq!(highlight("xyz"), "xyz");
+    }
+}
@@ -0,0 +1,245 @@
+//! Day 4: Ceres Search
+//!
+//! A word search on a grid of letters.
+//!
+//! # Approach
+//!
+//! Part 1 tries all eight directions from every `X`; part 2 looks at the four diagonal neighbors of
+//! every `A`. Both are a single scan of the grid, so linear in its size.
+//!
+//! The grid is a [`ByteGrid`] over the input's own bytes, so there's no parsing to speak of, and
+//! the `X`s and `A`s are found with `memchr` rather than by checking every cell.
+//!
+//! The "rotations" strategy for part 1 avoids the bounds checks in eight directions instead: it
+//! only reads left to right and diagonally down to the right, in each of the grid's four
+//! rotations. `cargo bench --bench days` compares the two.
+
+use std::collections::HashSet;
+use std::iter::successors;
+use itertools::Itertools;
+use crate::grid::{ByteGrid, Grid};
+use crate::strategy::Strategies;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight
+}
+
+const DIRECTIONS: [Direction; 8] = [Up, Down, Left, Right, UpLeft, UpRight, DownLeft, DownRight];
+use Direction::*;
+
+impl Direction {
+    fn delta(&self) -> (isize, isize) {
+        match self {
+            Up => (-1, 0),
+            Down => (1, 0),
+            Left => (0, -1),
+            Right => (0, 1),
+            UpLeft => (-1, -1),
+            UpRight => (-1, 1),
+            DownLeft => (1, -1),
+            DownRight => (1, 1)
+        }
+    }
+}
+
+fn is_xmas(puzzle: &ByteGrid, (i, j): (usize, usize), dir: Direction) -> bool {
+    let (di, dj) = dir.delta();
+
+    // Only the last letter can be out of bounds, if any is.
+    let end = i.checked_add_signed(3 * di).zip(j.checked_add_signed(3 * dj));
+    if end.and_then(|end| puzzle.get(end)).is_none() {
+        return false;
+    }
+
+    b"XMAS".iter().enumerate().all(|(k, &c)| {
+        let k = k as isize;
+        puzzle[((i as isize + k * di) as usize, (j as isize + k * dj) as usize)] == c
+    })
+}
+
+/// Starting position (of the `X`) and direction of every XMAS in the puzzle.
+fn xmas_matches(puzzle: &ByteGrid) -> Vec<((usize, usize), Direction)> {
+    puzzle.find(b'X')
+        .cartesian_product(DIRECTIONS)
+        .filter(|&(pos, d)| is_xmas(puzzle, pos, d))
+        .collect()
+}
+
+fn part1_eight_way(input: &str) -> usize {
+    xmas_matches(&ByteGrid::new(input)).len()
+}
+
+/// Number of XMASes reading left to right, or diagonally down and to the right.
+fn count_forward(grid: &Grid<char>) -> usize {
+    let (rows, cols) = grid.bounds();
+    let spells = |(i, j): (usize, usize), (di, dj): (usize, usize)| {
+        "XMAS".chars().enumerate().all(|(k, c)| grid[(i + k * di, j + k * dj)] == c)
+    };
+
+    let across = (0..rows).cartesian_product(0..cols.saturating_sub(3))
+        .filter(|&pos| spells(pos, (0, 1)))
+        .count();
+    let diagonal = (0..rows.saturating_sub(3)).cartesian_product(0..cols.saturating_sub(3))
+        .filter(|&pos| spells(pos, (1, 1)))
+        .count();
+
+    across + diagonal
+}
+
+fn part1_rotations(input: &str) -> usize {
+    let grid = Grid::parse(input, |c| c);
+    successors(Some(grid), |grid| Some(grid.rotate_cw()))
+        .take(4)
+        .map(|grid| count_forward(&grid))
+        .sum()
+}
+
+/// Number of times XMAS appears, in any direction.
+pub const PART1: Strategies<usize> = Strategies(&[
+    ("eight-way", part1_eight_way),
+    ("rotations", part1_rotations)
+]);
+
+fn get_x(puzzle: &ByteGrid, i: usize, j: usize) -> [u8; 5] {
+    //! For the following:
+    //! A . B
+    //! . C .
+    //! D . E
+    //!
+    //! returns `[A, B, C, D, E]`
+    [(i-1, j-1), (i-1, j+1), (i, j), (i+1, j-1), (i+1, j+1)].map(|pos| puzzle[pos])
+}
+
+const GOOD_XS: [&[u8; 5]; 4] = [b"MMASS", b"MSAMS", b"SMASM", b"SSAMM"];
+
+/// Position of the central `A` of every X-MAS in the puzzle.
+fn x_mas_centers(puzzle: &ByteGrid) -> Vec<(usize, usize)> {
+    let (rows, cols) = puzzle.bounds();
+
+    puzzle.find(b'A')
+        .filter(|&(i, j)| 0 < i && i + 1 < rows && 0 < j && j + 1 < cols)
+        .filter(|&(i, j)| GOOD_XS.contains(&&get_x(puzzle, i, j)))
+        .collect()
+}
+
+/// Number of X-MASes: two MASes crossing diagonally at their `A`.
+pub fn part2(input: &str) -> usize {
+    x_mas_centers(&ByteGrid::new(input)).len()
+}
+
+/// The puzzle with every letter not in `keep` replaced by `.`.
+fn render(puzzle: &ByteGrid, keep: &HashSet<(usize, usize)>) -> String {
+    (0..puzzle.rows())
+        .map(|i| {
+            puzzle.row(i).iter().enumerate()
+                .map(|(j, &c)| if keep.contains(&(i, j)) { c as char } else { '.' })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+/// The puzzle with only the letters of some XMAS shown, as in the part 1 illustration.
+pub fn render_xmas(input: &str) -> String {
+    let puzzle = ByteGrid::new(input);
+    let keep = xmas_matches(&puzzle).into_iter()
+        .flat_map(|((i, j), dir)| {
+            let (di, dj) = dir.delta();
+            (0..4).map(move |k| ((i as isize + k * di) as usize, (j as isize + k * dj) as usize))
+        })
+        .collect();
+
+    render(&puzzle, &keep)
+}
+
+/// The puzzle with only the letters of some X-MAS shown, as in the part 2 illustration.
+pub fn render_x_mas(input: &str) -> String {
+    let puzzle = ByteGrid::new(input);
+    let keep = x_mas_centers(&puzzle).into_iter()
+        .flat_map(|(i, j)| [(i-1, j-1), (i-1, j+1), (i, j), (i+1, j-1), (i+1, j+1)])
+        .collect();
+
+    render(&puzzle, &keep)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gen::{day04_letters, Rng};
+    use super::{part1_eight_way, part1_rotations, part2, render_x_mas, render_xmas, PART1};
+
+    const TEST_INPUT: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+    crate::test_line_endings! { PART1, part2; TEST_INPUT }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1_eight_way(TEST_INPUT), 18);
+        assert_eq!(part1_rotations(TEST_INPUT), 18);
+    }
+
+    #[test]
+    fn test_rotations_against_eight_way() {
+        for seed in 0..20 {
+            let puzzle = day04_letters(&mut Rng::new(seed), 7 + seed as usize % 5, 9);
+            assert_eq!(part1_rotations(&puzzle), part1_eight_way(&puzzle), "for\n{puzzle}");
+        }
+
+        // Too small for any XMAS in some directions.
+        assert_eq!(part1_rotations("XMAS\nMMMM"), 1);
+        assert_eq!(part1_rotations("SAMX"), 1);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), 9);
+        assert_eq!(part2(&format!("{TEST_INPUT}\n")), 9);
+        // No room for an X around any A on the edges.
+        assert_eq!(part2("AAA\nAAA"), 0);
+    }
+
+    #[test]
+    fn test_render_xmas() {
+        let expected = "....XXMAS.
+.SAMXMS...
+...S..A...
+..A.A.MS.X
+XMASAMX.MM
+X.....XA.A
+S.S.S.S.SS
+.A.A.A.A.A
+..M.M.M.MM
+.X.X.XMASX";
+        assert_eq!(render_xmas(TEST_INPUT), expected);
+    }
+
+    #[test]
+    fn test_render_x_mas() {
+        let expected = ".M.S......
+..A..MSMS.
+.M.S.MAA..
+..A.ASMSM.
+.M.S.M....
+..........
+S.S.S.S.S.
+.A.A.A.A..
+M.M.M.M.M.
+..........";
+        assert_eq!(render_x_mas(TEST_INPUT), expected);
+    }
+}
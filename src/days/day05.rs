@@ -0,0 +1,377 @@
+//! Day 5: Print Queue
+//!
+//! Page ordering rules, and updates that may or may not follow them.
+//!
+//! # Approach
+//!
+//! The rules are indexed once, by page, in a [`RuleSet`] that both parts share. An update is
+//! correctly ordered if no page has one of its successors (under the rules) printed before it.
+//! Incorrect updates are fixed with a topological sort (Kahn's algorithm) of the rules restricted
+//! to the update's pages; the full rule set has cycles, but the restricted sets don't.
+
+use crate::iter::middle;
+use crate::parse::{two_sections, unexpected_in};
+use crate::sanitize::{check_sections, Format};
+use nom::character::complete::{char, digit1, line_ending, multispace0};
+use nom::combinator::{all_consuming, map, map_res};
+use nom::multi::separated_list1;
+use nom::sequence::{separated_pair, terminated};
+use nom::IResult;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// `Rule(a, b)`: if both pages are in an update, `a` must be printed before `b`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+struct Rule(usize, usize);
+
+/// The ordering rules, indexed by page in both directions.
+#[derive(Debug, Clone, Default)]
+struct RuleSet {
+    succs: HashMap<usize, Vec<usize>>,
+    preds: HashMap<usize, Vec<usize>>
+}
+
+impl RuleSet {
+    fn new(rules: &[Rule]) -> RuleSet {
+        let mut rule_set = RuleSet::default();
+        rules.iter().for_each(|&rule| rule_set.insert(rule));
+        rule_set
+    }
+
+    fn insert(&mut self, Rule(a, b): Rule) {
+        self.succs.entry(a).or_default().push(b);
+        self.preds.entry(b).or_default().push(a);
+    }
+
+    /// The pages that must come after `page`.
+    fn successors(&self, page: usize) -> &[usize] {
+        self.succs.get(&page).map_or(&[], Vec::as_slice)
+    }
+
+    /// The pages that must come before `page`.
+    fn predecessors(&self, page: usize) -> &[usize] {
+        self.preds.get(&page).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every page mentioned by some rule.
+    fn pages(&self) -> impl Iterator<Item=usize> + '_ {
+        self.succs.keys().chain(self.preds.keys()).copied().unique()
+    }
+
+    fn relates(&self, a: usize, b: usize) -> bool {
+        self.successors(a).contains(&b) || self.successors(b).contains(&a)
+    }
+
+    fn is_sorted(&self, update: &[usize]) -> bool {
+        let mut seen = HashSet::new();
+        update.iter().all(|&page| {
+            seen.insert(page);
+            !self.successors(page).iter().any(|s| seen.contains(s))
+        })
+    }
+
+    /// The rules broken by `update`, in order: those `Rule(a, b)` where `b` is printed before `a`.
+    fn violations(&self, update: &[usize]) -> Vec<Rule> {
+        let positions: HashMap<usize, usize> =
+            update.iter().enumerate().map(|(i, &p)| (p, i)).collect();
+        let positions = &positions;
+
+        update.iter().enumerate()
+            .flat_map(|(i, &a)| {
+                self.successors(a).iter()
+                    .filter(move |&b| positions.get(b).is_some_and(|&j| j < i))
+                    .map(move |&b| Rule(a, b))
+            })
+            .sorted()
+            .collect()
+    }
+
+    /// The pages of `update`, in an order that follows every rule between them, if there is one.
+    fn sort(&self, update: &[usize]) -> Option<Vec<usize>> {
+        let pages: HashSet<usize> = update.iter().copied().collect();
+        let mut waiting_on: HashMap<usize, usize> = pages.iter()
+            .map(|&page| {
+                (page, self.predecessors(page).iter().filter(|p| pages.contains(p)).count())
+            })
+            .collect();
+
+        let mut ready: Vec<usize> = update.iter().copied()
+            .filter(|page| waiting_on[page] == 0)
+            .collect();
+        let mut result = Vec::new();
+
+        while let Some(page) = ready.pop() {
+            result.push(page);
+            for s in self.successors(page).iter().filter(|s| pages.contains(s)) {
+                let count = waiting_on.get_mut(s).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(*s);
+                }
+            }
+        }
+
+        (result.len() == pages.len()).then_some(result)
+    }
+}
+
+fn number(input: &str) -> IResult<&str, usize> {
+    map_res(digit1, |s: &str| s.parse::<usize>())(input)
+}
+
+fn rule(input: &str) -> IResult<&str, Rule> {
+    map(separated_pair(number, char('|'), number), |(a, b)| Rule(a, b))(input)
+}
+
+/// The input's basic format: rules like `47|53`, then updates like `75,47,61`.
+const FORMAT: [Format; 2] = [
+    Format::new().chars("0123456789|"),
+    Format::new().chars("0123456789,")
+];
+
+/// The rules and updates, failing with where the input is malformed.
+fn parse_input(input: &str) -> Result<(RuleSet, Vec<Vec<usize>>), String> {
+    check_sections(input, &FORMAT).map_err(|e| e.to_string())?;
+    let (rules, updates) = all_consuming(terminated(
+        two_sections(
+            separated_list1(line_ending, rule),
+            separated_list1(line_ending, separated_list1(char(','), number))
+        ),
+        multispace0
+    ))(input)
+        .map_err(|e| match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => unexpected_in(input, e.input).to_string(),
+            nom::Err::Incomplete(_) => unexpected_in(input, "").to_string()
+        })?
+        .1;
+
+    Ok((RuleSet::new(&rules), updates))
+}
+
+/// The middle page of an update; updates with an even number of pages have none, and are an
+/// error.
+fn middle_page(pages: &[usize]) -> Result<usize, String> {
+    middle(pages).copied().map_err(|e| format!("Update {}: {e}", pages.iter().join(",")))
+}
+
+/// Sum of the middle pages of the correctly-ordered updates.
+pub fn part1(input: &str) -> Result<usize, String> {
+    let (rules, page_groups) = parse_input(input)?;
+
+    page_groups.iter().filter(|&pages| rules.is_sorted(pages))
+        .map(|pages| middle_page(pages))
+        .sum()
+}
+
+/// Sum of the middle pages of the incorrectly-ordered updates, after putting them in order.
+pub fn part2(input: &str) -> Result<usize, String> {
+    let (rules, page_groups) = parse_input(input)?;
+
+    page_groups.iter()
+        .filter(|&pages| !rules.is_sorted(pages))
+        .map(|pages| {
+            let sorted = rules.sort(pages)
+                .ok_or_else(|| format!("Update {}: the rules are cyclic", pages.iter().join(",")))?;
+            middle_page(&sorted)
+        })
+        .sum()
+}
+
+/// Find a cycle in the digraph of all rules, if there is one.
+///
+/// The result lists the pages around the cycle, with the first page repeated at the end.
+fn find_cycle(rules: &RuleSet) -> Option<Vec<usize>> {
+    let mut finished = HashSet::new();
+
+    for root in rules.pages().sorted() {
+        if finished.contains(&root) {
+            continue;
+        }
+
+        // Iterative DFS; `path` holds the current stack of pages and how many of each page's
+        // successors have been explored so far.
+        let mut path: Vec<(usize, usize)> = vec![(root, 0)];
+        let mut on_path: HashSet<usize> = HashSet::from([root]);
+
+        while let Some(&mut (page, ref mut next)) = path.last_mut() {
+            let ss = rules.successors(page);
+
+            if *next == ss.len() {
+                path.pop();
+                on_path.remove(&page);
+                finished.insert(page);
+                continue;
+            }
+
+            let s = ss[*next];
+            *next += 1;
+
+            if on_path.contains(&s) {
+                let mut cycle: Vec<usize> = path.iter()
+                    .map(|&(p, _)| p)
+                    .skip_while(|&p| p != s)
+                    .collect();
+                cycle.push(s);
+                return Some(cycle);
+            }
+
+            if !finished.contains(&s) {
+                path.push((s, 0));
+                on_path.insert(s);
+            }
+        }
+    }
+
+    None
+}
+
+/// Pairs of pages in an update that no rule relates, so whose relative order is unconstrained.
+fn unrelated_pairs(rules: &RuleSet, pages: &[usize]) -> Vec<(usize, usize)> {
+    pages.iter().cloned()
+        .tuple_combinations()
+        .filter(|&(a, b)| !rules.relates(a, b))
+        .collect()
+}
+
+/// Human-readable explanation of the rule set and of why each update does or doesn't pass.
+pub fn explain(input: &str) -> Result<String, String> {
+    let (rules, page_groups) = parse_input(input)?;
+    let mut result = String::new();
+
+    match find_cycle(&rules) {
+        Some(cycle) => writeln!(
+            result, "Rules are cyclic: {}", cycle.iter().join(" -> ")
+        ).unwrap(),
+        None => writeln!(result, "Rules are acyclic").unwrap()
+    }
+
+    for (i, pages) in page_groups.iter().enumerate() {
+        let violations = rules.violations(pages);
+        let status = if violations.is_empty() { "ok" } else { "out of order" };
+        writeln!(result, "Update {} ({}): {status}", i + 1, pages.iter().join(",")).unwrap();
+
+        for Rule(a, b) in violations {
+            writeln!(result, "    violates {a}|{b}").unwrap();
+        }
+
+        for (a, b) in unrelated_pairs(&rules, pages) {
+            writeln!(result, "    no rule relates {a} and {b}").unwrap();
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_cycle, parse_input, part1, part2, unrelated_pairs, Rule, RuleSet};
+    const TEST_INPUT: &str = "47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+
+    crate::test_line_endings! { part1, part2; TEST_INPUT }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), Ok(143));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), Ok(123));
+    }
+
+    #[test]
+    fn test_even_length_update() {
+        let input = "47|53\n53|29\n\n47,53,29\n47,53\n53,47";
+        let error = "Update 47,53: No middle element in a list of length 2".to_owned();
+        assert_eq!(part1(input), Err(error.clone()));
+        assert_eq!(part2(input), Err(error));
+    }
+
+    #[test]
+    fn test_malformed() {
+        let expected = "Failed to parse line 2, column 3: unexpected ',' in \"53,29\"";
+        assert_eq!(part1("47|53\n53,29\n\n47,53,29"), Err(expected.to_owned()));
+        let expected = "Failed to parse line 4, column 3: unexpected '|' in \"47|53\"";
+        assert_eq!(part2("47|53\n53|29\n\n47|53"), Err(expected.to_owned()));
+        // The right characters, in the wrong places, get past the format to the parser.
+        assert!(part1("47||53\n\n47,53,29").unwrap_err().starts_with("Failed to parse line 1"));
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let (rules, _) = parse_input(TEST_INPUT).unwrap();
+        assert_eq!(find_cycle(&rules), None);
+
+        let cyclic = RuleSet::new(&[Rule(1, 2), Rule(2, 3), Rule(3, 1), Rule(3, 4)]);
+        assert_eq!(find_cycle(&cyclic), Some(vec![1, 2, 3, 1]));
+    }
+
+    #[test]
+    fn test_violations() {
+        let (rules, page_groups) = parse_input(TEST_INPUT).unwrap();
+
+        assert_eq!(rules.violations(&page_groups[0]), vec![]);
+        assert_eq!(rules.violations(&page_groups[3]), vec![Rule(97, 75)]);
+        assert_eq!(rules.violations(&page_groups[4]), vec![Rule(29, 13)]);
+        assert_eq!(
+            rules.violations(&page_groups[5]),
+            vec![Rule(29, 13), Rule(47, 13), Rule(47, 29), Rule(75, 13)]
+        );
+        assert!(page_groups.iter().all(|pages| {
+            rules.is_sorted(pages) == rules.violations(pages).is_empty()
+        }));
+    }
+
+    #[test]
+    fn test_sort() {
+        let (rules, page_groups) = parse_input(TEST_INPUT).unwrap();
+
+        assert_eq!(rules.sort(&page_groups[3]), Some(vec![97, 75, 47, 61, 53]));
+        assert_eq!(rules.sort(&page_groups[4]), Some(vec![61, 29, 13]));
+        assert_eq!(rules.sort(&page_groups[5]), Some(vec![97, 75, 47, 29, 13]));
+        assert_eq!(rules.sort(&page_groups[0]).as_ref(), Some(&page_groups[0]));
+
+        // Rules are indexed as they're added, and can make an update unsortable.
+        let mut rules = RuleSet::new(&[Rule(1, 2), Rule(2, 3)]);
+        assert_eq!(rules.sort(&[3, 1, 2]), Some(vec![1, 2, 3]));
+        assert_eq!(rules.predecessors(3), [2]);
+        rules.insert(Rule(3, 1));
+        assert_eq!(rules.sort(&[3, 1, 2]), None);
+        assert_eq!(rules.sort(&[3, 1]), Some(vec![3, 1]));
+    }
+
+    #[test]
+    fn test_unrelated_pairs() {
+        let (rules, page_groups) = parse_input(TEST_INPUT).unwrap();
+        assert_eq!(unrelated_pairs(&rules, &page_groups[0]), vec![]);
+        assert_eq!(unrelated_pairs(&rules, &[75, 13, 99]), vec![(75, 99), (13, 99)]);
+    }
+}
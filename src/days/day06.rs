@@ -0,0 +1,818 @@
+//! Day 6: Guard Gallivant
+//!
+//! A guard walks forward, turning right at obstructions, until leaving the map.
+//!
+//! # Approach
+//!
+//! The board is a (position, heading) state graph, the same kind day 16 searches, built by
+//! [`grid_with_headings`]: the guard's move from each state is its edge forward if it has one, and
+//! otherwise its right turn ([`guard_move`]). Rather than stepping along it cell by cell, the board
+//! precomputes jump tables over it: for each position and direction, the state the guard will be
+//! in after the next turn (or that they'll leave). A path is then a list of corners, and loop
+//! detection only needs to remember corners.
+//!
+//! For part 2, the only useful places for a new obstruction are on the original path. Each
+//! candidate is checked with an `AugmentedBoard`, which answers from the jump tables except where
+//! the guard's next jump would cross the new obstruction. The guard's walk is the same as the
+//! original one until they first step towards the obstruction, so each check resumes from the
+//! space just before it ([`Path::approaches`] says which, and from which direction), with the
+//! corners passed so far still counting towards loop detection ([`Board::path_from_with_prefix`]),
+//! looked up in one index of the original path's corners rather than copied for each candidate.
+//! Checking each candidate from the guard's start instead is the "from-start" strategy, which
+//! `cargo bench --bench days` compares against.
+//!
+//! The walk itself is a [`Patrol`]: a [`Simulation`] stepping from one corner to the next, which
+//! [`simulation`] starts on the input's board.
+//!
+//! [`layouts`] prepares a plain cell-by-cell walk of the guard in each grid layout, for the bench
+//! to time.
+//!
+//! # Complexity
+//!
+//! Building the tables is linear in the board size. Each candidate obstruction then costs time
+//! proportional to the number of corners on the guard's path, not its length.
+
+use crate::cache::parse_cached;
+use crate::grid::Direction::{self, *};
+use crate::graph::{grid_with_headings, Heading, Turns, WeightedGraph};
+use crate::grid::{ColumnMajor, Grid, Layout, LayoutKernels, Nested, RowMajor};
+use crate::hash::{HashMap, HashSet};
+use crate::simulation::{Simulation, StepResult};
+use crate::strategy::Strategies;
+use itertools::Itertools;
+use nom::branch::alt;
+use nom::character::complete::{char, line_ending};
+use nom::combinator::value;
+use nom::multi::{many1, separated_list1};
+use nom::IResult;
+
+/// Tokens representing the semantics of the input characters.
+#[derive(Copy, Clone)]
+enum Token { Empty, Obstruction, Guard(Direction) }
+
+/// State of the guard (either present at a position on the board or gone)
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "parse-cache", derive(serde::Serialize, serde::Deserialize))]
+enum GuardState { Gone, Present(usize, usize, Direction) }
+
+/// A path through the board, represented by the "corners" of the path.
+///
+/// If the path is a loop, the last element of corners will be the first position/direction
+/// recognized as a loop.
+///
+/// If it is not a loop, the last element of corners will be the last position touched before
+/// exiting the board.
+struct Path {
+    corners: Vec<(usize, usize, Direction)>,
+    is_loop: bool
+}
+
+impl Path {
+    /// All the spaces (and corresponding directions) touched by this path.
+    fn all_spaces_and_dirs(&self) -> Vec<(usize, usize, Direction)> {
+        self.corners.iter().cloned()
+            .tuple_windows::<((usize, usize, Direction), (usize, usize, Direction))>()
+            .flat_map(|((i1, j1, d1), (i2, j2, _))| {
+                let segment: Vec<(usize, usize, Direction)> = match d1 {
+                    Up => (i2..=i1).rev().map(|i| (i, j1, d1)).collect(),
+                    Down => (i1..=i2).map(|i| (i, j1, d1)).collect(),
+                    Left => (j2..=j1).rev().map(|j| (i1, j, d1)).collect(),
+                    Right => (j1..=j2).map(|j| (i1, j, d1)).collect()
+                };
+                segment
+            }).collect()
+    }
+
+    /// HashSet of all spaces this path ever touches.
+    fn visited_spaces(&self) -> HashSet<(usize, usize)> {
+        self.all_spaces_and_dirs().iter().map(|&(i, j, _)| (i, j)).collect()
+    }
+
+    /// Where each corner comes on this path, from 0.
+    fn corner_indices(&self) -> HashMap<(usize, usize, Direction), usize> {
+        self.corners.iter().enumerate().map(|(i, &corner)| (corner, i)).collect()
+    }
+
+    /// Each space this path touches after leaving its start, in the order first reached, with how
+    /// the guard first reaches it.
+    fn approaches(&self) -> Vec<Approach> {
+        let Some(&(i, j, _)) = self.corners.first() else { return Vec::new() };
+        let mut seen: HashSet<(usize, usize)> = HashSet::from_iter([(i, j)]);
+
+        // Each stretch of the path starts at its `k`th corner, which the guard has already
+        // reached; from the stretch's next space on, the space behind the guard is past it.
+        let stretches = self.corners.iter().tuple_windows().enumerate();
+        stretches
+            .flat_map(|(k, (&from, &to))| {
+                Path { corners: vec![from, to], is_loop: false }.all_spaces_and_dirs()
+                    .into_iter()
+                    .enumerate()
+                    .skip(1)
+                    .map(move |(n, (i, j, dir))| {
+                        let corners_before = if n == 1 { k } else { k + 1 };
+                        Approach { space: (i, j), dir, corners_before }
+                    })
+            })
+            .filter(|approach| seen.insert(approach.space))
+            .collect()
+    }
+}
+
+/// How the guard first reaches a space on a [`Path`]: heading `dir`, from the space behind them.
+/// The path has `corners_before` corners before the guard's state on that space.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Approach {
+    space: (usize, usize),
+    dir: Direction,
+    corners_before: usize
+}
+
+/// Trait representing the board, including the ability to find the next corner from a given state.
+trait Board {
+    fn next_state(&self, cur_state: GuardState) -> GuardState;
+    fn num_rows(&self) -> usize;
+    fn num_cols(&self) -> usize;
+
+    /// Compute the full path followed from the given initial state.
+    fn path_from(&self, start: GuardState) -> Path {
+        self.path_from_with_prefix(Prefix::EMPTY, start)
+    }
+
+    /// Compute the path followed from `start`, continuing a walk that has already passed the
+    /// corners in `prefix` (which mustn't include `start`): coming back round to one of them is a
+    /// loop too. The path's corners only go from `start` on.
+    fn path_from_with_prefix(&self, prefix: Prefix, start: GuardState) -> Path {
+        let mut seen: HashSet<(usize, usize, Direction)> = HashSet::default();
+        let mut corners = Vec::new();
+
+        // Record each corner as the guard reaches it, stopping if one comes round again.
+        let mut patrol = Patrol { board: self, guard: start };
+        let is_loop = loop {
+            let GuardState::Present(i, j, dir) = patrol.guard else { break false };
+            if prefix.contains(&(i, j, dir)) || !seen.insert((i, j, dir)) {
+                break true;
+            }
+            corners.push((i, j, dir));
+            patrol.step();
+        };
+
+        if !is_loop {
+            if let Some(&(i, j, dir)) = corners.last() {
+                let last = match dir {
+                    Up => (0, j, Up),
+                    Down => (self.num_rows() - 1, j, Down),
+                    Left => (i, 0, Left),
+                    Right => (i, self.num_cols() - 1, Right)
+                };
+                corners.push(last);
+            }
+        }
+
+        Path { corners, is_loop }
+    }
+}
+
+/// The first `len` corners of a path, looked up by where each comes on it (see
+/// [`Path::corner_indices`]), so that resuming a walk after them doesn't have to copy them.
+#[derive(Copy, Clone)]
+struct Prefix<'a> {
+    indices: Option<&'a HashMap<(usize, usize, Direction), usize>>,
+    len: usize
+}
+
+impl Prefix<'_> {
+    /// No corners at all, for a walk from the start.
+    const EMPTY: Prefix<'static> = Prefix { indices: None, len: 0 };
+
+    fn contains(&self, corner: &(usize, usize, Direction)) -> bool {
+        self.indices.and_then(|indices| indices.get(corner)).is_some_and(|&i| i < self.len)
+    }
+}
+
+impl<B: Board + ?Sized> Board for &B {
+    fn next_state(&self, cur_state: GuardState) -> GuardState {
+        (**self).next_state(cur_state)
+    }
+
+    fn num_rows(&self) -> usize {
+        (**self).num_rows()
+    }
+
+    fn num_cols(&self) -> usize {
+        (**self).num_cols()
+    }
+}
+
+/// The guard walking a board, one straight stretch (to the next corner, or off the board) per
+/// [`Simulation`] step. [`simulation`] starts one on the input's board.
+pub struct Patrol<B> {
+    board: B,
+    guard: GuardState
+}
+
+impl<B> Patrol<B> {
+    /// Where the guard is and which way they're facing, or `None` once they've left the board.
+    pub fn guard(&self) -> Option<(usize, usize, Direction)> {
+        match self.guard {
+            GuardState::Present(i, j, dir) => Some((i, j, dir)),
+            GuardState::Gone => None
+        }
+    }
+}
+
+impl<B: Board> Simulation for Patrol<B> {
+    fn step(&mut self) -> StepResult {
+        if self.guard != GuardState::Gone {
+            self.guard = self.board.next_state(self.guard);
+        }
+
+        match self.guard {
+            GuardState::Gone => StepResult::Finished,
+            GuardState::Present(..) => StepResult::Running
+        }
+    }
+}
+
+/// Representation of the original board (as directly parsed from the input).
+///
+/// The up/down/right/left vector arrays contain the next "corner" from each position on the board
+/// for the given direction, found by following its state graph (see [`jump_table`]).
+#[derive(Debug)]
+#[cfg_attr(feature = "parse-cache", derive(serde::Serialize, serde::Deserialize))]
+pub struct OriginalBoard {
+    rows: usize,
+    cols: usize,
+    up: Vec<Vec<GuardState>>,
+    down: Vec<Vec<GuardState>>,
+    left: Vec<Vec<GuardState>>,
+    right: Vec<Vec<GuardState>>
+}
+
+impl OriginalBoard {
+    fn from_tokens(tokens: &[Vec<Token>]) -> OriginalBoard {
+        let graph = grid_with_headings(tokens, 1, 1, Turns::All, |&token| {
+            !matches!(token, Token::Obstruction)
+        });
+        let bounds = (tokens.len(), tokens[0].len());
+        let [up, down, left, right] = [Up, Down, Left, Right]
+            .map(|dir| jump_table(&graph, bounds, dir));
+
+        OriginalBoard { rows: bounds.0, cols: bounds.1, up, down, left, right }
+    }
+}
+
+/// The guard's move from `state` on `graph`, a board's (position, heading) state graph: off the
+/// board if the cell ahead is outside it, along the edge forward if there is one, and otherwise a
+/// turn right.
+fn guard_move(
+    graph: &WeightedGraph<Heading>,
+    bounds: (usize, usize),
+    state: Heading
+) -> GuardState {
+    let (pos, dir) = state;
+    if dir.step(pos, bounds).is_none() {
+        return GuardState::Gone;
+    }
+
+    let ((i, j), dir) = graph[&state].iter()
+        .map(|&(next, _)| next)
+        .find(|&(next, d)| d == dir && next != pos)
+        .unwrap_or((pos, dir.turn_right()));
+    GuardState::Present(i, j, dir)
+}
+
+/// For each cell, the guard's state after following `graph` heading `dir` from it until they turn
+/// or leave. (Obstructed cells have no states, and are left `Gone`.)
+fn jump_table(
+    graph: &WeightedGraph<Heading>,
+    (rows, cols): (usize, usize),
+    dir: Direction
+) -> Vec<Vec<GuardState>> {
+    let mut table = vec![vec![GuardState::Gone; cols]; rows];
+
+    // Fill in the cells nearest the edge the guard is heading for first, so that a step forward
+    // always lands on a cell that's done.
+    let mut cells: Vec<(usize, usize)> = (0..rows).cartesian_product(0..cols).collect();
+    if matches!(dir, Down | Right) {
+        cells.reverse();
+    }
+
+    for (i, j) in cells {
+        if !graph.contains_key(&((i, j), dir)) {
+            continue;
+        }
+        table[i][j] = match guard_move(graph, (rows, cols), ((i, j), dir)) {
+            GuardState::Present(ni, nj, d) if d == dir => table[ni][nj],
+            next => next
+        };
+    }
+
+    table
+}
+
+impl Board for OriginalBoard {
+    fn next_state(&self, guard: GuardState) -> GuardState {
+        match guard {
+            GuardState::Gone => GuardState::Gone,
+            GuardState::Present(i, j, dir) => {
+                match dir {
+                    Up => self.up[i][j],
+                    Down => self.down[i][j],
+                    Left => self.left[i][j],
+                    Right => self.right[i][j]
+                }
+            }
+        }
+    }
+
+    fn num_rows(&self) -> usize {
+        self.rows
+    }
+
+    fn num_cols(&self) -> usize {
+        self.cols
+    }
+}
+
+/// Board that represents adding one additional obstruction to the original board.
+struct AugmentedBoard<'a> {
+    row: usize,
+    col: usize,
+    orig: &'a OriginalBoard
+}
+
+impl<'a> AugmentedBoard<'a> {
+    fn from(orig: &'a OriginalBoard, row: usize, col: usize) -> AugmentedBoard<'a> {
+        AugmentedBoard { row, col, orig }
+    }
+}
+
+impl<'a> Board for AugmentedBoard<'a> {
+    fn next_state(&self, guard: GuardState) -> GuardState {
+        match guard {
+            GuardState::Present(i, j, Up) if j == self.col && i > self.row => {
+                match self.orig.next_state(guard) {
+                    g@ GuardState::Present(x, _, _) if x > self.row => g,
+                    _ => GuardState::Present(self.row + 1, j, Right)
+                }
+            }
+            GuardState::Present(i, j, Down) if j == self.col && i < self.row => {
+                match self.orig.next_state(guard) {
+                    g@ GuardState::Present(x, _, _) if x < self.row => g,
+                    _ => GuardState::Present(self.row - 1, j, Left)
+                }
+            }
+            GuardState::Present(i, j, Left) if i == self.row && j > self.col => {
+                match self.orig.next_state(guard) {
+                    g@ GuardState::Present(_, y, _) if y > self.col => g,
+                    _ => GuardState::Present(i, self.col + 1, Up)
+                }
+            },
+            GuardState::Present(i, j, Right) if i == self.row && j < self.col => {
+                match self.orig.next_state(guard) {
+                    g@ GuardState::Present(_, y, _) if y < self.col => g,
+                    _ => GuardState::Present(i, self.col - 1, Down)
+                }
+            },
+            guard => self.orig.next_state(guard)
+        }
+    }
+
+    fn num_rows(&self) -> usize {
+        self.orig.num_rows()
+    }
+
+    fn num_cols(&self) -> usize {
+        self.orig.num_cols()
+    }
+}
+
+/// The board's jump tables and the guard's starting state; building the tables is the slow part,
+/// so this goes through the parse cache.
+fn parse_input(input: &str) -> (OriginalBoard, GuardState) {
+    parse_cached(input, parse_input_uncached)
+}
+
+fn parse_input_uncached(input: &str) -> (OriginalBoard, GuardState) {
+    fn parser(i: &str) -> IResult<&str, Vec<Vec<Token>>> {
+        separated_list1(
+            line_ending,
+            many1(
+                alt(
+                    (
+                        value(Token::Empty, char('.')),
+                        value(Token::Obstruction, char('#')),
+                        value(Token::Guard(Up), char('^')),
+                        value(Token::Guard(Down), char('v')),
+                        value(Token::Guard(Left), char('<')),
+                        value(Token::Guard(Right), char('>'))
+                    )
+                )
+            )
+        )(i)
+    }
+
+    let tokens = parser(input).unwrap().1;
+    let base = OriginalBoard::from_tokens(&tokens);
+    let (i, j, dir) = tokens.iter().enumerate()
+        .filter_map(|(i, row)|
+            row.iter().enumerate()
+                .filter_map(|(j, &g)| {
+                    match g {
+                        Token::Guard(dir) => Some((i, j, dir)),
+                        _ => None
+                    }
+                }).next()
+        ).next().unwrap();
+
+    (base, GuardState::Present(i, j, dir))
+}
+
+/// Number of distinct positions the guard visits before leaving the map.
+pub fn part1(input: &str) -> usize {
+    let (base, guard) = parse_input(input);
+    base.path_from(guard).visited_spaces().len()
+}
+
+/// Part 2, resuming each candidate's walk just before it.
+fn part2_resume(input: &str) -> usize {
+    let (base, guard) = parse_input(input);
+    let path = base.path_from(guard);
+    let bounds = (base.rows, base.cols);
+
+    let indices = path.corner_indices();
+    let approaches = path.approaches();
+    log::debug!("Trying {} candidate obstructions", approaches.len());
+
+    approaches.into_iter()
+        .filter(|&Approach { space: (i, j), dir, corners_before }| {
+            // The guard was on the space before, heading towards it; nothing has changed so far.
+            let (pi, pj) = dir.reverse().step((i, j), bounds).expect("The guard came from a space");
+            let before = GuardState::Present(pi, pj, dir);
+            let prefix = Prefix { indices: Some(&indices), len: corners_before };
+
+            let board = AugmentedBoard::from(&base, i, j);
+            let is_loop = board.path_from_with_prefix(prefix, before).is_loop;
+            if is_loop {
+                log::debug!("Obstruction at {:?} traps the guard", (i, j));
+            }
+            is_loop
+        })
+        .count()
+}
+
+/// Part 2 as first written, walking from the guard's start for every candidate.
+fn part2_from_start(input: &str) -> usize {
+    let (base, guard) = parse_input(input);
+
+    let (row, col) = match guard {
+        GuardState::Present(i, j, _) => (i, j),
+        _ => panic!("This will always be present at the beginning")
+    };
+
+    let candidates = base.path_from(guard).visited_spaces();
+    log::debug!("Trying {} candidate obstructions", candidates.len() - 1);
+
+    candidates.into_iter()
+        .filter(|&p| p != (row, col))
+        .filter(|&(i, j)| {
+            let is_loop = AugmentedBoard::from(&base, i, j).path_from(guard).is_loop;
+            if is_loop {
+                log::debug!("Obstruction at {:?} traps the guard", (i, j));
+            }
+            is_loop
+        })
+        .count()
+}
+
+/// Number of positions where one new obstruction would trap the guard in a loop.
+pub const PART2: Strategies<usize> = Strategies(&[
+    ("resume", part2_resume),
+    ("from-start", part2_from_start)
+]);
+
+/// The guard at their starting position on the input's board, ready to patrol.
+pub fn simulation(input: &str) -> Patrol<OriginalBoard> {
+    let (board, guard) = parse_input(input);
+    Patrol { board, guard }
+}
+
+/// The map with the guard's route drawn on it, as in the puzzle's illustrations: `|` and `-` where
+/// the guard moves vertically or horizontally, and `+` where they do both (where they turn, or
+/// their route crosses itself). Their starting position keeps its `^`.
+pub fn render(input: &str) -> String {
+    let (base, guard) = parse_input(input);
+
+    // Whether the guard moves through each cell (vertically, horizontally).
+    let mut moves = vec![vec![(false, false); base.cols]; base.rows];
+    for (i, j, dir) in base.path_from(guard).all_spaces_and_dirs() {
+        match dir {
+            Up | Down => moves[i][j].0 = true,
+            Left | Right => moves[i][j].1 = true
+        }
+    }
+
+    input.lines().zip(moves)
+        .map(|(line, row)| {
+            line.chars().zip(row)
+                .map(|(c, moves)| match (c, moves) {
+                    ('.', (true, true)) => '+',
+                    ('.', (true, false)) => '|',
+                    ('.', (false, true)) => '-',
+                    (c, _) => c
+                })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+/// Number of distinct positions visited by a guard at `guard`, stepping cell by cell.
+fn walk<L: Layout>(obstructions: &Grid<bool, L>, guard: GuardState) -> usize {
+    let GuardState::Present(i, j, mut dir) = guard else { return 0 };
+    let mut pos = (i, j);
+    let (rows, cols) = obstructions.bounds();
+    let mut visited: Grid<bool, L> = Grid::from_fn(rows, cols, |_| false);
+    let mut count = 0;
+
+    loop {
+        if !visited[pos] {
+            visited[pos] = true;
+            count += 1;
+        }
+
+        match dir.step(pos, obstructions.bounds()) {
+            None => return count,
+            Some(next) if obstructions[next] => dir = dir.turn_right(),
+            Some(next) => pos = next
+        }
+    }
+}
+
+/// The part 1 answer by walking cell by cell, with the board parsed into layout `L` up front.
+fn walk_in<L: Layout + 'static>(input: &str) -> Box<dyn Fn() -> usize> {
+    let (_, guard) = parse_input(input);
+    let obstructions: Grid<bool, L> = Grid::parse_in(input, |c| c == '#');
+    Box::new(move || walk(&obstructions, guard))
+}
+
+/// A cell-by-cell walk of the guard with the board in each grid layout.
+pub fn layouts(input: &str) -> LayoutKernels {
+    vec![
+        (RowMajor::NAME, walk_in::<RowMajor>(input)),
+        (ColumnMajor::NAME, walk_in::<ColumnMajor>(input)),
+        (Nested::NAME, walk_in::<Nested>(input))
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use crate::gen::{day06_board, Rng};
+    use crate::graph::{grid_with_headings, Heading, Turns, WeightedGraph};
+    use crate::simulation::{Simulation, StepResult};
+    use super::{guard_move, layouts, parse_input, part1, part2_from_start, part2_resume};
+    use super::{render, simulation, PART2};
+    use super::{Approach, AugmentedBoard, Board, GuardState, Prefix};
+    use super::Direction::{self, *};
+    const TEST_INPUT: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+    crate::test_line_endings! { part1, PART2; TEST_INPUT }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 41);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2_resume(TEST_INPUT), 6);
+        assert_eq!(part2_from_start(TEST_INPUT), 6);
+    }
+
+    #[test]
+    fn test_approaches() {
+        let (board, guard) = parse_input(TEST_INPUT);
+        let path = board.path_from(guard);
+        let approaches = path.approaches();
+
+        // Every space but the start, each once.
+        assert_eq!(approaches.len(), 40);
+        assert_eq!(approaches[0], Approach { space: (5, 4), dir: Up, corners_before: 0 });
+        assert_eq!(approaches[1], Approach { space: (4, 4), dir: Up, corners_before: 1 });
+        // Turning at (1, 4), the guard heads right from there.
+        let turned = approaches.iter().find(|approach| approach.space == (1, 5)).unwrap();
+        assert_eq!(*turned, Approach { space: (1, 5), dir: Right, corners_before: 1 });
+        assert_eq!(path.corners[1], (1, 4, Right));
+    }
+
+    #[test]
+    fn test_path_from_with_prefix() {
+        let (board, guard) = parse_input(TEST_INPUT);
+        let path = board.path_from(guard);
+
+        let indices = path.corner_indices();
+        let prefix = |len| Prefix { indices: Some(&indices), len };
+
+        // Resuming partway along gives the rest of the same path.
+        let before = GuardState::Present(1, 6, Right);
+        let resumed = board.path_from_with_prefix(prefix(2), before);
+        assert!(!resumed.is_loop);
+        assert_eq!(resumed.corners[1..], path.corners[2..]);
+
+        // The obstruction at (6, 3) sends the guard back round to the start.
+        let approach = path.approaches().into_iter().find(|a| a.space == (6, 3)).unwrap();
+        assert_eq!(approach, Approach { space: (6, 3), dir: Left, corners_before: 4 });
+        let obstructed = AugmentedBoard::from(&board, 6, 3);
+        let before = GuardState::Present(6, 4, Left);
+        let resumed = obstructed.path_from_with_prefix(prefix(4), before);
+        assert!(resumed.is_loop);
+        assert!(obstructed.path_from(guard).is_loop);
+    }
+
+    #[test]
+    fn test_patrol() {
+        let (board, guard) = parse_input(TEST_INPUT);
+        let path = board.path_from(guard);
+
+        // One step per corner; the last corner on the path is where the guard walks off.
+        let mut patrol = simulation(TEST_INPUT);
+        assert_eq!(patrol.guard(), Some((6, 4, Up)));
+        assert_eq!(patrol.step(), StepResult::Running);
+        assert_eq!(patrol.guard(), Some((1, 4, Right)));
+        assert_eq!(patrol.run_until(|_| false), (path.corners.len() - 2, StepResult::Finished));
+        assert_eq!(patrol.guard(), None);
+        assert_eq!(patrol.step(), StepResult::Finished);
+    }
+
+    #[test]
+    fn test_layouts() {
+        let kernels = layouts(TEST_INPUT);
+        assert_eq!(kernels.len(), 3);
+        assert!(kernels.iter().all(|(_, walk)| walk() == 41));
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(render(TEST_INPUT), "....#.....
+....+---+#
+....|...|.
+..#.|...|.
+..+-+-+#|.
+..|.|.|.|.
+.#+-^-+-+.
+.+----++#.
+#+----+|..
+......#|..");
+    }
+
+    /// Reference board that moves the guard one cell per step, with no jump tables.
+    ///
+    /// The guard walks the board's (position, heading) state graph with [`guard_move`], so its
+    /// "corners" are every state along the path.
+    struct NaiveBoard {
+        obstructed: Vec<Vec<bool>>,
+        graph: WeightedGraph<Heading>
+    }
+
+    impl NaiveBoard {
+        fn from_input(input: &str) -> NaiveBoard {
+            let obstructed: Vec<Vec<bool>> = input.lines()
+                .map(|line| line.chars().map(|c| c == '#').collect())
+                .collect();
+            let graph = grid_with_headings(&obstructed, 1, 1, Turns::All, |&blocked| !blocked);
+            NaiveBoard { obstructed, graph }
+        }
+
+        /// The same board with an extra obstruction, which just blocks the step onto its cell
+        /// rather than needing a new graph.
+        fn with_obstruction(&self, row: usize, col: usize) -> ObstructedNaiveBoard<'_> {
+            ObstructedNaiveBoard { naive: self, obstruction: (row, col) }
+        }
+    }
+
+    impl Board for NaiveBoard {
+        fn next_state(&self, guard: GuardState) -> GuardState {
+            let GuardState::Present(i, j, dir) = guard else { return GuardState::Gone };
+            guard_move(&self.graph, (self.num_rows(), self.num_cols()), ((i, j), dir))
+        }
+
+        fn num_rows(&self) -> usize {
+            self.obstructed.len()
+        }
+
+        fn num_cols(&self) -> usize {
+            self.obstructed[0].len()
+        }
+    }
+
+    struct ObstructedNaiveBoard<'a> {
+        naive: &'a NaiveBoard,
+        obstruction: (usize, usize)
+    }
+
+    impl Board for ObstructedNaiveBoard<'_> {
+        fn next_state(&self, guard: GuardState) -> GuardState {
+            let blocked = |i, j, dir: Direction| {
+                dir.step((i, j), (self.num_rows(), self.num_cols())) == Some(self.obstruction)
+            };
+            match guard {
+                GuardState::Present(i, j, dir) if blocked(i, j, dir) => {
+                    GuardState::Present(i, j, dir.turn_right())
+                },
+                guard => self.naive.next_state(guard)
+            }
+        }
+
+        fn num_rows(&self) -> usize {
+            self.naive.num_rows()
+        }
+
+        fn num_cols(&self) -> usize {
+            self.naive.num_cols()
+        }
+    }
+
+    fn naive_part2(input: &str) -> usize {
+        let (_, guard) = parse_input(input);
+        let naive = NaiveBoard::from_input(input);
+
+        naive.path_from(guard).visited_spaces().into_iter()
+            .filter(|&(i, j)| GuardState::Present(i, j, Up) != guard)
+            .filter(|&(i, j)| naive.with_obstruction(i, j).path_from(guard).is_loop)
+            .count()
+    }
+
+    #[test]
+    fn test_naive_matches_sample() {
+        let (_, guard) = parse_input(TEST_INPUT);
+        assert_eq!(NaiveBoard::from_input(TEST_INPUT).path_from(guard).visited_spaces().len(), 41);
+        assert_eq!(naive_part2(TEST_INPUT), 6);
+    }
+
+    #[test]
+    fn test_against_naive() {
+        for seed in 0..50 {
+            let board = day06_board(&mut Rng::new(seed), 12, 15, 15);
+            let (_, guard) = parse_input(&board);
+            let visited = NaiveBoard::from_input(&board).path_from(guard).visited_spaces();
+
+            assert_eq!(part1(&board), visited.len(), "part 1 for board\n{board}");
+            assert_eq!(part2_resume(&board), naive_part2(&board), "part 2 for board\n{board}");
+            assert_eq!(part2_from_start(&board), naive_part2(&board), "part 2 for board\n{board}");
+        }
+    }
+
+    #[test]
+    fn test_corner_jumps_against_naive() {
+        //! Compare every start state and every extra obstruction on small, dense boards, so that
+        //! the guard regularly starts against, or exits next to, the board's edges.
+        for seed in 0..20 {
+            let board = day06_board(&mut Rng::new(seed), 5, 6, 25);
+            let (orig, _) = parse_input(&board);
+            let naive = NaiveBoard::from_input(&board);
+
+            let empty: Vec<(usize, usize)> = (0..orig.num_rows())
+                .cartesian_product(0..orig.num_cols())
+                .filter(|&(i, j)| !naive.obstructed[i][j])
+                .collect();
+
+            for (&(i, j), dir) in empty.iter().cartesian_product([Up, Down, Left, Right]) {
+                let start = GuardState::Present(i, j, dir);
+                let expected = naive.path_from(start);
+                let actual = orig.path_from(start);
+                assert_eq!(actual.is_loop, expected.is_loop, "{start:?} on\n{board}");
+                assert_eq!(actual.visited_spaces(), expected.visited_spaces(), "{start:?} on\n{board}");
+
+                for &(r, c) in empty.iter().filter(|&&pos| pos != (i, j)) {
+                    let expected = naive.with_obstruction(r, c).path_from(start);
+                    let actual = AugmentedBoard::from(&orig, r, c).path_from(start);
+                    let context = format!("{start:?} with obstruction at {:?} on\n{board}", (r, c));
+                    assert_eq!(actual.is_loop, expected.is_loop, "{context}");
+                    if !expected.is_loop {
+                        assert_eq!(actual.visited_spaces(), expected.visited_spaces(), "{context}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_scaling() {
+        let board = day06_board(&mut Rng::new(500), 500, 500, 5);
+        let (_, guard) = parse_input(&board);
+        let naive = NaiveBoard::from_input(&board).path_from(guard);
+
+        assert!(!naive.is_loop);
+        assert_eq!(part1(&board), naive.visited_spaces().len());
+        assert!(part2_resume(&board) > 0);
+    }
+}
@@ -0,0 +1,315 @@
+//! Day 7: Bridge Repair
+//!
+//! Decide whether the numbers of an equation can be joined by operators (evaluated left to right)
+//! to reach its test value.
+//!
+//! # Approach
+//!
+//! Work backwards from the test value: the last operator must be undoable on the last number (a
+//! subtraction that stays positive, an exact division, or stripping a matching suffix of digits for
+//! concatenation). This prunes almost every branch immediately, unlike trying every operator
+//! combination forwards. It also means the search never overflows: each undone operator leaves a
+//! smaller number. Only the final total is checked, since it may not fit in 64 bits.
+//!
+//! The search counts every choice of operators that works, and how often each operator appears in
+//! them, rather than stopping at the first. The `--stats` mode reports those counts.
+
+use std::fmt::Write;
+use itertools::Itertools;
+use nom::bytes::complete::tag;
+use nom::character::complete::space1;
+use nom::combinator::map;
+use nom::IResult;
+use nom::multi::separated_list1;
+use nom::sequence::separated_pair;
+use crate::numtheory::digits::{num_digits, split_at_digit};
+use crate::parse::{parse_lines, unsigned};
+
+struct Problem {
+    expected: u64,
+    nums: Vec<u64>
+}
+
+fn problem(input: &str) -> IResult<&str, Problem> {
+    map(
+        separated_pair(unsigned, tag(": "), separated_list1(space1, unsigned)),
+        |(expected, nums)| Problem { expected, nums },
+    )(input)
+}
+
+/// The problems, or the first line that isn't one (including numbers too big for 64 bits).
+fn parse_input(input: &str) -> Result<Vec<Problem>, String> {
+    parse_lines(input, problem)
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// An operator that can join two numbers of an equation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Op { Add, Mul, Concat }
+
+use Op::*;
+
+impl Op {
+    fn symbol(self) -> &'static str {
+        match self {
+            Add => "+",
+            Mul => "*",
+            Concat => "||"
+        }
+    }
+
+    /// The number that this operator joins with `operand` to give `result`, if there is one.
+    fn undo(self, result: u64, operand: u64) -> Option<u64> {
+        match self {
+            Add => try_sub(result, operand),
+            Mul => try_div(result, operand),
+            Concat => try_split(result, operand)
+        }
+    }
+}
+
+const PART1_OPS: &[Op] = &[Add, Mul];
+const PART2_OPS: &[Op] = &[Add, Mul, Concat];
+
+/// The ways of choosing operators that solve a problem.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+struct Solutions {
+    /// How many choices of operators reach the test value.
+    count: usize,
+    /// How many times each operator is used, over all of those choices, indexed by `Op as usize`.
+    uses: [usize; 3]
+}
+
+impl Solutions {
+    fn add(&mut self, other: &Solutions) {
+        self.count += other.count;
+        self.uses.iter_mut().zip(other.uses).for_each(|(a, b)| *a += b);
+    }
+}
+
+/// The ways of joining `nums` with `ops` to make `target`.
+fn solutions_for(target: u64, nums: &[u64], ops: &[Op]) -> Solutions {
+    // Every operator undone here leaves a smaller number than it started with, so working
+    // backwards can't overflow however many operands there are.
+    let Some((&last, rest)) = nums.split_last() else { return Solutions::default() };
+    if rest.is_empty() {
+        return Solutions { count: usize::from(target == last), uses: [0; 3] };
+    }
+
+    let mut result = Solutions::default();
+    for &op in ops {
+        if let Some(earlier) = op.undo(target, last) {
+            let solutions = solutions_for(earlier, rest, ops);
+            result.uses[op as usize] += solutions.count;
+            result.add(&solutions);
+        }
+    }
+
+    result
+}
+
+fn solutions(problem: &Problem, ops: &[Op]) -> Solutions {
+    solutions_for(problem.expected, &problem.nums, ops)
+}
+
+fn try_sub(result: u64, addend: u64) -> Option<u64> {
+    if result <= addend { None } else { Some(result - addend) }
+}
+
+fn try_div(result: u64, divisor: u64) -> Option<u64> {
+    // Multiplying by 0 can't be undone; AoC's operands are all positive anyway.
+    if divisor != 0 && result.is_multiple_of(divisor) { Some(result / divisor) } else { None }
+}
+
+fn try_split(joined: u64, second: u64) -> Option<u64> {
+    if second != 0 && second >= joined {
+        return None;
+    }
+
+    let (first, suffix) = split_at_digit(joined, num_digits(second));
+    if suffix == second { Some(first) } else { None }
+}
+
+/// Sum of the test values of the problems solvable with `ops`; an error if it overflows 64 bits.
+fn total(input: &str, ops: &[Op]) -> Result<u64, String> {
+    parse_input(input)?.into_iter()
+        .filter(|p| solutions(p, ops).count > 0)
+        .try_fold(0u64, |total, p| total.checked_add(p.expected))
+        .ok_or_else(|| "Total calibration result overflows 64 bits".to_owned())
+}
+
+/// Total of the test values reachable with `+` and `*`.
+pub fn part1(input: &str) -> Result<u64, String> {
+    total(input, PART1_OPS)
+}
+
+/// Total of the test values reachable with `+`, `*` and `||` (concatenation).
+pub fn part2(input: &str) -> Result<u64, String> {
+    total(input, PART2_OPS)
+}
+
+/// For each part's operators, how many equations have each number of solutions, and how often
+/// each operator is used over all the solutions.
+pub fn stats(input: &str) -> Result<String, String> {
+    let problems = parse_input(input)?;
+    let mut out = String::new();
+
+    for (part, ops) in [(1, PART1_OPS), (2, PART2_OPS)] {
+        let all: Vec<Solutions> = problems.iter().map(|p| solutions(p, ops)).collect();
+        let symbols = ops.iter().map(|op| op.symbol()).join(" ");
+        writeln!(out, "Part {part} ({symbols}):").unwrap();
+
+        writeln!(out, "{:>10} {:>10}", "solutions", "equations").unwrap();
+        for (count, equations) in all.iter().map(|s| s.count).counts().into_iter().sorted() {
+            writeln!(out, "{count:>10} {equations:>10}").unwrap();
+        }
+
+        let mut totals = Solutions::default();
+        all.iter().for_each(|s| totals.add(s));
+        let uses = ops.iter().map(|&op| format!("{} {}", op.symbol(), totals.uses[op as usize]));
+        writeln!(out, "{} solutions, using {}", totals.count, uses.format(", ")).unwrap();
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gen::Rng;
+    use crate::numtheory::digits::concat;
+    use super::{parse_input, part1, part2, solutions, stats, try_split, Problem, Solutions};
+    use super::{PART1_OPS, PART2_OPS};
+
+    const TEST_INPUT: &str = "190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20";
+
+    crate::test_line_endings! { part1, part2; TEST_INPUT }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), Ok(3749));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), Ok(11387));
+    }
+
+    #[test]
+    fn test_solutions() {
+        let problems = parse_input(TEST_INPUT).unwrap();
+        let counts: Vec<usize> = problems.iter().map(|p| solutions(p, PART2_OPS).count).collect();
+        assert_eq!(counts, vec![1, 2, 0, 1, 1, 0, 1, 0, 1]);
+
+        // 81 + 40 * 27 and 81 * 40 + 27.
+        assert_eq!(solutions(&problems[1], PART1_OPS), Solutions { count: 2, uses: [2, 2, 0] });
+        // 6 * 8 || 6 * 15
+        assert_eq!(solutions(&problems[4], PART2_OPS), Solutions { count: 1, uses: [0, 2, 1] });
+        assert_eq!(solutions(&problems[2], PART2_OPS), Solutions::default());
+    }
+
+    #[test]
+    fn test_stats() {
+        let expected = "Part 1 (+ *):
+ solutions  equations
+         0          6
+         1          2
+         2          1
+4 solutions, using + 4, * 4
+Part 2 (+ * ||):
+ solutions  equations
+         0          3
+         1          5
+         2          1
+7 solutions, using + 5, * 6, || 3
+";
+        assert_eq!(stats(TEST_INPUT), Ok(expected.to_owned()));
+    }
+
+    #[test]
+    fn test_concatenation_near_max() {
+        assert_eq!(try_split(1_999_999_999_999_999_999, 999_999_999_999_999_999), Some(1));
+        assert_eq!(try_split(u64::MAX, 18_446_744_073_709_551_614), None);
+        assert_eq!(try_split(u64::MAX, 8_446_744_073_709_551_615), Some(1));
+        assert_eq!(try_split(120, 0), Some(12));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(part1("18446744073709551615: 18446744073709551615"), Ok(u64::MAX));
+        assert!(part1("18446744073709551615: 18446744073709551615\n1: 1").is_err());
+        assert!(part2("18446744073709551616: 1 2").unwrap_err().contains("line 1"));
+        assert!(part1("1: 0\n0: 0 0").is_ok());
+    }
+
+    /// Every value reachable by applying `+`, `*` and `||` forwards, where it fits in 64 bits.
+    fn forwards(nums: &[u64]) -> Vec<u64> {
+        nums[1..].iter().fold(vec![nums[0] as u128], |acc, &x| {
+            let x = x as u128;
+            acc.into_iter()
+                .flat_map(|y| {
+                    let shift = 10u128.pow(x.to_string().len() as u32);
+                    [y + x, y * x, y.checked_mul(shift).map_or(u128::MAX, |z| z + x)]
+                })
+                .filter(|&z| z <= u64::MAX as u128)
+                .collect()
+        })
+        .into_iter()
+        .map(|z| z as u64)
+        .collect()
+    }
+
+    #[test]
+    fn test_stress_near_max() {
+        let mut rng = Rng::new(7);
+
+        for _ in 0..200 {
+            // Many small operands, and a few huge ones, so that totals land near u64::MAX.
+            let len = rng.between(2, 9);
+            let nums: Vec<u64> = (0..len)
+                .map(|_| match rng.chance(1, 4) {
+                    true => rng.next_u64() >> rng.below(8),
+                    false => rng.between(1, 999) as u64
+                })
+                .collect();
+
+            let reachable = forwards(&nums);
+            for &expected in reachable.iter().filter(|&&z| z > u64::MAX / 1000).take(20) {
+                let problem = Problem { expected, nums: nums.clone() };
+                assert!(solutions(&problem, PART2_OPS).count > 0, "{expected}: {nums:?}");
+            }
+
+            // Nearby unreachable targets are rejected.
+            let near = u64::MAX - rng.below(1000) as u64;
+            let problem = Problem { expected: near, nums: nums.clone() };
+            assert_eq!(solutions(&problem, PART2_OPS).count > 0, reachable.contains(&near));
+        }
+
+        // Fifteen or more operands, with a random choice of operators that stays below u64::MAX.
+        for _ in 0..50 {
+            let len = rng.between(15, 25);
+            let nums: Vec<u64> = (0..len).map(|_| rng.between(1, 99) as u64).collect();
+            let expected = nums[1..].iter().fold(nums[0], |y, &x| {
+                let options: Vec<u64> = [y.checked_mul(x), concat(y, x)]
+                    .into_iter()
+                    .flatten()
+                    .filter(|&z| z < u64::MAX / 100)
+                    .collect();
+                // Adding keeps well clear of overflow, whatever else won't fit.
+                match rng.below(options.len() + 1) {
+                    0 => y + x,
+                    k => options[k - 1]
+                }
+            });
+            assert!(solutions(&Problem { expected, nums }, PART2_OPS).count > 0);
+        }
+    }
+}
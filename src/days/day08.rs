@@ -0,0 +1,225 @@
+//! Day 8: Resonant Collinearity
+//!
+//! Antennas of the same frequency create antinodes along the lines through each pair of them.
+//!
+//! # Approach
+//!
+//! For every pair of same-frequency antennas, step along their difference vector from each end,
+//! keeping the points that are on the map. The number of pairs is quadratic in the number of
+//! antennas per frequency, which is small.
+//!
+//! Antinodes are kept grouped by frequency, so that `--render` can draw each part's map as in the
+//! puzzle's examples and count the antinodes of each frequency.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::ops::{Add, Sub};
+use itertools::Itertools;
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+struct Vector(isize, isize);
+
+impl Add<Vector> for Vector {
+    type Output = Vector;
+
+    fn add(self, rhs: Vector) -> Self::Output {
+        Vector(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl Sub<Vector> for Vector {
+    type Output = Vector;
+
+    fn sub(self, rhs: Vector) -> Self::Output {
+        Vector(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+/// The antinodes of each frequency.
+type Antinodes = BTreeMap<char, HashSet<Vector>>;
+
+/// The distinct antinodes of any frequency.
+fn all_antinodes(antinodes: &Antinodes) -> HashSet<Vector> {
+    antinodes.values().flatten().copied().collect()
+}
+
+#[derive(Debug)]
+struct Board {
+    rows: usize,
+    cols: usize,
+    antennas: HashMap<char, Vec<Vector>>
+}
+
+impl Board {
+    fn contains(&self, v: &Vector) -> bool {
+        v.0 >= 0 && v.1 >= 0 && v.0 < self.rows as isize && v.1 < self.cols as isize
+    }
+
+    fn pair_antinodes(&self) -> Antinodes {
+        self.antennas.iter().map(|(&freq, vs)| {
+                let antinodes = vs.iter().combinations(2).flat_map(|vec| {
+                    let v = vec[0];
+                    let u = vec[1];
+                    let delta = *v - *u;
+                    vec![*u - delta, *v + delta]
+                });
+
+                (freq, antinodes.filter(|v| self.contains(v)).collect())
+            })
+            .collect()
+    }
+
+    fn linear_antinodes(&self) -> Antinodes {
+        self.antennas.iter().map(|(&freq, vs)| {
+                let antinodes = vs.iter().combinations(2).flat_map(|vec| {
+                    let v = vec[0];
+                    let u = vec[1];
+                    let delta = *v - *u;
+
+                    let mut result = Vec::new();
+                    let mut cur = *u;
+                    while self.contains(&(cur - delta)) {
+                        cur = cur - delta;
+                    }
+                    while self.contains(&cur) {
+                        result.push(cur);
+                        cur = cur + delta;
+                    }
+
+                    result
+                });
+
+                (freq, antinodes.collect())
+            })
+            .collect()
+    }
+
+    /// The map with `antinodes` marked `#`, except where there's an antenna.
+    fn draw(&self, antinodes: &Antinodes) -> String {
+        let mut cells = vec![vec!['.'; self.cols]; self.rows];
+        let antennas = self.antennas.iter()
+            .flat_map(|(&freq, vs)| vs.iter().map(move |v| (freq, v)));
+        let marks = antinodes.values().flatten().map(|v| ('#', v)).chain(antennas);
+        for (c, &Vector(i, j)) in marks {
+            cells[i as usize][j as usize] = c;
+        }
+
+        cells.into_iter().map(String::from_iter).join("\n")
+    }
+}
+
+fn parse_input(input: &str) -> Board {
+    let antennas: HashMap<char, Vec<Vector>> = input.lines().enumerate()
+        .flat_map(|(i, line)| {
+            line.chars().enumerate().filter_map(move |(j, c)| {
+                if c == '.' { None } else { Some((c, Vector(i as isize, j as isize))) }
+            })
+        })
+        .fold(HashMap::new(), |mut acc, (c, v)| {
+            acc.entry(c).or_default().push(v);
+            acc
+        });
+
+    let rows = input.lines().take_while(|line| !line.is_empty()).count();
+    let cols = input.lines().next().unwrap().len();
+
+    Board { rows, cols, antennas }
+}
+
+/// Number of distinct antinodes one step beyond each end of each pair.
+pub fn part1(input: &str) -> usize {
+    let board = parse_input(input);
+    all_antinodes(&board.pair_antinodes()).len()
+}
+
+/// Number of distinct antinodes anywhere along the line through each pair, including the antennas
+/// themselves.
+pub fn part2(input: &str) -> usize {
+    let board = parse_input(input);
+    all_antinodes(&board.linear_antinodes()).len()
+}
+
+/// Each part's map with its antinodes marked, and the number of antinodes of each frequency.
+pub fn render(input: &str) -> String {
+    let board = parse_input(input);
+    let mut out = String::new();
+
+    for (part, antinodes) in [(1, board.pair_antinodes()), (2, board.linear_antinodes())] {
+        writeln!(out, "Part {part}: {} antinodes", all_antinodes(&antinodes).len()).unwrap();
+        writeln!(out, "{}", board.draw(&antinodes)).unwrap();
+        for (freq, positions) in &antinodes {
+            writeln!(out, "{freq}: {}", positions.len()).unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{part1, part2, render};
+
+    const TEST_INPUT: &str = "............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............";
+
+    crate::test_line_endings! { part1, part2; TEST_INPUT }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 14);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), 34);
+    }
+
+    #[test]
+    fn test_render() {
+        let expected = "Part 1: 14 antinodes
+......#....#
+...#....0...
+....#0....#.
+..#....0....
+....0....#..
+.#....A.....
+...#........
+#......#....
+........A...
+.........A..
+..........#.
+..........#.
+0: 10
+A: 5
+
+Part 2: 34 antinodes
+##....#....#
+.#.#....0...
+..#.#0....#.
+..##...0....
+....0....#..
+.#...#A....#
+...#..#.....
+#....#.#....
+..#.....A...
+....#....A..
+.#........#.
+...#......##
+0: 21
+A: 16
+
+";
+        assert_eq!(render(TEST_INPUT), expected);
+    }
+}
@@ -0,0 +1,321 @@
+//! Day 9: Disk Fragmenter
+//!
+//! A disk map alternates file and free-space sizes; files are compacted towards the start.
+//!
+//! # Approach
+//!
+//! The disk is kept as a list of segments rather than individual blocks, and a file's contribution
+//! to the checksum is computed in closed form from its position and size, using triangular numbers.
+//! For whole-file moves, the free space is a `RangeSet` of block ranges.
+//!
+//! Besides the puzzle's one digit per segment, the disk map can be given as sizes separated by
+//! commas or whitespace, which may be bigger than 9; `cargo bench --bench days` uses this to
+//! time both parts on large generated disks.
+
+use std::cmp::min;
+use crate::numtheory::triangular;
+use crate::ranges::RangeSet;
+
+#[derive(Copy, Clone, Debug)]
+struct Segment { file_id: Option<usize>, size: usize, index: usize }
+
+#[derive(Debug)]
+struct Disk {
+    segments: Vec<Segment>,
+    /// Free blocks, for moving whole files; moving single blocks works on the gap segments instead.
+    free: RangeSet<usize>,
+    len: usize
+}
+
+/// The checksum of `size` blocks of file `file_id` starting at block `index`: `file_id` times the
+/// sum of the positions `index..index + size`, in closed form, so any size works.
+fn segment_checksum(file_id: usize, index: usize, size: usize) -> usize {
+    file_id * (size * index + triangular(size) - size)
+}
+
+/// The segment sizes in a disk map: one digit each (the puzzle's format), or numbers separated by
+/// commas or whitespace, e.g. `2,13,0,9`.
+fn segment_sizes(input: &str) -> Vec<usize> {
+    let input = input.trim();
+    let is_delimiter = |c: char| c == ',' || c.is_whitespace();
+
+    if input.contains(is_delimiter) {
+        input.split(is_delimiter)
+            .filter(|size| !size.is_empty())
+            .map(|size| size.parse().expect("Segment sizes must be numbers"))
+            .collect()
+    }
+    else {
+        input.chars()
+            .map(|c| c.to_digit(10).expect("Segment sizes must be digits") as usize)
+            .collect()
+    }
+}
+
+impl Disk {
+    fn read(input: &str) -> Disk {
+        let (segments, len) = segment_sizes(input).into_iter()
+            .enumerate()
+            .fold((Vec::new(), 0), |(mut acc, index), (i, size)| {
+                let file_id = if i % 2 == 0 { Some(i / 2) } else { None };
+                acc.push(Segment { file_id, size, index });
+                (acc, index + size)
+            });
+
+        let free = segments.iter()
+            .filter(|seg| seg.file_id.is_none())
+            .map(|seg| seg.index..seg.index + seg.size)
+            .collect();
+
+        Disk { segments, free, len }
+    }
+
+    /// One character per block, as in the puzzle: the ID of the file there (mod 10, since the
+    /// examples only use single digits), or `.` for free space.
+    fn render_blocks(&self) -> String {
+        let mut blocks = vec!['.'; self.len];
+        for seg in self.segments.iter() {
+            if let Some(file_id) = seg.file_id {
+                let c = char::from_digit((file_id % 10) as u32, 10).unwrap();
+                blocks[seg.index..seg.index + seg.size].fill(c);
+            }
+        }
+
+        blocks.into_iter().collect()
+    }
+
+    /// Move the last file block into the leftmost free block, as in part 1.
+    ///
+    /// Returns `false` (without changing anything) once there are no gaps left between files.
+    #[cfg(test)]
+    fn move_block(&mut self) -> bool {
+        let Some(last) = (0..self.segments.len())
+            .filter(|&k| self.segments[k].file_id.is_some() && self.segments[k].size > 0)
+            .max_by_key(|&k| self.segments[k].index) else { return false };
+        let Some(gap) = self.segments.iter()
+            .position(|seg| seg.file_id.is_none() && seg.size > 0) else { return false };
+
+        let (last_seg, gap_seg) = (self.segments[last], self.segments[gap]);
+        if gap_seg.index > last_seg.index {
+            return false;
+        }
+
+        self.segments[last].size -= 1;
+        self.segments[gap].size -= 1;
+        self.segments[gap].index += 1;
+        self.segments.push(Segment { file_id: last_seg.file_id, size: 1, index: gap_seg.index });
+
+        true
+    }
+
+    /// Move the whole file `file_id` to the leftmost gap before it that can hold it, as in part 2.
+    ///
+    /// Returns whether the file moved.
+    fn move_file(&mut self, file_id: usize) -> bool {
+        let file = self.segments.iter()
+            .position(|seg| seg.file_id == Some(file_id))
+            .expect("No such file");
+        let Segment { size, index, .. } = self.segments[file];
+
+        let gap = self.free.iter()
+            .take_while(|gap| gap.start < index)
+            .find(|gap| gap.len() >= size);
+
+        match gap {
+            Some(gap) => {
+                self.free.remove(gap.start..gap.start + size);
+                self.free.insert(index..index + size);
+                self.segments[file].index = gap.start;
+                true
+            },
+            None => false
+        }
+    }
+
+    fn checksum(&self) -> usize {
+        self.segments.iter()
+            .filter_map(|seg| {
+                seg.file_id.map(|file_id| segment_checksum(file_id, seg.index, seg.size))
+            })
+            .sum()
+    }
+}
+
+
+/// Checksum after moving blocks one at a time from the end into the leftmost free space, using two
+/// pointers that meet in the middle.
+pub fn part1(input: &str) -> usize {
+    let mut disk = Disk::read(input);
+
+    let mut total = 0;
+    let mut i = 0;
+    let mut j = disk.segments.len() - 1;
+
+    while i < j {
+        let (seg1, seg2) = (disk.segments[i], disk.segments[j]);
+        match (seg1, seg2) {
+            (Segment { size: 0, ..}, _) => { i += 1; },
+            (_, Segment { size: 0, ..}) => { j -= 1; },
+            (_, Segment { file_id: None, .. }) => { j -= 1; },
+            (Segment { file_id: Some(file_id), size, index}, _) => {
+                total += segment_checksum(file_id, index, size);
+                i += 1;
+            },
+            (
+                Segment { file_id: None, size: gap_size, index: gap_index},
+                Segment { file_id: Some(file_id), size: file_size, ..}
+            ) => {
+                let size = min(file_size, gap_size);
+                disk.segments[i].size -= size;
+                disk.segments[i].index += size;
+                disk.segments[j].size -= size;
+                total += segment_checksum(file_id, gap_index, size);
+            }
+        }
+    }
+
+    if let Segment{ file_id: Some(file_id), size, index } = disk.segments[i] {
+        total += segment_checksum(file_id, index, size);
+    }
+
+    total
+}
+
+/// Checksum after moving whole files, from the highest ID down, into the leftmost gap big enough to
+/// hold them.
+pub fn part2(input: &str) -> usize {
+    let mut disk = Disk::read(input);
+    let num_files = disk.segments.len().div_ceil(2);
+
+    for file_id in (0..num_files).rev() {
+        disk.move_file(file_id);
+    }
+
+    disk.checksum()
+}
+
+/// The disk's blocks before and after part 2's compaction.
+pub fn render(input: &str) -> String {
+    let mut disk = Disk::read(input);
+    let before = disk.render_blocks();
+
+    let num_files = disk.segments.len().div_ceil(2);
+    for file_id in (0..num_files).rev() {
+        disk.move_file(file_id);
+    }
+
+    format!("{before}\n{}", disk.render_blocks())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{part1, part2, segment_checksum, segment_sizes, Disk};
+
+    const TEST_INPUT: &str = "2333133121414131402";
+
+    crate::test_line_endings! { part1, part2; TEST_INPUT }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 1928);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), 2858);
+    }
+
+    #[test]
+    fn test_segment_sizes() {
+        assert_eq!(segment_sizes("12345\n"), vec![1, 2, 3, 4, 5]);
+        assert_eq!(segment_sizes("2,13,0, 9\n"), vec![2, 13, 0, 9]);
+        assert_eq!(segment_sizes("12 0\n345"), vec![12, 0, 345]);
+    }
+
+    #[test]
+    fn test_delimited() {
+        let delimited = TEST_INPUT.chars().map(String::from).collect::<Vec<_>>().join(",");
+        assert_eq!(part1(&delimited), 1928);
+        assert_eq!(part2(&delimited), 2858);
+
+        // File 1 has 12 blocks, which render as 12 digits.
+        let mut disk = Disk::read("2,3,12,1,2");
+        assert_eq!(disk.render_blocks(), "00...111111111111.22");
+        assert!(disk.move_file(2));
+        assert!(!disk.move_file(1));
+        assert_eq!(disk.render_blocks(), "0022.111111111111...");
+    }
+
+    #[test]
+    fn test_segment_checksum() {
+        assert_eq!(segment_checksum(7, 3, 0), 0);
+        assert_eq!(segment_checksum(0, 3, 5), 0);
+        assert_eq!(segment_checksum(2, 4, 1), 8);
+
+        for (file_id, index, size) in [(3, 0, 9), (5, 12, 10), (41, 1000, 37), (1, 7, 250)] {
+            let blocks: usize = (index..index + size).map(|pos| file_id * pos).sum();
+            assert_eq!(segment_checksum(file_id, index, size), blocks);
+        }
+    }
+
+    #[test]
+    fn test_block_moves() {
+        let mut disk = Disk::read("12345");
+        let mut states = vec![disk.render_blocks()];
+        while disk.move_block() {
+            states.push(disk.render_blocks());
+        }
+
+        assert_eq!(states, vec![
+            "0..111....22222",
+            "02.111....2222.",
+            "022111....222..",
+            "0221112...22...",
+            "02211122..2....",
+            "022111222......"
+        ]);
+
+        let mut disk = Disk::read(TEST_INPUT);
+        let mut states = vec![disk.render_blocks()];
+        while disk.move_block() {
+            states.push(disk.render_blocks());
+        }
+
+        assert_eq!(states, vec![
+            "00...111...2...333.44.5555.6666.777.888899",
+            "009..111...2...333.44.5555.6666.777.88889.",
+            "0099.111...2...333.44.5555.6666.777.8888..",
+            "00998111...2...333.44.5555.6666.777.888...",
+            "009981118..2...333.44.5555.6666.777.88....",
+            "0099811188.2...333.44.5555.6666.777.8.....",
+            "009981118882...333.44.5555.6666.777.......",
+            "0099811188827..333.44.5555.6666.77........",
+            "00998111888277.333.44.5555.6666.7.........",
+            "009981118882777333.44.5555.6666...........",
+            "009981118882777333644.5555.666............",
+            "00998111888277733364465555.66.............",
+            "0099811188827773336446555566.............."
+        ]);
+        assert_eq!(disk.checksum(), part1(TEST_INPUT));
+    }
+
+    #[test]
+    fn test_file_moves() {
+        let mut disk = Disk::read(TEST_INPUT);
+        let mut states = vec![disk.render_blocks()];
+        for file_id in (0..10).rev() {
+            if disk.move_file(file_id) {
+                states.push(disk.render_blocks());
+            }
+        }
+
+        assert_eq!(states, vec![
+            "00...111...2...333.44.5555.6666.777.888899",
+            "0099.111...2...333.44.5555.6666.777.8888..",
+            "0099.1117772...333.44.5555.6666.....8888..",
+            "0099.111777244.333....5555.6666.....8888..",
+            "00992111777.44.333....5555.6666.....8888.."
+        ]);
+        assert_eq!(disk.checksum(), part2(TEST_INPUT));
+    }
+}
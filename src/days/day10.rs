@@ -0,0 +1,175 @@
+//! Day 10: Hoof It
+//!
+//! Hiking trails on a topographic map climb from height 0 to 9 one step at a time.
+//!
+//! # Approach
+//!
+//! The map becomes a DAG with edges from each cell to neighbors exactly one higher. A trailhead's
+//! score is the number of peaks a breadth-first walk from it reaches. Its rating is the number of
+//! paths to any peak, which [`count_paths_dag`] counts for every trailhead at once by dynamic
+//! programming in reverse topological order.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+use itertools::Itertools;
+use crate::graph::{count_paths_dag, to_dot, DotKind};
+use crate::hash::{HashMap, HashSet};
+use crate::prelude::*;
+
+struct Digraph {
+    adj_list: HashMap<(usize, usize), Vec<(usize, usize)>>,
+    zeroes: Vec<(usize, usize)>,
+    nines: HashSet<(usize, usize)>
+}
+
+impl Digraph {
+    /// The peaks reachable from `node`.
+    fn peaks_from(&self, node: (usize, usize)) -> HashSet<(usize, usize)> {
+        let mut peaks = HashSet::default();
+        let mut seen = HashSet::from_iter([node]);
+        let mut queue = VecDeque::from([node]);
+
+        while let Some(v) = queue.pop_front() {
+            if self.nines.contains(&v) {
+                peaks.insert(v);
+            }
+            for &u in self.adj_list.get(&v).map_or(&[][..], Vec::as_slice) {
+                if seen.insert(u) {
+                    queue.push_back(u);
+                }
+            }
+        }
+
+        peaks
+    }
+
+    /// Score, rating, and reachable peaks of every trailhead, in reading order.
+    fn trailheads(&self) -> Vec<Trailhead> {
+        let ratings = count_paths_dag(&self.adj_list, &self.zeroes, &self.nines)
+            .expect("Trails only climb, so can't loop");
+
+        self.zeroes.iter().zip(ratings)
+            .map(|(&pos, rating)| {
+                let nines = self.peaks_from(pos);
+                Trailhead { pos, score: nines.len(), rating, nines }
+            })
+            .collect()
+    }
+}
+
+struct Trailhead {
+    pos: (usize, usize),
+    /// Number of distinct peaks reachable from this trailhead.
+    score: usize,
+    /// Number of distinct trails starting at this trailhead.
+    rating: usize,
+    nines: HashSet<(usize, usize)>
+}
+
+fn parse_input(input: &str) -> Digraph {
+    let topo = Grid::parse(input, |c| c.to_digit(10).unwrap() as usize);
+
+    let mut zeroes = Vec::new();
+    let mut nines = HashSet::default();
+    let mut adj_list: HashMap<(usize, usize), Vec<(usize, usize)>>= HashMap::default();
+
+    for (pos, &val) in topo.iter() {
+        if val == 0 {
+            zeroes.push(pos);
+        }
+
+        if val == 9 {
+            nines.insert(pos);
+        }
+
+        topo.neighbors(pos).filter(|&next| topo[next] == val + 1)
+            .for_each(|next| adj_list.entry(pos).or_default().push(next));
+    }
+
+    Digraph { adj_list, zeroes, nines }
+}
+
+/// Sum of trailhead scores: the number of distinct peaks reachable from each trailhead.
+pub fn part1(input: &str) -> usize {
+    parse_input(input).trailheads().iter().map(|t| t.score).sum()
+}
+
+/// Sum of trailhead ratings: the number of distinct trails starting at each trailhead.
+pub fn part2(input: &str) -> usize {
+    parse_input(input).trailheads().iter().map(|t| t.rating).sum()
+}
+
+/// Per-trailhead breakdown of the part 1 and part 2 totals.
+pub fn report(input: &str) -> String {
+    let mut result = String::new();
+
+    for trailhead in parse_input(input).trailheads() {
+        let (i, j) = trailhead.pos;
+        let nines = trailhead.nines.iter().sorted().map(|(x, y)| format!("{x},{y}")).join(" ");
+        writeln!(
+            result,
+            "Trailhead {i},{j}: score {}, rating {}, peaks {nines}",
+            trailhead.score,
+            trailhead.rating
+        ).unwrap();
+    }
+
+    result
+}
+
+/// Graphviz rendering of the trail DAG, with the trailheads and peaks highlighted.
+pub fn dot(input: &str) -> String {
+    let digraph = parse_input(input);
+
+    let mut edges: Vec<((usize, usize), (usize, usize))> = digraph.adj_list.iter()
+        .flat_map(|(&v, us)| us.iter().map(move |&u| (v, u)))
+        .collect();
+    edges.sort();
+
+    let highlighted = digraph.zeroes.iter().chain(digraph.nines.iter()).cloned().collect();
+
+    let label = |&(i, j): &(usize, usize)| format!("{i},{j}");
+    to_dot(DotKind::Directed, edges, label, &highlighted, &HashSet::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hash::HashSet;
+    use super::{parse_input, part1, part2};
+
+    const TEST_INPUT: &str = "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732";
+
+    crate::test_line_endings! { part1, part2; TEST_INPUT }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 36);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), 81);
+    }
+
+    #[test]
+    fn test_trailheads() {
+        let trailheads = parse_input(TEST_INPUT).trailheads();
+
+        let scores: Vec<usize> = trailheads.iter().map(|t| t.score).collect();
+        assert_eq!(scores, vec![5, 6, 5, 3, 1, 3, 5, 3, 5]);
+
+        let ratings: Vec<usize> = trailheads.iter().map(|t| t.rating).collect();
+        assert_eq!(ratings, vec![20, 24, 10, 4, 1, 4, 5, 8, 5]);
+
+        let expected = HashSet::from_iter([(0, 1), (3, 0), (3, 4), (4, 5), (5, 4)]);
+        assert_eq!(trailheads[0].pos, (0, 2));
+        assert_eq!(trailheads[0].nines, expected);
+    }
+}
@@ -0,0 +1,253 @@
+//! Day 11: Plutonian Pebbles
+//!
+//! Stones change on each blink, and some split in two.
+//!
+//! # Approach
+//!
+//! Stones never interact and their order doesn't matter for the count, so only the number of stones
+//! with each engraving is tracked. There turn out to be few distinct engravings, so each blink is
+//! cheap even when there are trillions of stones.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use itertools::Itertools;
+use nom::character::complete::{digit1, space1};
+use nom::combinator::map_res;
+use nom::IResult;
+use nom::multi::separated_list1;
+use crate::linalg::WrappingMatrix;
+use crate::numtheory::digits::{num_digits, split_at_digit};
+
+crate::day_args! { blinks: usize = 75 }
+
+fn parse_input(input: &str) -> Vec<u64> {
+    let number = map_res(digit1, |s: &str| s.parse());
+    let result: IResult<&str, Vec<u64>> = separated_list1(space1, number)(input);
+    result.unwrap().1
+}
+
+fn stones_after_blink(stone: u64) -> Vec<u64> {
+    if stone == 0 {
+        vec![1]
+    } else {
+        let d = num_digits(stone);
+        if d.is_multiple_of(2) {
+            let (left, right) = split_at_digit(stone, d / 2);
+            vec![left, right]
+        } else {
+            vec![2024 * stone]
+        }
+    }
+}
+
+fn count_after_blinks(num_blinks: usize, stones: Vec<u64>) -> usize {
+    let mut stone_counts = stones.into_iter().counts();
+
+    for _ in 0..num_blinks {
+        let mut new_counts = HashMap::new();
+        for (num, count) in stone_counts.into_iter() {
+            let new_stones = stones_after_blink(num);
+            new_stones.into_iter().for_each(|n| {
+                *new_counts.entry(n).or_insert(0) += count
+            });
+        }
+        stone_counts = new_counts;
+    }
+
+    stone_counts.values().sum()
+}
+
+/// Every engraving reachable from `stones`, mapped to what a stone with it turns into on a blink.
+fn transitions(stones: &[u64]) -> HashMap<u64, Vec<u64>> {
+    let mut graph = HashMap::new();
+    let mut stack = stones.to_vec();
+
+    while let Some(stone) = stack.pop() {
+        if graph.contains_key(&stone) {
+            continue;
+        }
+        let next = stones_after_blink(stone);
+        stack.extend(next.iter().copied());
+        graph.insert(stone, next);
+    }
+
+    graph
+}
+
+/// The engravings that keep turning up however long the stones blink: what's left after
+/// repeatedly discarding engravings that nothing (else still in the set) turns into.
+fn recurrent(graph: &HashMap<u64, Vec<u64>>) -> HashSet<u64> {
+    let mut in_degree: HashMap<u64, usize> = graph.keys().map(|&v| (v, 0)).collect();
+    graph.values().flatten().for_each(|next| *in_degree.get_mut(next).unwrap() += 1);
+
+    let mut queue: VecDeque<u64> = in_degree.iter()
+        .filter(|&(_, &d)| d == 0)
+        .map(|(&v, _)| v)
+        .collect();
+    while let Some(v) = queue.pop_front() {
+        in_degree.remove(&v);
+        for next in graph[&v].iter() {
+            let d = in_degree.get_mut(next).unwrap();
+            *d -= 1;
+            if *d == 0 {
+                queue.push_back(*next);
+            }
+        }
+    }
+
+    in_degree.into_keys().collect()
+}
+
+/// One blink of the stones counted in `counts`, with counts modulo 2^64.
+fn blink_counts(graph: &HashMap<u64, Vec<u64>>, counts: HashMap<u64, u64>) -> HashMap<u64, u64> {
+    let mut new_counts = HashMap::new();
+    for (v, count) in counts {
+        for &next in graph[&v].iter() {
+            let c = new_counts.entry(next).or_insert(0u64);
+            *c = c.wrapping_add(count);
+        }
+    }
+
+    new_counts
+}
+
+/// Number of stones, modulo 2^64, after the stones in `counts` (all engraved with values in
+/// `core`) blink `num_blinks` times, via a power of the core's transition matrix.
+fn count_by_matrix(
+    graph: &HashMap<u64, Vec<u64>>,
+    core: &HashSet<u64>,
+    counts: &HashMap<u64, u64>,
+    num_blinks: usize
+) -> u64 {
+    let values: Vec<u64> = core.iter().copied().sorted().collect();
+    let index: HashMap<u64, usize> = values.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+
+    let mut matrix = WrappingMatrix::zero(values.len());
+    for (i, v) in values.iter().enumerate() {
+        for next in graph[v].iter() {
+            matrix[(index[next], i)] += 1;
+        }
+    }
+
+    let initial: Vec<u64> = values.iter().map(|v| counts.get(v).copied().unwrap_or(0)).collect();
+    matrix.pow(num_blinks).mul_vec(&initial)
+        .into_iter()
+        .fold(0, u64::wrapping_add)
+}
+
+/// Number of stones after `num_blinks` blinks, modulo 2^64: exact for the 75 blinks of part 2, and
+/// fast for thousands of blinks or (on small inputs) trillions.
+///
+/// Only finitely many engravings are reachable, and all but a recurrent core of them soon split
+/// into smaller ones for good. So this blinks one step at a time until every stone is in the core;
+/// the remaining blinks are then applied at once, as a power of the core's transition matrix, if
+/// that's cheaper than stepping through them. Real inputs have cores of a few thousand engravings,
+/// so that only pays off for very many blinks.
+pub fn count_after_blinks_fast(num_blinks: usize, stones: Vec<u64>) -> u64 {
+    let graph = transitions(&stones);
+    let core = recurrent(&graph);
+
+    let mut counts: HashMap<u64, u64> = HashMap::new();
+    stones.iter().for_each(|&s| *counts.entry(s).or_insert(0) += 1);
+
+    let mut blinks = 0;
+    while blinks < num_blinks && !counts.keys().all(|v| core.contains(v)) {
+        counts = blink_counts(&graph, counts);
+        blinks += 1;
+    }
+
+    // Each step costs about one operation per edge; each of the ~2 log2(n) matrix products, one
+    // per entry per core engraving.
+    let remaining = (num_blinks - blinks) as u128;
+    if remaining == 0 {
+        return counts.into_values().fold(0, u64::wrapping_add);
+    }
+    let edges = core.iter().map(|v| graph[v].len() as u128).sum::<u128>();
+    let size = core.len() as u128;
+    if remaining * edges > 2 * (remaining.ilog2() as u128 + 1) * size.pow(3) {
+        return count_by_matrix(&graph, &core, &counts, num_blinks - blinks);
+    }
+
+    for _ in blinks..num_blinks {
+        counts = blink_counts(&graph, counts);
+    }
+
+    counts.into_values().fold(0, u64::wrapping_add)
+}
+
+/// Number of stones after 25 blinks.
+pub fn part1(input: &str) -> usize {
+    let stones = parse_input(input);
+    count_after_blinks(25, stones)
+}
+
+/// Number of stones after 75 blinks.
+pub fn part2(input: &str) -> usize {
+    let stones = parse_input(input);
+    count_after_blinks(75, stones)
+}
+
+/// Number of stones after `--blinks N` blinks (75 by default), modulo 2^64, via
+/// [`count_after_blinks_fast`].
+pub fn fast(input: &str, args: &Args) -> String {
+    let count = count_after_blinks_fast(args.blinks, parse_input(input));
+    format!("Stones after {} blinks (modulo 2^64): {count}", args.blinks)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use super::{
+        count_after_blinks, count_after_blinks_fast, count_by_matrix, parse_input, part1, part2,
+        recurrent, transitions
+    };
+
+    const TEST_INPUT: &str = "125 17";
+
+    crate::test_line_endings! { part1, part2; TEST_INPUT }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 55312);
+    }
+
+    #[test]
+    fn test_fast_matches_dp() {
+        for input in [TEST_INPUT, "0", "773 79858 0 71 213357 2937 1 3998391"] {
+            for blinks in 0..=75 {
+                let stones = parse_input(input);
+                let expected = count_after_blinks(blinks, stones.clone()) as u64;
+                assert_eq!(count_after_blinks_fast(blinks, stones), expected, "{input}: {blinks}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_matrix_matches_dp() {
+        // 0 is in the core, and so is everything it turns into.
+        let graph = transitions(&[0]);
+        let core = recurrent(&graph);
+        let counts = HashMap::from([(0, 1)]);
+
+        for blinks in 0..=75 {
+            let expected = count_after_blinks(blinks, vec![0]) as u64;
+            assert_eq!(count_by_matrix(&graph, &core, &counts, blinks), expected, "{blinks}");
+        }
+    }
+
+    #[test]
+    fn test_fast_many_blinks() {
+        // 0 becomes 1 on the first blink, and stones never interact.
+        assert_eq!(count_after_blinks_fast(5000, vec![0]), count_after_blinks_fast(4999, vec![1]));
+        let separately = [125, 17].map(|stone| count_after_blinks_fast(2000, vec![stone]));
+        assert_eq!(
+            count_after_blinks_fast(2000, vec![125, 17]),
+            separately[0].wrapping_add(separately[1])
+        );
+
+        let trillion = 1_000_000_000_000;
+        assert_eq!(
+            count_after_blinks_fast(trillion, vec![0]),
+            count_after_blinks_fast(trillion - 1, vec![1])
+        );
+    }
+}
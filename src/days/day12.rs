@@ -0,0 +1,565 @@
+//! Day 12: Garden Groups
+//!
+//! Price the fencing around each region of same-type garden plots.
+//!
+//! # Approach
+//!
+//! One sweep over the garden, row by row, labels the regions and counts everything at once (see
+//! [`sweep`]). Each plot takes the label of a same-type neighbor above or to its left, and labels
+//! that turn out to belong to the same region are merged, union-find style, along with their
+//! totals. The perimeter is the number of plot edges bordering a different type; the number of
+//! sides equals the number of corners, which can be read off from the 2×2 block of plots around
+//! each lattice point (see the comments in `count_corners`). Only the current and previous rows'
+//! labels are needed, so large gardens cost little more than reading them.
+//!
+//! [`fences`] also lists each region's actual fence segments, which give both counts again
+//! independently, and [`svg`] draws them. With `--dump DIR`, each plot's region number is written
+//! to `DIR/day12/regions-orthogonal.csv` (or `regions-eight-way.csv`). `cargo bench --bench days`
+//! times the sweep on generated gardens of up to 2000×2000 plots.
+
+use std::fmt::Write;
+use itertools::Itertools;
+use crate::dump::{csv, DebugSink};
+use crate::grid::{Direction, Grid};
+use crate::hash::{HashMap, HashSet};
+
+/// The quantities that fencing is priced by, for one region.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+struct Totals {
+    area: usize,
+    perimeter: usize,
+    corners: usize
+}
+
+struct Region {
+    plots: HashSet<(usize, usize)>
+}
+
+/// The labels handed out by [`sweep`], as a union-find forest: labels found to be in the same
+/// region are merged, and the root of each tree holds its region's totals.
+///
+/// The root is always the smallest label in its tree, which is the one given to the region's first
+/// plot, since labels are handed out in scan order.
+struct Labels {
+    parents: Vec<usize>,
+    totals: Vec<Totals>
+}
+
+impl Labels {
+    fn new() -> Labels {
+        Labels { parents: Vec::new(), totals: Vec::new() }
+    }
+
+    fn add(&mut self) -> usize {
+        self.parents.push(self.parents.len());
+        self.totals.push(Totals::default());
+        self.parents.len() - 1
+    }
+
+    /// The root of `label`'s tree, halving the path to it along the way.
+    fn find(&mut self, mut label: usize) -> usize {
+        while self.parents[label] != label {
+            self.parents[label] = self.parents[self.parents[label]];
+            label = self.parents[label];
+        }
+        label
+    }
+
+    /// Merge the regions of labels `a` and `b`, returning the root of the result.
+    fn union(&mut self, a: usize, b: usize) -> usize {
+        let (a, b) = (self.find(a), self.find(b));
+        let (a, b) = (a.min(b), a.max(b));
+        if a != b {
+            self.parents[b] = a;
+            let Totals { area, perimeter, corners } = self.totals[b];
+            let totals = &mut self.totals[a];
+            totals.area += area;
+            totals.perimeter += perimeter;
+            totals.corners += corners;
+        }
+        a
+    }
+
+    /// The totals of the region with `label`.
+    fn totals_mut(&mut self, label: usize) -> &mut Totals {
+        let root = self.find(label);
+        &mut self.totals[root]
+    }
+
+    /// The totals of every region, in the order their first plots were labelled.
+    fn regions(&self) -> impl Iterator<Item=&Totals> + '_ {
+        (0..self.parents.len()).filter(|&label| self.parents[label] == label)
+            .map(|label| &self.totals[label])
+    }
+}
+
+/// Which neighboring plots of the same type count as part of the same region.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Connectivity { Orthogonal, EightWay }
+
+/// How the fence around a region is priced.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Pricing {
+    /// Area times perimeter (part 1).
+    Perimeter,
+    /// Area times number of sides (part 2's bulk discount).
+    Sides
+}
+
+impl Pricing {
+    fn price(&self, region: &Totals) -> usize {
+        match self {
+            Pricing::Perimeter => region.area * region.perimeter,
+            Pricing::Sides => region.area * region.corners
+        }
+    }
+}
+
+/// Add the corners at one lattice point to the regions of the plots around it.
+///
+/// `block` holds the type and label of each plot around the point, if it's in the garden,
+/// clockwise from the top left.
+fn count_corners(labels: &mut Labels, block: [Option<(char, usize)>; 4]) {
+    // What can corners look like? Take a plot to the bottom right of the point as an example, so
+    // that the point is the plot's top left corner. Possible configurations (where X matches and O
+    // doesn't) are:
+    //
+    // XX   XX   XO   XO   OX   OX   OO   OO
+    // X*   O*   X*   O*   X*   O*   X*   O*
+    // No   No   No   Yes  Yes  No   No   Yes
+    //
+    // So if in clockwise order those spaces matching/non-matching are bools `[a, b, c]`, then
+    // we get corners precisely when a == c and at least one element of the triple is false.
+    for (k, &plot) in block.iter().enumerate() {
+        let Some((plot_type, label)) = plot else { continue };
+        let matches = |m: usize| block[m % 4].is_some_and(|(t, _)| t == plot_type);
+        let (a, b, c) = (matches(k + 3), matches(k + 2), matches(k + 1));
+
+        if a == c && !(a && b && c) {
+            labels.totals_mut(label).corners += 1;
+        }
+    }
+}
+
+/// Label the garden's regions under `connectivity` in one pass, row by row, totalling their area,
+/// perimeter and corners; `visit` is told the label given to each plot (not necessarily its
+/// region's root), in the same order.
+///
+/// Fences always run between orthogonally adjacent plots of different types, so the perimeter
+/// and corner counts are unaffected by connectivity, other than through which plots are grouped.
+fn sweep<V>(garden: &Grid<char>, connectivity: Connectivity, mut visit: V) -> Labels
+where
+    V: FnMut((usize, usize), usize)
+{
+    let (rows, cols) = (garden.rows(), garden.cols());
+    let mut labels = Labels::new();
+    let mut above: Vec<usize> = Vec::new();
+    let mut row = Vec::with_capacity(cols);
+
+    // Plots next to (i, j) that have been labelled already: the left one, then those above.
+    let earlier: &[(isize, isize)] = match connectivity {
+        Connectivity::Orthogonal => &[(0, -1), (-1, 0)],
+        Connectivity::EightWay => &[(0, -1), (-1, -1), (-1, 0), (-1, 1)]
+    };
+
+    for i in 0..rows {
+        for j in 0..cols {
+            let plot_type = garden[(i, j)];
+            let label_of = |row: &[usize], above: &[usize], (di, dj): (isize, isize)| {
+                let (ni, nj) = (i.checked_add_signed(di)?, j.checked_add_signed(dj)?);
+                if nj >= cols || garden[(ni, nj)] != plot_type {
+                    return None;
+                }
+                Some(if di == 0 { row[nj] } else { above[nj] })
+            };
+
+            let mut label = None;
+            for &offset in earlier {
+                if let Some(other) = label_of(&row, &above, offset) {
+                    label = Some(label.map_or(other, |label| labels.union(label, other)));
+                }
+            }
+            let label = label.unwrap_or_else(|| labels.add());
+            row.push(label);
+            visit((i, j), label);
+
+            // Edges with the plots above and to the left count for both sides; edges on the
+            // garden's boundary just for this plot.
+            labels.totals_mut(label).area += 1;
+            let outer = [i == 0, j == 0, i == rows - 1, j == cols - 1];
+            labels.totals_mut(label).perimeter += outer.iter().filter(|&&edge| edge).count();
+            if i > 0 && garden[(i - 1, j)] != plot_type {
+                labels.totals_mut(label).perimeter += 1;
+                labels.totals_mut(above[j]).perimeter += 1;
+            }
+            if j > 0 && garden[(i, j - 1)] != plot_type {
+                labels.totals_mut(label).perimeter += 1;
+                labels.totals_mut(row[j - 1]).perimeter += 1;
+            }
+        }
+
+        // The lattice points along the top of this row.
+        for j in 0..=cols {
+            let plot = |i: usize, j: Option<usize>, labels: &[usize]| {
+                j.filter(|&j| j < cols && !labels.is_empty()).map(|j| (garden[(i, j)], labels[j]))
+            };
+            let up = i.saturating_sub(1);
+            count_corners(&mut labels, [
+                plot(up, j.checked_sub(1), &above),
+                plot(up, Some(j), &above),
+                plot(i, Some(j), &row),
+                plot(i, j.checked_sub(1), &row)
+            ]);
+        }
+
+        above = std::mem::replace(&mut row, Vec::with_capacity(cols));
+    }
+
+    // The lattice points along the bottom of the garden.
+    for j in 0..=cols {
+        let plot = |j: Option<usize>| {
+            j.filter(|&j| j < cols && !above.is_empty()).map(|j| (garden[(rows - 1, j)], above[j]))
+        };
+        count_corners(&mut labels, [plot(j.checked_sub(1)), plot(Some(j)), None, None]);
+    }
+
+    labels
+}
+
+/// One unit of fence: the edge on `side` of `plot`, between it and a plot of another region (or
+/// the edge of the garden).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Segment {
+    pub plot: (usize, usize),
+    pub side: Direction
+}
+
+impl Segment {
+    /// The segment's endpoints, as `(row, col)` lattice points: plot `(i, j)` is the unit square
+    /// from `(i, j)` to `(i + 1, j + 1)`.
+    pub fn endpoints(&self) -> ((usize, usize), (usize, usize)) {
+        let (i, j) = self.plot;
+        match self.side {
+            Direction::Up => ((i, j), (i, j + 1)),
+            Direction::Down => ((i + 1, j), (i + 1, j + 1)),
+            Direction::Left => ((i, j), (i + 1, j)),
+            Direction::Right => ((i, j + 1), (i + 1, j + 1))
+        }
+    }
+
+    /// The segment just before this one along the same line (above or to the left), facing the
+    /// same way.
+    fn previous(&self) -> Option<Segment> {
+        let (i, j) = self.plot;
+        let plot = match self.side {
+            Direction::Up | Direction::Down => (i, j.checked_sub(1)?),
+            Direction::Left | Direction::Right => (i.checked_sub(1)?, j)
+        };
+        Some(Segment { plot, side: self.side })
+    }
+}
+
+/// The fence around one region.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fence {
+    pub plot_type: char,
+    /// Every unit segment of the fence, in sorted order.
+    pub segments: Vec<Segment>
+}
+
+impl Fence {
+    fn of(region: &Region, garden: &Grid<char>) -> Fence {
+        let bounds = garden.bounds();
+        let &plot = region.plots.iter().next().expect("Regions aren't empty");
+
+        let segments = region.plots.iter()
+            .flat_map(|&plot| Direction::ALL.map(|side| (plot, side)))
+            .filter(|&(plot, side)| {
+                side.step(plot, bounds).is_none_or(|next| !region.plots.contains(&next))
+            })
+            .map(|(plot, side)| Segment { plot, side })
+            .sorted()
+            .collect();
+
+        Fence { plot_type: garden[plot], segments }
+    }
+
+    pub fn perimeter(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// The number of straight sides: maximal runs of segments along one line with the region on
+    /// the same side of them.
+    pub fn sides(&self) -> usize {
+        let segments: HashSet<&Segment> = self.segments.iter().collect();
+        self.segments.iter()
+            .filter(|s| s.previous().is_none_or(|prev| !segments.contains(&prev)))
+            .count()
+    }
+}
+
+/// Split the garden into regions of same-type plots joined under the given connectivity, in the
+/// order they're found scanning the garden row by row.
+fn regions(garden: &Grid<char>, connectivity: Connectivity) -> Vec<Region> {
+    let mut plot_labels = Vec::with_capacity(garden.rows() * garden.cols());
+    let mut labels = sweep(garden, connectivity, |plot, label| plot_labels.push((plot, label)));
+
+    let mut index = HashMap::default();
+    let mut components: Vec<Vec<(usize, usize)>> = Vec::new();
+    for (plot, label) in plot_labels {
+        let k = *index.entry(labels.find(label)).or_insert_with(|| {
+            components.push(Vec::new());
+            components.len() - 1
+        });
+        components[k].push(plot);
+    }
+
+    let name = match connectivity {
+        Connectivity::Orthogonal => "regions-orthogonal.csv",
+        Connectivity::EightWay => "regions-eight-way.csv"
+    };
+    DebugSink::new("day12").write(name, || region_labels(garden, &components));
+
+    components.into_iter().map(|plots| Region { plots: plots.into_iter().collect() }).collect()
+}
+
+/// Each plot's region, numbered from 0 in the order of `components`, as CSV.
+fn region_labels(garden: &Grid<char>, components: &[Vec<(usize, usize)>]) -> String {
+    let mut labels: Grid<usize> = Grid::from_fn(garden.rows(), garden.cols(), |_| 0);
+    for (label, component) in components.iter().enumerate() {
+        for &plot in component {
+            labels[plot] = label;
+        }
+    }
+
+    let labels = &labels;
+    csv((0..labels.rows()).map(|i| (0..labels.cols()).map(move |j| labels[(i, j)])))
+}
+
+fn total_price(input: &str, connectivity: Connectivity, pricing: Pricing) -> usize {
+    let garden = Grid::parse(input, |c| c);
+    sweep(&garden, connectivity, |_, _| {}).regions().map(|r| pricing.price(r)).sum()
+}
+
+/// The fence around each region of plots (joined orthogonally), in the order the regions are
+/// found scanning the garden row by row.
+pub fn fences(input: &str) -> Vec<Fence> {
+    let garden = Grid::parse(input, |c| c);
+    regions(&garden, Connectivity::Orthogonal).iter().map(|r| Fence::of(r, &garden)).collect()
+}
+
+/// An SVG drawing of every region's fence, one `<path>` per region, at 10 units per plot.
+pub fn svg(input: &str) -> String {
+    const SCALE: usize = 10;
+
+    let rows = input.lines().count();
+    let cols = input.lines().next().map_or(0, |l| l.chars().count());
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}">"#,
+        cols * SCALE,
+        rows * SCALE
+    ).unwrap();
+    for fence in fences(input) {
+        let d = fence.segments.iter()
+            .map(|s| {
+                let ((i0, j0), (i1, j1)) = s.endpoints();
+                format!("M{} {}L{} {}", j0 * SCALE, i0 * SCALE, j1 * SCALE, i1 * SCALE)
+            })
+            .join("");
+        writeln!(
+            out,
+            r#"<path d="{d}" stroke="black" fill="none"><title>{}</title></path>"#,
+            fence.plot_type
+        ).unwrap();
+    }
+    out.push_str("</svg>");
+
+    out
+}
+
+/// Total price as area times perimeter.
+pub fn part1(input: &str) -> usize {
+    total_price(input, Connectivity::Orthogonal, Pricing::Perimeter)
+}
+
+/// Total price with the bulk discount: area times number of sides.
+pub fn part2(input: &str) -> usize {
+    total_price(input, Connectivity::Orthogonal, Pricing::Sides)
+}
+
+/// Both fence prices, treating diagonally touching plots as part of the same region.
+pub fn eight_way(input: &str) -> String {
+    format!(
+        "Part 1: {}\nPart 2: {}",
+        total_price(input, Connectivity::EightWay, Pricing::Perimeter),
+        total_price(input, Connectivity::EightWay, Pricing::Sides)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use crate::gen::{day12_garden, Rng};
+    use crate::grid::{connected_components, Direction, Grid};
+    use super::{
+        fences, part1, part2, region_labels, regions, svg, sweep, total_price, Connectivity,
+        Pricing, Segment, Totals
+    };
+
+    const TEST_INPUT: &str = "RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE";
+
+    const SMALL_INPUT: &str = "AAAA
+BBCD
+BBCC
+EEEC";
+
+    const NESTED_INPUT: &str = "OOOOO
+OXOXO
+OOOOO
+OXOXO
+OOOOO";
+
+    const E_INPUT: &str = "EEEEE
+EXXXX
+EEEEE
+EXXXX
+EEEEE";
+
+    const MOBIUS_INPUT: &str = "AAAAAA
+AAABBA
+AAABBA
+ABBAAA
+ABBAAA
+AAAAAA";
+
+    crate::test_samples! {
+        part1, part2;
+        (SMALL_INPUT, 140, 80),
+        (NESTED_INPUT, 772, 436),
+        (TEST_INPUT, 1930, 1206),
+        (E_INPUT, _, 236),
+        (MOBIUS_INPUT, _, 368),
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 1930);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), 1206);
+    }
+
+    const CHECKERBOARD: &str = "AB\nBA";
+    const DIAGONAL_X: &str = "A.A\n.A.\nA.A";
+
+    #[test]
+    fn test_region_labels() {
+        let garden = Grid::parse(SMALL_INPUT, |c| c);
+        let components: Vec<_> = regions(&garden, Connectivity::Orthogonal).into_iter()
+            .map(|region| region.plots.into_iter().collect())
+            .collect();
+        assert_eq!(region_labels(&garden, &components), "0,0,0,0\n1,1,2,3\n1,1,2,2\n4,4,4,2\n");
+    }
+
+    #[test]
+    fn test_eight_way_regions() {
+        let garden = Grid::parse(DIAGONAL_X, |c| c);
+        assert_eq!(regions(&garden, Connectivity::Orthogonal).len(), 9);
+
+        let mut areas: Vec<usize> = regions(&garden, Connectivity::EightWay).iter()
+            .map(|r| r.plots.len())
+            .collect();
+        areas.sort();
+        assert_eq!(areas, vec![4, 5]);
+    }
+
+    #[test]
+    fn test_eight_way_prices() {
+        assert_eq!(total_price(CHECKERBOARD, Connectivity::Orthogonal, Pricing::Perimeter), 16);
+        assert_eq!(total_price(CHECKERBOARD, Connectivity::EightWay, Pricing::Perimeter), 32);
+        assert_eq!(total_price(CHECKERBOARD, Connectivity::EightWay, Pricing::Sides), 32);
+
+        assert_eq!(total_price(DIAGONAL_X, Connectivity::Orthogonal, Pricing::Perimeter), 36);
+        assert_eq!(total_price(DIAGONAL_X, Connectivity::EightWay, Pricing::Perimeter), 164);
+        assert_eq!(total_price(DIAGONAL_X, Connectivity::EightWay, Pricing::Sides), 164);
+    }
+
+    #[test]
+    fn test_fence_segments() {
+        let fences = fences(SMALL_INPUT);
+        let d = &fences[3];
+        assert_eq!(d.plot_type, 'D');
+        assert_eq!(d.segments, Direction::ALL.map(|side| Segment { plot: (1, 3), side }));
+        assert_eq!(
+            d.segments.iter().map(Segment::endpoints).collect::<Vec<_>>(),
+            vec![((1, 3), (1, 4)), ((2, 3), (2, 4)), ((1, 3), (2, 3)), ((1, 4), (2, 4))]
+        );
+    }
+
+    #[test]
+    fn test_fences_match_counts() {
+        for input in [SMALL_INPUT, NESTED_INPUT, TEST_INPUT, E_INPUT, MOBIUS_INPUT] {
+            let garden = Grid::parse(input, |c| c);
+            let labels = sweep(&garden, Connectivity::Orthogonal, |_, _| {});
+            let totals: Vec<&Totals> = labels.regions().collect();
+            let fences = fences(input);
+
+            assert_eq!(fences.len(), totals.len());
+            for (fence, totals) in fences.iter().zip(totals) {
+                assert_eq!(fence.perimeter(), totals.perimeter);
+                assert_eq!(fence.sides(), totals.corners);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sweep_on_generated_gardens() {
+        for seed in 0..5 {
+            let input = day12_garden(&mut Rng::new(seed), 30, 40, 4);
+            let garden = Grid::parse(&input, |c| c);
+            let regions = regions(&garden, Connectivity::Orthogonal);
+            let labels = sweep(&garden, Connectivity::Orthogonal, |_, _| {});
+
+            // The same regions as a flood fill finds, in the same order.
+            let flood_filled: Vec<BTreeSet<(usize, usize)>> =
+                connected_components(&garden, |a, b| a == b).into_iter()
+                    .map(|plots| plots.into_iter().collect())
+                    .collect();
+            let swept: Vec<BTreeSet<(usize, usize)>> = regions.iter()
+                .map(|region| region.plots.iter().copied().collect())
+                .collect();
+            assert_eq!(swept, flood_filled, "seed {seed}");
+
+            let fences = fences(&input);
+            let counted = fences.iter().zip(&regions).zip(labels.regions());
+            for ((fence, region), totals) in counted {
+                assert_eq!(fence.perimeter(), totals.perimeter, "seed {seed}");
+                assert_eq!(fence.sides(), totals.corners, "seed {seed}");
+                assert_eq!(region.plots.len(), totals.area, "seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = svg("AB");
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 10">"#));
+        assert!(svg.contains(r#"<path d="M0 0L10 0M0 10L10 10M0 0L0 10M10 0L10 10""#));
+        assert_eq!(svg.matches("<path").count(), 2);
+        assert!(svg.ends_with("</svg>"));
+    }
+}
@@ -0,0 +1,584 @@
+//! Day 14: Restroom Redoubt
+//!
+//! Robots move in straight lines on a wrapping 101×103 board (`--width` and `--height`).
+//!
+//! # Approach
+//!
+//! Positions after any number of seconds are computed directly with modular arithmetic. For part 2,
+//! the positions repeat with period `101 × 103`, so every frame in one period is scored by how
+//! clustered the robots are, and the best one is taken as the picture. Frames are scored
+//! independently, so with the `parallel` feature they're spread across all cores.
+//!
+//! The "entropy" strategy scores frames differently: a picture bunches the robots into a few rows
+//! and columns, so it looks for the frame whose rows and columns are least evenly occupied. It
+//! steps through the frames with a [`Swarm`], a [`Simulation`] moving every robot one second at a
+//! time.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use std::ops::{Add, Mul, Rem, Sub};
+use itertools::Itertools;
+use crate::simulation::{Simulation, StepResult};
+use crate::strategy::ArgStrategies;
+use self::parse::parse_input;
+
+crate::day_args! { width: isize = 101, height: isize = 103, seconds: usize = 100 }
+
+impl Args {
+    fn board(&self) -> Vector {
+        Vector(self.width, self.height)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Vector(isize, isize);
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Vector(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vector(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+impl Mul<Vector> for usize {
+    type Output = Vector;
+
+    fn mul(self, rhs: Vector) -> Self::Output {
+        let k = self as isize;
+        Vector(k * rhs.0, k * rhs.1)
+    }
+}
+
+impl Rem for Vector {
+    type Output = Vector;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        Vector(self.0.rem_euclid(rhs.0), self.1.rem_euclid(rhs.1))
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Quadrant { NE, SE, SW, NW }
+
+impl Quadrant {
+    const ALL: [Quadrant; 4] = [Quadrant::NE, Quadrant::SE, Quadrant::SW, Quadrant::NW];
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Robot {
+    position: Vector,
+    velocity: Vector,
+    board: Vector
+}
+
+impl Robot {
+    fn updated(&self, frames: usize) -> Robot {
+        let position = (self.position + frames * self.velocity) % self.board;
+        Robot { position, ..*self }
+    }
+}
+
+/// All the robots, moved together one second per [`Simulation`] step. They never stop.
+///
+/// [`simulation`] starts one from the input, and it displays as the board with the robots drawn
+/// on it.
+pub struct Swarm {
+    robots: Vec<Robot>,
+    /// Seconds since the start.
+    frame: usize
+}
+
+impl Swarm {
+    fn new(robots: &[Robot]) -> Swarm {
+        Swarm { robots: robots.to_vec(), frame: 0 }
+    }
+
+    /// Seconds since the start.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+}
+
+impl Display for Swarm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&render(&self.robots, 0))
+    }
+}
+
+impl Simulation for Swarm {
+    fn step(&mut self) -> StepResult {
+        self.robots.iter_mut().for_each(|robot| *robot = robot.updated(1));
+        self.frame += 1;
+        StepResult::Running
+    }
+}
+
+/// Number of robots in each bucket, where `partitioner` maps a position to its bucket; robots it
+/// maps to `None` (say, on a dividing line) aren't counted anywhere.
+fn partition_counts<K, F>(robots: &[Robot], partitioner: F) -> HashMap<K, usize>
+where
+    K: Eq + Hash,
+    F: Fn(Vector) -> Option<K>
+{
+    let mut counts = HashMap::new();
+    robots.iter()
+        .filter_map(|robot| partitioner(robot.position))
+        .for_each(|key| *counts.entry(key).or_insert(0) += 1);
+
+    counts
+}
+
+/// Partitioner into the four quadrants of `board`. On an odd dimension the middle row or column
+/// belongs to no quadrant; on an even one, the two halves are the same size.
+fn quadrants(board: Vector) -> impl Fn(Vector) -> Option<Quadrant> {
+    move |position| {
+        // Compare against the midline at (size - 1) / 2, doubled to stay in integers.
+        let x = (2 * position.0 + 1).cmp(&board.0);
+        let y = (2 * position.1 + 1).cmp(&board.1);
+
+        match (x, y) {
+            (Ordering::Greater, Ordering::Greater) => Some(Quadrant::SE),
+            (Ordering::Greater, Ordering::Less) => Some(Quadrant::SW),
+            (Ordering::Less, Ordering::Greater) => Some(Quadrant::NE),
+            (Ordering::Less, Ordering::Less) => Some(Quadrant::NW),
+            _ => None
+        }
+    }
+}
+
+/// Partitioner into a `cols` × `rows` grid of sectors, as `(column, row)`. Every position is in
+/// exactly one sector; when the board doesn't divide evenly, sector sizes differ by at most one.
+fn sectors(board: Vector, cols: usize, rows: usize) -> impl Fn(Vector) -> Option<(usize, usize)> {
+    move |position| {
+        let col = position.0 as usize * cols / board.0 as usize;
+        let row = position.1 as usize * rows / board.1 as usize;
+        Some((col, row))
+    }
+}
+
+/// Product of the counts in `buckets`; a bucket with no robots makes it zero.
+fn safety_factor<K: Eq + Hash>(
+    counts: &HashMap<K, usize>,
+    buckets: impl IntoIterator<Item=K>
+) -> usize {
+    buckets.into_iter().map(|key| counts.get(&key).copied().unwrap_or(0)).product()
+}
+
+mod parse {
+    use nom::bytes::complete::tag;
+    use nom::character::complete::{char, line_ending, space1};
+    use nom::combinator::map;
+    use nom::IResult;
+    use nom::multi::separated_list1;
+    use nom::sequence::{preceded, separated_pair};
+    use crate::parse::signed;
+
+    use super::{Robot, Vector};
+
+    fn vector(input: &str) -> IResult<&str, Vector> {
+        let pair = separated_pair(signed, char(','), signed);
+        map(pair, |(x, y)| Vector(x, y))(input)
+    }
+
+    pub fn parse_input(input: &str, board: Vector) -> Vec<Robot> {
+        let p = preceded(tag("p="), vector);
+        let v = preceded(tag("v="), vector);
+        let robot = map(
+            separated_pair(p, space1, v),
+            |(pos, vel)| Robot { position: pos, velocity: vel, board }
+        );
+
+        separated_list1(line_ending, robot)(input).unwrap().1
+    }
+}
+
+/// The robots at their starting positions, on a `--width` × `--height` board.
+pub fn simulation(input: &str, args: &Args) -> Swarm {
+    Swarm::new(&parse_input(input, args.board()))
+}
+
+/// Safety factor: the product of the robot counts in the four quadrants after 100 seconds (or
+/// `--seconds`).
+pub fn part1(input: &str, args: &Args) -> usize {
+    let board = args.board();
+    let robots: Vec<Robot> = parse_input(input, board).iter()
+        .map(|r| r.updated(args.seconds))
+        .collect();
+    safety_factor(&partition_counts(&robots, quadrants(board)), Quadrant::ALL)
+}
+
+/// Robot counts in each of 3×3 sectors after 100 seconds, and their product: an alternative take
+/// on the safety factor.
+pub fn sector_counts(input: &str, args: &Args) -> String {
+    let board = args.board();
+    let robots: Vec<Robot> = parse_input(input, board).iter()
+        .map(|r| r.updated(args.seconds))
+        .collect();
+    let counts = partition_counts(&robots, sectors(board, 3, 3));
+
+    let mut result = String::new();
+    for row in 0..3 {
+        for col in 0..3 {
+            result.push_str(&format!("{:>5}", counts.get(&(col, row)).unwrap_or(&0)));
+        }
+        result.push('\n');
+    }
+    let all = (0..3).cartesian_product(0..3).map(|(row, col)| (col, row));
+    result.push_str(&format!("Product: {}", safety_factor(&counts, all)));
+
+    result
+}
+
+fn empty_map(board: Vector) -> Vec<Vec<bool>> {
+    (0..board.1).map(|_| vec![false; board.0 as usize]).collect()
+}
+
+/// Draw the robots as they are after `frame` seconds onto `map`, reusing its rows.
+fn draw(robots: &[Robot], frame: usize, map: &mut [Vec<bool>]) {
+    let board = robots[0].board;
+
+    map.iter_mut().for_each(|row| row.fill(false));
+    robots.iter().for_each(|robot| {
+        assert_eq!(robot.board, board);
+        let position = robot.updated(frame).position;
+        map[position.1 as usize][position.0 as usize] = true;
+    });
+}
+
+fn map_to_string(map: &[Vec<bool>]) -> String {
+    let mut result = String::new();
+
+    map.iter().for_each(|row| {
+        row.iter().for_each(|&present| {
+            result.push(if present { '*' } else { ' ' });
+        });
+        result.push('\n');
+    });
+
+    result
+}
+
+fn neighbor_score_at(map: &[Vec<bool>], i: usize, j: usize) -> usize {
+    if !map[i][j] {
+        return 0
+    }
+
+    let mut score = -1;
+    (i as isize - 1..i as isize +1)
+        .cartesian_product(j as isize - 1..j as isize +1)
+        .filter(|&(i, j)| i >= 0 && i < map.len() as isize && j >= 0 && j < map[0].len() as isize)
+        .map(|(i, j)| (i as usize, j as usize))
+        .for_each(|(i, j)| if map[i][j] { score += 1 });
+
+    score as usize
+}
+
+/// Score to try to look for the tree...
+///
+/// If the robots are going to form a picture, they're going to need to be close to each other.
+///
+/// This score is added up pixel by pixel; the score for a pixel is 0 if it is off; if it is on,
+/// then the score is the number of pixels the 3x3 grid centered at this pixel that are on.
+///
+/// This will tend to favor images that have lots of structure to them as opposed to random single
+/// pixels.
+fn neighbor_score(map: &[Vec<bool>]) -> usize {
+    (0..map.len()).cartesian_product(0..map[0].len())
+        .map(|(i, j)| neighbor_score_at(map, i, j))
+        .sum()
+}
+
+/// [`neighbor_score`] of the robots after `frame` seconds, drawn onto the scratch `map`.
+fn frame_score(robots: &[Robot], frame: usize, map: &mut [Vec<bool>]) -> usize {
+    draw(robots, frame, map);
+    neighbor_score(map)
+}
+
+/// Number of frames before the robots' positions repeat.
+fn period(robots: &[Robot]) -> usize {
+    let board = robots[0].board;
+    (board.0 * board.1) as usize
+}
+
+/// The frame (within one full period of the board) that scores highest for [`neighbor_score`];
+/// the earliest, if there's a tie.
+fn best_frame(robots: &[Robot]) -> usize {
+    let mut map = empty_map(robots[0].board);
+    (0..period(robots))
+        .max_by_key(|&i| (frame_score(robots, i, &mut map), Reverse(i)))
+        .unwrap_or(0)
+}
+
+/// [`best_frame`], scoring the frames on all cores.
+#[cfg(feature = "parallel")]
+fn best_frame_parallel(robots: &[Robot]) -> usize {
+    use rayon::prelude::*;
+
+    let board = robots[0].board;
+    (0..period(robots)).into_par_iter()
+        .map_init(|| empty_map(board), |map, i| (frame_score(robots, i, map), Reverse(i)))
+        .max()
+        .map_or(0, |(_, Reverse(i))| i)
+}
+
+/// Shannon entropy (in nats) of how `values` are spread over `0..size`.
+fn entropy(values: impl Iterator<Item=usize>, size: usize) -> f64 {
+    let mut counts = vec![0usize; size];
+    values.for_each(|v| counts[v] += 1);
+    let total: usize = counts.iter().sum();
+
+    counts.into_iter()
+        .filter(|&count| count > 0)
+        .map(|count| {
+            let p = count as f64 / total as f64;
+            -p * p.ln()
+        })
+        .sum()
+}
+
+/// How evenly the robots are spread over the board's columns and rows: the sum of the entropies
+/// of their x and y coordinates.
+fn occupancy_entropy(robots: &[Robot]) -> f64 {
+    let board = robots[0].board;
+
+    entropy(robots.iter().map(|r| r.position.0 as usize), board.0 as usize)
+        + entropy(robots.iter().map(|r| r.position.1 as usize), board.1 as usize)
+}
+
+/// The frame (within one full period of the board) with the lowest [`occupancy_entropy`]; the
+/// earliest, if there's a tie.
+fn lowest_entropy_frame(robots: &[Robot]) -> usize {
+    let period = period(robots);
+    let mut lowest = (f64::INFINITY, 0);
+
+    // Stepping the swarm along is cheaper than computing each frame's positions from scratch.
+    let mut swarm = Swarm::new(robots);
+    loop {
+        let entropy = occupancy_entropy(&swarm.robots);
+        if entropy < lowest.0 {
+            lowest = (entropy, swarm.frame);
+        }
+        if swarm.frame + 1 >= period {
+            break;
+        }
+        swarm.step();
+    }
+
+    lowest.1
+}
+
+/// The robots after `frame` seconds, drawn with `*`s.
+fn render(robots: &[Robot], frame: usize) -> String {
+    let mut map = empty_map(robots[0].board);
+    draw(robots, frame, &mut map);
+    map_to_string(&map)
+}
+
+/// Part 2, finding the best frame with `find`; the frame itself is logged too (shown with `-v`).
+fn part2_with(input: &str, args: &Args, find: fn(&[Robot]) -> usize) -> usize {
+    let robots = parse_input(input, args.board());
+    let frame = find(&robots);
+
+    log::info!("Frame {frame}:\n{}", render(&robots, frame));
+    frame
+}
+
+fn part2_serial(input: &str, args: &Args) -> usize {
+    part2_with(input, args, best_frame)
+}
+
+#[cfg(feature = "parallel")]
+fn part2_parallel(input: &str, args: &Args) -> usize {
+    part2_with(input, args, best_frame_parallel)
+}
+
+fn part2_entropy(input: &str, args: &Args) -> usize {
+    part2_with(input, args, lowest_entropy_frame)
+}
+
+/// The first second at which the robots draw a Christmas tree; run with `-v` to see it.
+pub const PART2: ArgStrategies<usize, Args> = ArgStrategies(&[
+    #[cfg(feature = "parallel")]
+    ("parallel", part2_parallel),
+    ("serial", part2_serial),
+    ("entropy", part2_entropy)
+]);
+
+/// The frame each tree-detection heuristic picks, to check that they agree.
+pub fn heuristics(input: &str, args: &Args) -> String {
+    let robots = parse_input(input, args.board());
+    format!(
+        "Neighbors: {}\nEntropy: {}",
+        best_frame(&robots),
+        lowest_entropy_frame(&robots)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_snapshot;
+    use crate::gen::Rng;
+    use super::parse::parse_input;
+    use super::{best_frame, lowest_entropy_frame, part1, partition_counts, quadrants, render};
+    use crate::simulation::{Simulation, StepResult};
+    use super::{part2_serial, safety_factor, sectors};
+    use super::{simulation, Args, Quadrant, Robot, Swarm, Vector};
+
+    const TREE: &str = "*****************
+*               *
+*       *       *
+*      ***      *
+*     *****     *
+*    *******    *
+*      ***      *
+*     *****     *
+*    *******    *
+*   *********   *
+*  ***********  *
+*       *       *
+*       *       *
+*               *
+*****************";
+
+    /// Robots on a 31×29 board that form `TREE` at frame `frame`, plus some scattered noise.
+    fn tree_robots(frame: usize) -> Vec<Robot> {
+        let board = Vector(31, 29);
+        let mut rng = Rng::new(14);
+        let random_velocity = |rng: &mut Rng| {
+            Vector(rng.between(0, 10) as isize - 5, rng.between(0, 10) as isize - 5)
+        };
+
+        let mut robots: Vec<Robot> = TREE.lines().enumerate()
+            .flat_map(|(y, line)| {
+                line.chars().enumerate()
+                    .filter(|&(_, c)| c == '*')
+                    .map(move |(x, _)| Vector(x as isize + 7, y as isize + 7))
+            })
+            .map(|target| {
+                let velocity = random_velocity(&mut rng);
+                // Run the robot backwards from where it needs to be at `frame`.
+                let position = (target - frame * velocity) % board;
+                Robot { position, velocity, board }
+            })
+            .collect();
+
+        for _ in 0..40 {
+            let position = Vector(rng.below(31) as isize, rng.below(29) as isize);
+            let velocity = random_velocity(&mut rng);
+            robots.push(Robot { position, velocity, board });
+        }
+
+        robots
+    }
+
+    const EXAMPLE: &str = "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3";
+
+    crate::test_line_endings! {
+        |input: &str| part1(input, &Args { width: 11, height: 7, seconds: 100 }),
+        |input: &str| part2_serial(input, &Args { width: 11, height: 7, seconds: 100 });
+        EXAMPLE
+    }
+
+    #[test]
+    fn test_quadrants() {
+        let board = Vector(11, 7);
+        let robots: Vec<Robot> = parse_input(EXAMPLE, board).iter()
+            .map(|r| r.updated(100))
+            .collect();
+        let counts = partition_counts(&robots, quadrants(board));
+        assert_eq!(counts.values().sum::<usize>(), 9);
+        assert_eq!(safety_factor(&counts, Quadrant::ALL), 12);
+        assert_eq!(part1(EXAMPLE, &Args { width: 11, height: 7, seconds: 100 }), 12);
+
+        // Even dimensions split evenly, with no dividing line.
+        let even = quadrants(Vector(4, 6));
+        assert_eq!(even(Vector(1, 2)), Some(Quadrant::NW));
+        assert_eq!(even(Vector(2, 3)), Some(Quadrant::SE));
+        assert_eq!(quadrants(board)(Vector(5, 1)), None);
+    }
+
+    #[test]
+    fn test_sectors() {
+        let board = Vector(11, 7);
+        let robots = parse_input(EXAMPLE, board);
+        let counts = partition_counts(&robots, sectors(board, 3, 3));
+        assert_eq!(counts.values().sum::<usize>(), robots.len());
+
+        let sector = sectors(board, 3, 3);
+        assert_eq!(sector(Vector(0, 0)), Some((0, 0)));
+        assert_eq!(sector(Vector(3, 2)), Some((0, 0)));
+        assert_eq!(sector(Vector(4, 3)), Some((1, 1)));
+        assert_eq!(sector(Vector(10, 6)), Some((2, 2)));
+    }
+
+    #[test]
+    fn test_best_frame() {
+        let robots = tree_robots(500);
+        let frame = best_frame(&robots);
+        assert_eq!(frame, 500);
+        assert_snapshot!("day14_best_frame", render(&robots, frame));
+    }
+
+    #[test]
+    fn test_heuristics_agree() {
+        for frame in [0, 500, 898] {
+            let robots = tree_robots(frame);
+            assert_eq!(best_frame(&robots), frame);
+            assert_eq!(lowest_entropy_frame(&robots), frame);
+        }
+    }
+
+    #[test]
+    fn test_swarm() {
+        let robots = tree_robots(100);
+        let mut swarm = Swarm::new(&robots);
+        assert_eq!(swarm.run_for(37), (37, StepResult::Running));
+        assert_eq!(swarm.frame(), 37);
+
+        let positions = |robots: &[Robot]| robots.iter().map(|r| r.position).collect::<Vec<_>>();
+        let expected: Vec<Robot> = robots.iter().map(|r| r.updated(37)).collect();
+        assert_eq!(positions(&swarm.robots), positions(&expected));
+    }
+
+    #[test]
+    fn test_simulation() {
+        let args = Args { width: 3, height: 2, seconds: 0 };
+        let mut swarm = simulation("p=0,0 v=1,0\np=2,1 v=0,1", &args);
+        assert_eq!(swarm.to_string(), "*  \n  *\n");
+        swarm.step();
+        assert_eq!((swarm.frame(), swarm.to_string()), (1, " **\n   \n".to_owned()));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_best_frame_parallel() {
+        use super::best_frame_parallel;
+
+        for frame in [0, 500, 898] {
+            let robots = tree_robots(frame);
+            assert_eq!(best_frame_parallel(&robots), best_frame(&robots));
+        }
+    }
+}
@@ -0,0 +1,727 @@
+//! Day 15: Warehouse Woes
+//!
+//! A robot pushes boxes around a warehouse.
+//!
+//! # Approach
+//!
+//! Each move is a breadth-first search from the robot over everything that would be pushed; if it
+//! reaches a wall, nothing moves, and otherwise all the collected cells shift at once. On the wide
+//! map, pushing either half of a box vertically also pushes the other half, which this handles
+//! without special cases for chains of boxes.
+//!
+//! The map is widened as it's parsed ([`Scale::Double`]), rather than by expanding the narrow
+//! warehouse, and maps that are already wide can be read as they are.
+//!
+//! Every move can be recorded as a diff and undone, which `--check-boxes` uses to find the first
+//! move (if any) that splits a wide box. In debug builds, every move also checks the level's
+//! invariants ([`Level::validate`]), and panics with a before-and-after rendering if it broke one.
+//!
+//! The moves are made through a [`Warehouse`], which is a [`Simulation`] making one move per step.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+use itertools::Itertools;
+use crate::simulation::{Simulation, StepResult};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Contents { Empty, Box, BoxLeft, BoxRight, Wall, Robot }
+use self::Contents::*;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Direction { Up, Down, Left, Right }
+use Direction::*;
+
+/// How the map in the input is scaled to make the warehouse.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Scale {
+    /// As written, which may already include wide boxes.
+    Single,
+    /// Twice as wide: boxes become `[]`, walls `##`, empty space `..`, and the robot `@.`.
+    Double
+}
+
+impl Scale {
+    /// The cells that one cell of the input becomes.
+    fn cells(self, contents: Contents) -> Result<Vec<Contents>, String> {
+        match (self, contents) {
+            (Scale::Single, _) => Ok(vec![contents]),
+            (Scale::Double, Empty) => Ok(vec![Empty, Empty]),
+            (Scale::Double, Box) => Ok(vec![BoxLeft, BoxRight]),
+            (Scale::Double, Wall) => Ok(vec![Wall, Wall]),
+            (Scale::Double, Robot) => Ok(vec![Robot, Empty]),
+            (Scale::Double, BoxLeft | BoxRight) => {
+                Err("The map already has wide boxes, so can't be widened again".to_owned())
+            }
+        }
+    }
+}
+
+/// Something wrong with a level, which no move should ever cause.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Violation {
+    /// Half of a wide box, without its other half beside it.
+    SplitBox((usize, usize)),
+    /// A robot somewhere other than `robot_pos`.
+    StrayRobot((usize, usize)),
+    /// No robot at `robot_pos`.
+    MissingRobot((usize, usize))
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::SplitBox(pos) => write!(f, "Half a box at {pos:?}"),
+            Violation::StrayRobot(pos) => write!(f, "A second robot at {pos:?}"),
+            Violation::MissingRobot(pos) => write!(f, "No robot at {pos:?}")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Level {
+    rows: usize,
+    cols: usize,
+    board: Vec<Vec<Contents>>,
+    robot_pos: (usize, usize)
+}
+
+impl Level {
+    fn get(&self, pos: (usize, usize)) -> Option<&Contents> {
+        let (i, j) = pos;
+        self.board.get(i).and_then(|row| row.get(j))
+    }
+
+    fn get_mut(&mut self, pos: (usize, usize)) -> Option<&mut Contents> {
+        let (i, j) = pos;
+        self.board.get_mut(i).and_then(|row| row.get_mut(j))
+    }
+
+    fn next_pos(&self, pos: (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        match (direction, pos) {
+            (Up, (0, _)) => None,
+            (Up, (i, j)) => Some((i - 1, j)),
+            (Down, (i, _)) if i == self.rows - 1 => None,
+            (Down, (i, j)) => Some((i + 1, j)),
+            (Left, (_, 0)) => None,
+            (Left, (i, j)) => Some((i, j - 1)),
+            (Right, (_, j)) if j == self.cols - 1 => None,
+            (Right, (i, j)) => Some((i, j + 1))
+        }
+    }
+}
+
+impl Index<(usize, usize)> for Level {
+    type Output = Contents;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        self.get(index).unwrap()
+    }
+}
+
+impl IndexMut<(usize, usize)> for Level {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        self.get_mut(index).unwrap()
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.board.iter() {
+            let line: String = row.iter()
+                .map(|contents| match contents {
+                    Empty => '.',
+                    Box => 'O',
+                    BoxLeft => '[',
+                    BoxRight => ']',
+                    Wall => '#',
+                    Robot => '@'
+                })
+                .collect();
+            writeln!(f, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Level {
+    /// The warehouse and the robot's moves, with the map scaled by `scale`.
+    fn parse(input: &str, scale: Scale) -> Result<(Level, Vec<Direction>), String> {
+        let (board, directions) = parse::parse_input(input)
+            .map_err(|e| format!("Invalid input: {e}"))?
+            .1;
+
+        let board = board.into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|contents| scale.cells(contents))
+                    .flatten_ok()
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let rows = board.len();
+        let cols = board[0].len();
+        if board.iter().any(|row| row.len() != cols) {
+            return Err("The map isn't rectangular".to_owned());
+        }
+
+        let robot_pos = (0..rows).cartesian_product(0..cols)
+            .find(|&(i, j)| board[i][j] == Robot)
+            .ok_or("There's no robot on the map")?;
+
+        let level = Level { rows, cols, board, robot_pos };
+        if let Err(violations) = level.validate() {
+            return Err(format!("Invalid map: {}", violations[0]));
+        }
+
+        Ok((level, directions))
+    }
+
+    /// Check that every wide box is whole, and that there's exactly one robot, at `robot_pos`.
+    fn validate(&self) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+
+        for (i, row) in self.board.iter().enumerate() {
+            for (j, &contents) in row.iter().enumerate() {
+                match contents {
+                    BoxLeft if row.get(j + 1) != Some(&BoxRight) => {
+                        violations.push(Violation::SplitBox((i, j)));
+                    },
+                    BoxRight if j == 0 || row[j - 1] != BoxLeft => {
+                        violations.push(Violation::SplitBox((i, j)));
+                    },
+                    Robot if (i, j) != self.robot_pos => {
+                        violations.push(Violation::StrayRobot((i, j)));
+                    },
+                    _ => {}
+                }
+            }
+        }
+
+        if self.get(self.robot_pos) != Some(&Robot) {
+            violations.push(Violation::MissingRobot(self.robot_pos));
+        }
+
+        if violations.is_empty() { Ok(()) } else { Err(violations) }
+    }
+
+    /// Panic if the move `direction` that produced `diff` took a valid level to an invalid one,
+    /// showing what's wrong and the level before and after.
+    fn check_move(&self, direction: Direction, diff: &MoveDiff) {
+        let Err(violations) = self.validate() else { return };
+        let mut before = self.clone();
+        before.undo(diff);
+
+        if before.validate().is_ok() {
+            panic!(
+                "Moving {direction:?} broke the level:\n{}\n{}",
+                violations.iter().join("\n"),
+                render_diff(&before, self)
+            );
+        }
+    }
+
+    /// Move the robot one step, pushing any boxes in the way, and return how to undo it.
+    ///
+    /// If the robot is blocked, nothing changes and the diff is empty.
+    fn apply_move(&mut self, direction: Direction) -> MoveDiff {
+        let robot_pos = self.robot_pos;
+        let before = match self.pushed(direction) {
+            Some(updates) => {
+                self.robot_pos = self.next_pos(robot_pos, direction).unwrap();
+                updates.into_iter()
+                    .map(|(pos, contents)| (pos, std::mem::replace(&mut self[pos], contents)))
+                    .collect()
+            },
+            None => Vec::new()
+        };
+
+        let diff = MoveDiff { before, robot_pos };
+        if cfg!(debug_assertions) {
+            self.check_move(direction, &diff);
+        }
+
+        diff
+    }
+
+    /// Put back everything changed by the move that produced `diff`.
+    fn undo(&mut self, diff: &MoveDiff) {
+        for &(pos, contents) in diff.before.iter().rev() {
+            self[pos] = contents;
+        }
+        self.robot_pos = diff.robot_pos;
+    }
+
+    /// Apply `directions` in order, keeping a diff for each so that they can be undone.
+    fn apply_moves_with_history(self, directions: &[Direction]) -> History {
+        let mut history = History { level: self, diffs: Vec::with_capacity(directions.len()) };
+        for &direction in directions {
+            let diff = history.level.apply_move(direction);
+            history.diffs.push(diff);
+        }
+
+        history
+    }
+
+    /// The new contents of every cell that changes when the robot moves, or `None` if it can't.
+    ///
+    /// They're ordered by position, so that a move's diff doesn't depend on hash seeds.
+    fn pushed(&self, direction: Direction) -> Option<BTreeMap<(usize, usize), Contents>> {
+        let mut updates = BTreeMap::new();
+        let mut queue = VecDeque::new();
+        let mut seen = HashSet::new();
+
+        self.next_pos(self.robot_pos, direction)?;
+        queue.push_back(self.robot_pos);
+        seen.insert(self.robot_pos);
+
+        while let Some(cur_pos) = queue.pop_front() {
+            let cur_type = self[cur_pos];
+
+            if cur_type == Wall {
+                return None
+            }
+
+            if cur_type == Empty {
+                continue
+            }
+
+            let new_pos = self.next_pos(cur_pos, direction)?;
+            let mut neighbors = vec![new_pos];
+
+            if cur_type == BoxLeft && (direction == Up || direction == Down) {
+                neighbors.push(self.next_pos(cur_pos, Right)?);
+            }
+            else if cur_type == BoxRight && (direction == Up || direction == Down) {
+                neighbors.push(self.next_pos(cur_pos, Left)?);
+            }
+
+            neighbors.into_iter().for_each(|n| {
+                if !seen.contains(&n) {
+                    seen.insert(n);
+                    queue.push_back(n);
+                }
+            });
+
+            updates.insert(new_pos, cur_type);
+            updates.entry(cur_pos).or_insert(Empty);
+        }
+
+        Some(updates)
+    }
+}
+
+/// Two versions of a level side by side, followed by just the cells that differ between them.
+fn render_diff(before: &Level, after: &Level) -> String {
+    let (before, after) = (before.to_string(), after.to_string());
+    let width = before.lines().map(str::len).chain(["before".len()]).max().unwrap();
+    let line = |old: &str, new: &str, changed: &str| {
+        format!("{old:width$}   {new:width$}   {changed}").trim_end().to_owned()
+    };
+
+    let mut lines = vec![line("before", "after", "changed")];
+    for (old, new) in before.lines().zip(after.lines()) {
+        let changed: String = old.chars().zip(new.chars())
+            .map(|(a, b)| if a == b { ' ' } else { b })
+            .collect();
+        lines.push(line(old, new, &changed));
+    }
+
+    lines.join("\n")
+}
+
+/// How to undo one move: the previous contents of the cells it changed, and where the robot was.
+#[derive(Debug)]
+struct MoveDiff {
+    before: Vec<((usize, usize), Contents)>,
+    robot_pos: (usize, usize)
+}
+
+/// A level along with the diffs of the moves made so far, most recent last.
+#[derive(Debug)]
+struct History {
+    level: Level,
+    diffs: Vec<MoveDiff>
+}
+
+impl History {
+    /// Undo the most recent move; returns `false` if there were none left.
+    fn undo(&mut self) -> bool {
+        match self.diffs.pop() {
+            Some(diff) => {
+                self.level.undo(&diff);
+                true
+            },
+            None => false
+        }
+    }
+}
+
+/// A level with the robot's moves still to make, made one per [`Simulation`] step.
+///
+/// [`simulation`] starts one from the input, and it displays as the level's map.
+#[derive(Debug)]
+pub struct Warehouse {
+    level: Level,
+    directions: Vec<Direction>,
+    moves_made: usize
+}
+
+impl Warehouse {
+    fn new(level: Level, directions: Vec<Direction>) -> Warehouse {
+        Warehouse { level, directions, moves_made: 0 }
+    }
+
+    /// How many of the robot's moves have been made.
+    pub fn moves_made(&self) -> usize {
+        self.moves_made
+    }
+}
+
+impl Display for Warehouse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.level.fmt(f)
+    }
+}
+
+impl Simulation for Warehouse {
+    fn step(&mut self) -> StepResult {
+        let Some(&direction) = self.directions.get(self.moves_made) else {
+            return StepResult::Finished;
+        };
+
+        self.level.apply_move(direction);
+        self.moves_made += 1;
+        if self.moves_made == self.directions.len() {
+            StepResult::Finished
+        }
+        else {
+            StepResult::Running
+        }
+    }
+}
+
+mod parse {
+    use nom::branch::alt;
+    use nom::character::complete::{char, line_ending, multispace0};
+    use nom::combinator::value;
+    use nom::IResult;
+    use nom::multi::{many1, separated_list1};
+    use nom::sequence::preceded;
+    use crate::parse::two_sections;
+    use super::{Contents, Direction};
+
+    /// The map's rows as written, and the robot's moves.
+    pub fn parse_input(input: &str) -> IResult<&str, (Vec<Vec<Contents>>, Vec<Direction>)> {
+        let cellp = alt((
+            value(Contents::Box, char('O')),
+            value(Contents::BoxLeft, char('[')),
+            value(Contents::BoxRight, char(']')),
+            value(Contents::Empty, char('.')),
+            value(Contents::Wall, char('#')),
+            value(Contents::Robot, char('@'))
+        ));
+
+        let boardp = separated_list1(
+            line_ending,
+            many1(cellp)
+        );
+
+        let directionp = alt((
+            value(Direction::Up, char('^')),
+            value(Direction::Down, char('v')),
+            value(Direction::Left, char('<')),
+            value(Direction::Right, char('>'))
+        ));
+
+        // The moves are split over several lines, which may end in `\r\n`.
+        let directionsp = many1(preceded(multispace0, directionp));
+
+        two_sections(boardp, directionsp)(input)
+    }
+}
+
+/// The warehouse after the robot has made all of its moves, with the map scaled by `scale`.
+fn simulate(input: &str, scale: Scale) -> Level {
+    let mut warehouse = simulation(input, scale).unwrap();
+    warehouse.run_until(|_| false);
+
+    warehouse.level
+}
+
+/// The warehouse in the input, with the map scaled by `scale`, before the robot's first move.
+pub fn simulation(input: &str, scale: Scale) -> Result<Warehouse, String> {
+    let (level, directions) = Level::parse(input, scale)?;
+    Ok(Warehouse::new(level, directions))
+}
+
+/// Sum of the boxes' GPS coordinates, using the left half of each wide box.
+fn gps_total(level: &Level) -> usize {
+    (0..level.rows).cartesian_product(0..level.cols)
+        .filter(|&pos| matches!(level[pos], Box | BoxLeft))
+        .map(|(i, j)| 100*i + j)
+        .sum()
+}
+
+/// Sum of the boxes' GPS coordinates after all the moves.
+pub fn part1(input: &str) -> usize {
+    gps_total(&simulate(input, Scale::Single))
+}
+
+/// The same on the widened map.
+pub fn part2(input: &str) -> usize {
+    gps_total(&simulate(input, Scale::Double))
+}
+
+/// Whether every wide box on the board still has both of its halves side by side.
+fn boxes_whole(level: &Level) -> bool {
+    level.board.iter().all(|row| {
+        row.windows(2).all(|w| (w[0] == BoxLeft) == (w[1] == BoxRight))
+            && row.first() != Some(&BoxRight)
+            && row.last() != Some(&BoxLeft)
+    })
+}
+
+/// The first move (counting from 1) after which some wide box is split, found by rewinding the
+/// robot's moves one at a time.
+fn first_split(mut history: History) -> Option<usize> {
+    let mut first = None;
+    loop {
+        if !boxes_whole(&history.level) {
+            first = Some(history.diffs.len());
+        }
+
+        if !history.undo() {
+            return first;
+        }
+    }
+}
+
+/// Replay the moves on the widened map, and report the first one that breaks a box in two.
+pub fn check_boxes(input: &str) -> String {
+    let (level, directions) = Level::parse(input, Scale::Double).unwrap();
+    let history = level.apply_moves_with_history(&directions);
+
+    match first_split(history) {
+        None => format!("All {} moves keep every box whole", directions.len()),
+        Some(0) => "Boxes are already split before the first move".to_owned(),
+        Some(step) => format!("Move {step} ({:?}) splits a box", directions[step - 1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_snapshot;
+    use crate::simulation::{Simulation, StepResult};
+    use super::{first_split, gps_total, part1, part2, render_diff, simulate, simulation};
+    use super::{Contents::*, Direction, Level, MoveDiff, Scale, Violation};
+
+    const SMALL_INPUT: &str = "########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<";
+
+    const LARGE_INPUT: &str = "##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
+
+    crate::test_samples! {
+        part1, part2;
+        (SMALL_INPUT, 2028, _),
+        (LARGE_INPUT, 10092, 9021),
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(SMALL_INPUT), 2028);
+        assert_eq!(part1(LARGE_INPUT), 10092);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(LARGE_INPUT), 9021);
+    }
+
+    #[test]
+    fn test_windows_line_endings() {
+        // Even without normalizing, the parser copes with CRLF and trailing blank lines.
+        let crlf = format!("{}\r\n\r\n", LARGE_INPUT.replace('\n', "\r\n"));
+        assert_eq!(part1(&crlf), 10092);
+        assert_eq!(part2(&crlf), 9021);
+    }
+
+    #[test]
+    fn test_final_renders() {
+        assert_snapshot!("day15_small_final", simulate(SMALL_INPUT, Scale::Single).to_string());
+        assert_snapshot!("day15_large_final", simulate(LARGE_INPUT, Scale::Single).to_string());
+        assert_snapshot!(
+            "day15_large_wide_final",
+            simulate(LARGE_INPUT, Scale::Double).to_string()
+        );
+    }
+
+    const NARROW_INPUT: &str = "#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^";
+
+    const WIDE_INPUT: &str = "##############
+##......##..##
+##..........##
+##....[][]@.##
+##....[]....##
+##..........##
+##############
+
+<vv<<^^<<^^";
+
+    #[test]
+    fn test_parse_wide() {
+        let (narrow, directions) = Level::parse(NARROW_INPUT, Scale::Double).unwrap();
+        let (wide, wide_directions) = Level::parse(WIDE_INPUT, Scale::Single).unwrap();
+        assert_eq!(wide, narrow);
+        assert_eq!(wide_directions, directions);
+        assert_eq!(gps_total(&simulate(WIDE_INPUT, Scale::Single)), 618);
+        assert_eq!(part2(NARROW_INPUT), 618);
+
+        // The widened large example, read back in as it's printed.
+        let (level, _) = Level::parse(LARGE_INPUT, Scale::Double).unwrap();
+        let moves = LARGE_INPUT.split_once("\n\n").unwrap().1;
+        let printed = format!("{level}\n{moves}");
+        assert_eq!(gps_total(&simulate(&printed, Scale::Single)), 9021);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Level::parse(WIDE_INPUT, Scale::Double).is_err());
+        assert!(Level::parse(&WIDE_INPUT.replace("[]@", "[.@"), Scale::Single).is_err());
+        assert!(Level::parse(&WIDE_INPUT.replace("][", ".."), Scale::Single).is_err());
+        assert!(Level::parse(&NARROW_INPUT.replace('@', "."), Scale::Single).is_err());
+        assert!(Level::parse(&NARROW_INPUT.replace("#..O..#", "#..O.#"), Scale::Single).is_err());
+    }
+
+    #[test]
+    fn test_undo() {
+        for scale in [Scale::Single, Scale::Double] {
+            let (initial, directions) = Level::parse(LARGE_INPUT, scale).unwrap();
+
+            let mut history = initial.clone().apply_moves_with_history(&directions);
+            assert_eq!(history.level, simulate(LARGE_INPUT, scale));
+
+            // Rewind to just after the 100th move, and compare against replaying that far.
+            while history.diffs.len() > 100 {
+                assert!(history.undo());
+            }
+            let mut replayed = initial.clone();
+            directions[..100].iter().for_each(|&d| { replayed.apply_move(d); });
+            assert_eq!(history.level, replayed);
+
+            while history.undo() {}
+            assert_eq!(history.level, initial);
+        }
+    }
+
+    #[test]
+    fn test_warehouse() {
+        let (initial, directions) = Level::parse(LARGE_INPUT, Scale::Double).unwrap();
+        let mut replayed = initial.clone();
+        directions[..100].iter().for_each(|&d| { replayed.apply_move(d); });
+
+        let mut warehouse = simulation(LARGE_INPUT, Scale::Double).unwrap();
+        assert_eq!(warehouse.to_string(), initial.to_string());
+        assert_eq!(warehouse.run_for(100), (100, StepResult::Running));
+        assert_eq!((warehouse.moves_made(), &warehouse.level), (100, &replayed));
+
+        assert_eq!(warehouse.run_until(|_| false), (directions.len() - 100, StepResult::Finished));
+        assert_eq!(warehouse.level, simulate(LARGE_INPUT, Scale::Double));
+        assert_eq!(warehouse.step(), StepResult::Finished);
+    }
+
+    #[test]
+    fn test_first_split() {
+        let (level, directions) = Level::parse(LARGE_INPUT, Scale::Double).unwrap();
+        let history = level.apply_moves_with_history(&directions);
+        assert_eq!(first_split(history), None);
+
+        let (mut level, directions) = Level::parse(SMALL_INPUT, Scale::Double).unwrap();
+        let (i, j) = level.robot_pos;
+        level[(i, j + 2)] = BoxRight;
+        assert_eq!(first_split(level.apply_moves_with_history(&directions)), Some(0));
+    }
+
+    #[test]
+    fn test_validate() {
+        let (mut level, _) = Level::parse(WIDE_INPUT, Scale::Single).unwrap();
+        assert_eq!(level.validate(), Ok(()));
+
+        let (i, j) = level.robot_pos;
+        level[(i, j - 1)] = Empty;
+        level[(1, 2)] = Robot;
+        level.robot_pos = (2, 2);
+        assert_eq!(level.validate(), Err(vec![
+            Violation::StrayRobot((1, 2)),
+            Violation::SplitBox((3, 8)),
+            Violation::StrayRobot((3, 10)),
+            Violation::MissingRobot((2, 2))
+        ]));
+
+        assert_eq!(
+            Level::parse(&WIDE_INPUT.replace("[]@", "[.@"), Scale::Single).unwrap_err(),
+            "Invalid map: Half a box at (3, 8)"
+        );
+    }
+
+    #[test]
+    fn test_render_diff() {
+        let (before, _) = Level::parse("#####\n#@O.#\n#####\n\n>", Scale::Single).unwrap();
+        let after = simulate("#####\n#@O.#\n#####\n\n>", Scale::Single);
+        let expected = "\
+before   after    changed
+#####    #####
+#@O.#    #.@O#     .@O
+#####    #####";
+        assert_eq!(render_diff(&before, &after), expected);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Moving Left broke the level:\nHalf a box at (3, 8)")]
+    fn test_check_move() {
+        // As if a left push had moved only half of the box next to the robot.
+        let (mut level, _) = Level::parse(WIDE_INPUT, Scale::Single).unwrap();
+        let diff = MoveDiff { before: vec![((3, 9), BoxRight)], robot_pos: level.robot_pos };
+        level[(3, 9)] = Empty;
+        level.check_move(Direction::Left, &diff);
+    }
+}
@@ -0,0 +1,725 @@
+//! Day 16: Reindeer Maze
+//!
+//! Find the cheapest paths through a maze, where moving forward costs 1 and turning costs 1000.
+//!
+//! # Approach
+//!
+//! Dijkstra's algorithm on the (position, heading) state graph, keeping every predecessor on a
+//! cheapest path to each state rather than just one. The tiles on any best path are then everything
+//! reachable by walking predecessors back from the end.
+//!
+//! Turning to face a wall is never useful except to turn around in a dead end, so the state graph
+//! leaves out those turns (adding direct 180 degree turns where needed); `--pruning` compares the
+//! states explored and the edges with and without them. The "all-turns" strategies keep them.
+//!
+//! Part 1 only needs the lowest cost, so it has two more strategies: "a-star" guides the search
+//! with a lower bound on the cost still to come (see [`heuristic`]), and "contracted" first merges
+//! the moves along each corridor into one edge ([`Graph::contracted`]). `cargo bench --bench days`
+//! times every strategy on generated mazes of up to 501×501.
+//!
+//! [`layouts`] prepares a plain breadth-first search of the maze in each grid layout, for the bench
+//! to time. With `--dump DIR`, part 1 writes the lowest cost of reaching each tile (facing any
+//! way) to `DIR/day16/distances.csv`.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::ops::Deref;
+use std::fmt::Write;
+use itertools::Itertools;
+use nom::branch::alt;
+use nom::character::complete::{char, line_ending};
+use nom::combinator::{map, value};
+use nom::IResult;
+use nom::multi::{many1, separated_list1};
+use smallvec::SmallVec;
+use crate::cache::parse_cached;
+use crate::dump::{csv, DebugSink};
+use crate::graph::{grid_with_headings, to_dot, DotKind, Heading, Turns, WeightedGraph};
+use crate::grid::Direction::{self, *};
+use crate::grid::{ColumnMajor, Grid, Layout, LayoutKernels, Nested, RowMajor};
+use crate::hash::{HashMap, HashSet};
+use crate::strategy::Strategies;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "parse-cache", derive(serde::Serialize, serde::Deserialize))]
+enum CellType { Start, End, Empty, Wall }
+use CellType::*;
+
+
+#[cfg_attr(feature = "parse-cache", derive(serde::Serialize, serde::Deserialize))]
+struct Maze {
+    cells: Vec<Vec<CellType>>,
+    start: (usize, usize),
+    end: (usize, usize)
+}
+
+impl Maze {
+    fn from_cells(cells: Vec<Vec<CellType>>) -> Maze {
+        let rows = cells.len();
+        let cols = cells[0].len();
+        let start = (0..rows).cartesian_product(0..cols)
+            .find(|&(i, j)| cells[i][j] == Start)
+            .unwrap();
+
+        let end = (0..rows).cartesian_product(0..cols)
+            .find(|&(i, j)| cells[i][j] == End)
+            .unwrap();
+
+        Maze { cells, start, end }
+    }
+}
+
+fn parse_input(input: &str) -> IResult<&str, Maze> {
+    map(
+        separated_list1(
+            line_ending,
+            many1(
+                alt((
+                    value(Start, char('S')),
+                    value(End, char('E')),
+                    value(Empty, char('.')),
+                    value(Wall, char('#'))
+                ))
+            )
+        ), Maze::from_cells
+    )(input)
+}
+
+/// A position in the maze, and the direction the reindeer is facing.
+type Node = Heading;
+
+struct Graph {
+    adj_list: WeightedGraph<Node>,
+    bounds: (usize, usize)
+}
+
+/// Cost of moving one space forward, in the reindeer's maze.
+const STEP_COST: usize = 1;
+
+/// Cost of turning 90 degrees in place, in the reindeer's maze.
+const TURN_COST: usize = 1000;
+
+impl Graph {
+    /// The (position, heading) state graph of `maze`, where moving forward one space costs
+    /// `step_cost` and turning 90 degrees costs `turn_cost`.
+    fn from_maze(maze: &Maze, step_cost: usize, turn_cost: usize, turns: Turns) -> Graph {
+        let passable = |&cell: &CellType| cell != Wall;
+        let adj_list = grid_with_headings(&maze.cells, step_cost, turn_cost, turns, passable);
+        Graph { adj_list, bounds: (maze.cells.len(), maze.cells[0].len()) }
+    }
+
+    fn num_edges(&self) -> usize {
+        self.adj_list.values().map(Vec::len).sum()
+    }
+
+    /// This graph with every run of states that have just one way in and one way out (along a
+    /// corridor) merged into single edges, keeping only the other states and those in `keep`.
+    fn contracted(&self, keep: impl Fn(Node) -> bool) -> Graph {
+        let mut in_degrees: HashMap<Node, usize> = HashMap::default();
+        for &(node, _) in self.adj_list.values().flatten() {
+            *in_degrees.entry(node).or_default() += 1;
+        }
+        let kept = |node: Node| {
+            keep(node) || self.adj_list[&node].len() != 1 || in_degrees.get(&node) != Some(&1)
+        };
+
+        // Each state dropped has one way in, so the runs can't lead into a loop of dropped states.
+        let adj_list = self.adj_list.iter()
+            .filter(|&(&node, _)| kept(node))
+            .map(|(&node, edges)| {
+                let edges = edges.iter().map(|&(mut next, mut cost)| {
+                    while !kept(next) {
+                        let (after, weight) = self.adj_list[&next][0];
+                        (next, cost) = (after, cost + weight);
+                    }
+                    (next, cost)
+                });
+                (node, edges.collect())
+            })
+            .collect();
+
+        Graph { adj_list, bounds: self.bounds }
+    }
+}
+
+/// The maze, via the parse cache, and its state graph. Only the maze is cached, so the graph
+/// always has the current step and turn costs.
+fn parse_graph(input: &str) -> (Maze, Graph) {
+    let maze = parse_cached(input, |input| parse_input(input).unwrap().1);
+    let graph = Graph::from_maze(&maze, STEP_COST, TURN_COST, Turns::Useful);
+    (maze, graph)
+}
+
+/// The maze and its state graph with every turn left in, built afresh.
+fn parse_graph_all_turns(input: &str) -> (Maze, Graph) {
+    let maze = parse_input(input).unwrap().1;
+    let graph = Graph::from_maze(&maze, STEP_COST, TURN_COST, Turns::All);
+    (maze, graph)
+}
+
+#[derive(Eq, PartialEq)]
+struct HeapElem { node: Node, cost: usize }
+
+impl Ord for HeapElem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.cmp(&self.cost)
+            .then_with(|| self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for HeapElem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+/// Predecessors of a state on cheapest paths to it. A state has at most three incoming moves (a
+/// step forward and two turns), so these never spill onto the heap; part 2's "vec-preds" strategy
+/// keeps them in `Vec`s instead, for the bench to compare.
+type Preds = SmallVec<[Node; 3]>;
+
+/// A list of predecessors, as kept by [`BestPaths`].
+trait PredList: Clone + Default + Deref<Target=[Node]> {
+    fn push(&mut self, node: Node);
+    fn clear(&mut self);
+}
+
+impl PredList for Preds {
+    fn push(&mut self, node: Node) { SmallVec::push(self, node) }
+    fn clear(&mut self) { SmallVec::clear(self) }
+}
+
+impl PredList for Vec<Node> {
+    fn push(&mut self, node: Node) { Vec::push(self, node) }
+    fn clear(&mut self) { Vec::clear(self) }
+}
+
+/// Result of [`dijkstra`]: every state's lowest cost from the start, and its predecessors on the
+/// paths achieving it, stored flat and indexed by state id.
+struct BestPaths<P = Preds> {
+    cols: usize,
+    costs: Vec<usize>,
+    preds: Vec<P>
+}
+
+impl<P: PredList> BestPaths<P> {
+    fn new(bounds: (usize, usize)) -> BestPaths<P> {
+        let states = bounds.0 * bounds.1 * 4;
+        BestPaths {
+            cols: bounds.1,
+            costs: vec![usize::MAX; states],
+            preds: vec![P::default(); states]
+        }
+    }
+
+    fn id(&self, ((i, j), direction): Node) -> usize {
+        (i * self.cols + j) * 4 + direction as usize
+    }
+
+    /// Lowest cost of reaching `node`, or `usize::MAX` if it can't be reached.
+    fn cost(&self, node: Node) -> usize {
+        self.costs[self.id(node)]
+    }
+
+    /// States just before `node` on its cheapest paths.
+    fn preds(&self, node: Node) -> &[Node] {
+        &self.preds[self.id(node)]
+    }
+
+    /// Number of states reachable from the start.
+    fn num_reached(&self) -> usize {
+        self.costs.iter().filter(|&&cost| cost != usize::MAX).count()
+    }
+}
+
+fn dijkstra(graph: &Graph, from: Node) -> BestPaths {
+    dijkstra_with(graph, from)
+}
+
+/// [`dijkstra`], keeping predecessors in lists of type `P`.
+fn dijkstra_with<P: PredList>(graph: &Graph, from: Node) -> BestPaths<P> {
+    let mut result: BestPaths<P> = BestPaths::new(graph.bounds);
+    let id = result.id(from);
+    result.costs[id] = 0;
+
+    let mut heap = BinaryHeap::new();
+    heap.push(HeapElem { node: from, cost: 0 });
+
+    while let Some(HeapElem { node, cost }) = heap.pop() {
+        if result.cost(node) > cost { continue; }
+
+        for &(neighbor, weight) in graph.adj_list[&node].iter() {
+            let id = result.id(neighbor);
+            if result.costs[id] == cost + weight {
+                result.preds[id].push(node);
+            }
+            else if result.costs[id] > cost + weight {
+                result.preds[id].clear();
+                result.preds[id].push(node);
+                result.costs[id] = cost + weight;
+                heap.push(HeapElem { node: neighbor, cost: cost + weight });
+            }
+        }
+    }
+
+    result
+}
+
+/// The lowest cost of reaching each tile, facing any way, as CSV; unreachable tiles are blank.
+fn distance_map(maze: &Maze, result: &BestPaths) -> String {
+    let rows = maze.cells.iter().enumerate().map(|(i, row)| {
+        (0..row.len()).map(move |j| {
+            let cost = Direction::ALL.iter().map(|&d| result.cost(((i, j), d))).min().unwrap();
+            if cost == usize::MAX { String::new() } else { cost.to_string() }
+        })
+    });
+
+    csv(rows)
+}
+
+/// Lowest cost to get from the start (facing east) to the end (facing any way).
+fn lowest_cost(maze: &Maze, graph: &Graph) -> usize {
+    let start = (maze.start, Right);
+    let result = dijkstra(graph, start);
+    DebugSink::new("day16").write("distances.csv", || distance_map(maze, &result));
+
+    Direction::ALL.iter()
+        .map(|&d| (maze.end, d))
+        .map(|n| result.cost(n))
+        .min()
+        .unwrap()
+}
+
+/// A lower bound on the cost from `node` to `end` in the reindeer's maze: a step for every row and
+/// column between them, and a turn to face each way still to go (or back round, if facing away).
+///
+/// Each move changes this by no more than it costs, so [`a_star`] never has to revisit a state.
+fn heuristic(((i, j), dir): Node, end: (usize, usize)) -> usize {
+    let vertical = match end.0.cmp(&i) {
+        Ordering::Less => Some(Up),
+        Ordering::Greater => Some(Down),
+        Ordering::Equal => None
+    };
+    let horizontal = match end.1.cmp(&j) {
+        Ordering::Less => Some(Left),
+        Ordering::Greater => Some(Right),
+        Ordering::Equal => None
+    };
+
+    let turns = match (vertical, horizontal) {
+        (None, None) => 0,
+        (Some(way), None) | (None, Some(way)) if way == dir => 0,
+        (Some(way), None) | (None, Some(way)) if way == dir.reverse() => 2,
+        (Some(_), None) | (None, Some(_)) => 1,
+        (Some(v), Some(h)) => if v == dir || h == dir { 1 } else { 2 }
+    };
+
+    (i.abs_diff(end.0) + j.abs_diff(end.1)) * STEP_COST + turns * TURN_COST
+}
+
+/// Lowest cost to get from the start (facing east) to the end, by A* search with [`heuristic`].
+fn a_star(maze: &Maze, graph: &Graph) -> usize {
+    let cols = graph.bounds.1;
+    let id = |((i, j), direction): Node| (i * cols + j) * 4 + direction as usize;
+    let estimate = |node: Node, cost: usize| cost + heuristic(node, maze.end);
+
+    let start = (maze.start, Right);
+    let mut costs = vec![usize::MAX; graph.bounds.0 * cols * 4];
+    costs[id(start)] = 0;
+    let mut heap = BinaryHeap::from([HeapElem { node: start, cost: estimate(start, 0) }]);
+
+    while let Some(HeapElem { node, cost }) = heap.pop() {
+        let so_far = costs[id(node)];
+        if cost > estimate(node, so_far) { continue; }
+        if node.0 == maze.end {
+            return so_far;
+        }
+
+        for &(neighbor, weight) in graph.adj_list[&node].iter() {
+            if so_far + weight < costs[id(neighbor)] {
+                costs[id(neighbor)] = so_far + weight;
+                heap.push(HeapElem { node: neighbor, cost: estimate(neighbor, so_far + weight) });
+            }
+        }
+    }
+
+    panic!("The end must be reachable");
+}
+
+fn part1_pruned(input: &str) -> usize {
+    let (maze, graph) = parse_graph(input);
+    lowest_cost(&maze, &graph)
+}
+
+fn part1_all_turns(input: &str) -> usize {
+    let (maze, graph) = parse_graph_all_turns(input);
+    lowest_cost(&maze, &graph)
+}
+
+fn part1_a_star(input: &str) -> usize {
+    let (maze, graph) = parse_graph(input);
+    a_star(&maze, &graph)
+}
+
+fn part1_contracted(input: &str) -> usize {
+    let (maze, graph) = parse_graph(input);
+    let contracted = graph.contracted(|(pos, _)| pos == maze.start || pos == maze.end);
+    lowest_cost(&maze, &contracted)
+}
+
+/// Lowest possible score from the start (facing east) to the end.
+pub const PART1: Strategies<usize> = Strategies(&[
+    ("pruned", part1_pruned),
+    ("all-turns", part1_all_turns),
+    ("a-star", part1_a_star),
+    ("contracted", part1_contracted)
+]);
+
+/// Number of tiles on at least one of the best paths from the start (facing east) to the end.
+fn best_tiles(maze: &Maze, graph: &Graph) -> usize {
+    best_tiles_with::<Preds>(maze, graph)
+}
+
+/// [`best_tiles`], keeping predecessors in lists of type `P`.
+fn best_tiles_with<P: PredList>(maze: &Maze, graph: &Graph) -> usize {
+    let start = (maze.start, Right);
+
+    let result = dijkstra_with::<P>(graph, start);
+
+    let end = Direction::ALL.iter()
+        .map(|&d| (maze.end, d))
+        .min_by_key(|&n| result.cost(n))
+        .unwrap();
+
+    // Many best paths can share a state, so only walk back from each one once.
+    let mut seen = HashSet::from_iter([end]);
+    let mut queue = VecDeque::from([end]);
+    while let Some(node) = queue.pop_front() {
+        for &pred in result.preds(node) {
+            if seen.insert(pred) {
+                queue.push_back(pred);
+            }
+        }
+    }
+
+    seen.into_iter().map(|(pos, _)| pos).unique().count()
+}
+
+fn part2_pruned(input: &str) -> usize {
+    let (maze, graph) = parse_graph(input);
+    best_tiles(&maze, &graph)
+}
+
+fn part2_all_turns(input: &str) -> usize {
+    let (maze, graph) = parse_graph_all_turns(input);
+    best_tiles(&maze, &graph)
+}
+
+fn part2_vec_preds(input: &str) -> usize {
+    let (maze, graph) = parse_graph(input);
+    best_tiles_with::<Vec<Node>>(&maze, &graph)
+}
+
+/// Number of tiles on at least one of the best paths.
+pub const PART2: Strategies<usize> = Strategies(&[
+    ("pruned", part2_pruned),
+    ("all-turns", part2_all_turns),
+    ("vec-preds", part2_vec_preds)
+]);
+
+/// States explored and edges with every turn in the state graph, and with the useless ones
+/// pruned.
+pub fn pruning(input: &str) -> String {
+    let maze = parse_input(input).unwrap().1;
+
+    let mut out = String::new();
+    writeln!(out, "{:>6} {:>8} {:>8}", "turns", "states", "edges").unwrap();
+
+    let mut answers = Vec::new();
+    for (name, turns) in [("all", Turns::All), ("useful", Turns::Useful)] {
+        let graph = Graph::from_maze(&maze, STEP_COST, TURN_COST, turns);
+        answers.push((lowest_cost(&maze, &graph), best_tiles(&maze, &graph)));
+
+        let reached = dijkstra(&graph, (maze.start, Right)).num_reached();
+        writeln!(out, "{name:>6} {reached:>8} {:>8}", graph.num_edges()).unwrap();
+    }
+    assert_eq!(answers[0], answers[1], "Pruning changed the answers");
+
+    out
+}
+
+/// Fewest steps from `from` to `to` through `cells`, ignoring the cost of turning.
+fn fewest_steps<L: Layout>(
+    cells: &Grid<CellType, L>,
+    from: (usize, usize),
+    to: (usize, usize)
+) -> usize {
+    let (rows, cols) = cells.bounds();
+    let mut steps: Grid<Option<usize>, L> = Grid::from_fn(rows, cols, |_| None);
+    let mut queue = VecDeque::from([from]);
+    steps[from] = Some(0);
+
+    while let Some(pos) = queue.pop_front() {
+        let n = steps[pos].unwrap();
+        if pos == to {
+            return n;
+        }
+
+        for next in cells.neighbors(pos) {
+            if cells[next] != Wall && steps[next].is_none() {
+                steps[next] = Some(n + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    panic!("The end must be reachable");
+}
+
+/// [`fewest_steps`] from start to end, with the maze copied into layout `L` up front.
+fn search_in<L: Layout + 'static>(maze: &Maze) -> Box<dyn Fn() -> usize> {
+    let cells: Grid<CellType, L> = Grid::from_rows_in(maze.cells.clone());
+    let (start, end) = (maze.start, maze.end);
+    Box::new(move || fewest_steps(&cells, start, end))
+}
+
+/// A breadth-first search through the maze in each grid layout.
+pub fn layouts(input: &str) -> LayoutKernels {
+    let maze = parse_input(input).unwrap().1;
+    vec![
+        (RowMajor::NAME, search_in::<RowMajor>(&maze)),
+        (ColumnMajor::NAME, search_in::<ColumnMajor>(&maze)),
+        (Nested::NAME, search_in::<Nested>(&maze))
+    ]
+}
+
+/// Graphviz rendering of the (position, heading) state graph, with every state and move lying on
+/// some best path highlighted.
+pub fn dot(input: &str) -> String {
+    let (maze, graph) = parse_graph(input);
+    let start = (maze.start, Right);
+
+    let result = dijkstra(&graph, start);
+
+    let end = Direction::ALL.iter()
+        .map(|&d| (maze.end, d))
+        .min_by_key(|&n| result.cost(n))
+        .unwrap();
+
+    let mut best_nodes = HashSet::default();
+    let mut best_edges = HashSet::default();
+    let mut queue = VecDeque::new();
+    best_nodes.insert(end);
+    queue.push_back(end);
+
+    while let Some(node) = queue.pop_front() {
+        for &pred in result.preds(node) {
+            best_edges.insert((pred, node));
+            if best_nodes.insert(pred) {
+                queue.push_back(pred);
+            }
+        }
+    }
+
+    let edges = graph.adj_list.iter()
+        .sorted()
+        .flat_map(|(&from, tos)| tos.iter().map(move |&(to, _)| (from, to)));
+
+    let label = |&((x, y), direction): &Node| {
+        let arrow = match direction { Up => '^', Down => 'v', Left => '<', Right => '>' };
+        format!("{x},{y} {arrow}")
+    };
+
+    to_dot(DotKind::Directed, edges, label, &best_nodes, &best_edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, VecDeque};
+    use crate::gen::{day16_maze, Rng};
+    use crate::graph::Turns;
+    use crate::grid::Direction;
+    use super::{
+        best_tiles, dijkstra, distance_map, heuristic, layouts, lowest_cost, parse_input,
+        part1_pruned, part2_pruned, CellType, Graph, Maze, PART1, PART2
+    };
+
+    const TEST_INPUT_1: &str = "###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############";
+
+    const TEST_INPUT_2: &str = "#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################";
+
+    crate::test_samples! {
+        part1_pruned, part2_pruned;
+        (TEST_INPUT_1, 7036, 45),
+        (TEST_INPUT_2, 11048, 64),
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1_pruned(TEST_INPUT_1), 7036);
+        assert_eq!(part1_pruned(TEST_INPUT_2), 11048);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2_pruned(TEST_INPUT_1), 45);
+        assert_eq!(part2_pruned(TEST_INPUT_2), 64);
+    }
+
+    /// Plain breadth-first distance from start to end, ignoring headings.
+    fn bfs_distance(maze: &Maze) -> usize {
+        let mut dists = HashMap::from([(maze.start, 0)]);
+        let mut queue = VecDeque::from([maze.start]);
+        let bounds = (maze.cells.len(), maze.cells[0].len());
+
+        while let Some(pos) = queue.pop_front() {
+            for direction in Direction::ALL {
+                if let Some(next) = direction.step(pos, bounds) {
+                    if maze.cells[next.0][next.1] != CellType::Wall && !dists.contains_key(&next) {
+                        dists.insert(next, dists[&pos] + 1);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        dists[&maze.end]
+    }
+
+    #[test]
+    fn test_costs() {
+        for input in [TEST_INPUT_1, TEST_INPUT_2] {
+            let maze = parse_input(input).unwrap().1;
+
+            for turns in [Turns::All, Turns::Useful] {
+                let free_turns = Graph::from_maze(&maze, 1, 0, turns);
+                assert_eq!(lowest_cost(&maze, &free_turns), bfs_distance(&maze));
+
+                let doubled = Graph::from_maze(&maze, 2, 2000, turns);
+                assert_eq!(lowest_cost(&maze, &doubled), 2 * part1_pruned(input));
+            }
+        }
+    }
+
+    #[test]
+    fn test_pruning() {
+        for input in [TEST_INPUT_1, TEST_INPUT_2] {
+            let maze = parse_input(input).unwrap().1;
+            let all = Graph::from_maze(&maze, 1, 1000, Turns::All);
+            let useful = Graph::from_maze(&maze, 1, 1000, Turns::Useful);
+
+            assert_eq!(lowest_cost(&maze, &useful), lowest_cost(&maze, &all));
+            assert_eq!(best_tiles(&maze, &useful), best_tiles(&maze, &all));
+
+            let start = (maze.start, Direction::Right);
+            assert!(dijkstra(&useful, start).num_reached() < dijkstra(&all, start).num_reached());
+            assert!(useful.num_edges() < all.num_edges());
+        }
+    }
+
+    #[test]
+    fn test_strategies() {
+        let generated = day16_maze(&mut Rng::new(16), 41, 10);
+        for input in [TEST_INPUT_1, TEST_INPUT_2, &generated] {
+            let expected = part1_pruned(input);
+            for &(name, solve) in PART1.0 {
+                assert_eq!(solve(input), expected, "part 1 {name} on\n{input}");
+            }
+
+            let expected = part2_pruned(input);
+            for &(name, solve) in PART2.0 {
+                assert_eq!(solve(input), expected, "part 2 {name} on\n{input}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_heuristic() {
+        // Facing away from an end straight ahead takes two turns; facing across it takes one.
+        assert_eq!(heuristic(((5, 1), Direction::Down), (1, 1)), 4 + 2000);
+        assert_eq!(heuristic(((5, 1), Direction::Right), (1, 1)), 4 + 1000);
+        assert_eq!(heuristic(((5, 1), Direction::Up), (1, 3)), 6 + 1000);
+        assert_eq!(heuristic(((5, 1), Direction::Left), (1, 3)), 6 + 2000);
+
+        for input in [TEST_INPUT_1, TEST_INPUT_2] {
+            let maze = parse_input(input).unwrap().1;
+            let graph = Graph::from_maze(&maze, 1, 1000, Turns::Useful);
+            let start = (maze.start, Direction::Right);
+            assert!(heuristic(start, maze.end) <= lowest_cost(&maze, &graph));
+        }
+    }
+
+    #[test]
+    fn test_contracted() {
+        let maze = parse_input(TEST_INPUT_1).unwrap().1;
+        let graph = Graph::from_maze(&maze, 1, 1000, Turns::Useful);
+        let contracted = graph.contracted(|(pos, _)| pos == maze.start || pos == maze.end);
+        assert!(contracted.adj_list.len() < graph.adj_list.len());
+        assert_eq!(lowest_cost(&maze, &contracted), 7036);
+    }
+
+    #[test]
+    fn test_layouts() {
+        // Fewest steps, not cheapest: the first example's best path has 36 steps, but 28 will do.
+        assert!(layouts(TEST_INPUT_1).iter().all(|(_, search)| search() == 28));
+    }
+
+    #[test]
+    fn test_distance_map() {
+        let maze = parse_input("#####\n#..E#\n#S#.#\n#####").unwrap().1;
+        let graph = Graph::from_maze(&maze, 1, 1000, Turns::Useful);
+        let distances = distance_map(&maze, &dijkstra(&graph, (maze.start, Direction::Right)));
+        assert_eq!(distances, ",,,,\n,1001,2002,2003,\n,0,,3004,\n,,,,\n");
+    }
+
+    #[test]
+    fn test_many_best_paths() {
+        // A corridor past 40 pillars, each with an equally good way round above and below: 2^40
+        // best paths, which only finishes if each state on them is walked back from just once.
+        let units = 40;
+        let row = |ends: (&str, &str), unit: &str| {
+            format!("#{}{}{}#", ends.0, unit.repeat(units), ends.1)
+        };
+        let input = [
+            row(("#", "#"), "#####"),
+            row(("#", "#"), "....#"),
+            row(("S", "E"), ".##.."),
+            row(("#", "#"), "....#"),
+            row(("#", "#"), "#####"),
+        ].join("\n");
+
+        let maze = parse_input(&input).unwrap().1;
+        for turns in [Turns::All, Turns::Useful] {
+            let graph = Graph::from_maze(&maze, 1, 1000, turns);
+            assert_eq!(best_tiles(&maze, &graph), 11 * units + 2);
+        }
+    }
+}
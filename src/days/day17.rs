@@ -0,0 +1,674 @@
+//! Day 17: Chronospatial Computer
+//!
+//! A tiny 3-bit computer with three registers and eight instructions.
+//!
+//! # Approach
+//!
+//! Part 1 just runs the program. For part 2, the programs shift A right three bits per output, so A
+//! can be built one octal digit at a time from the most significant end, keeping only prefixes
+//! whose output matches the end of the program. Three search orders are available via `--strategy`.
+//!
+//! A machine's state (its registers and instruction pointer) can be saved with
+//! [`Machine::snapshot`] and rewound to with [`Machine::restore`]; the searches use this to rerun
+//! one machine from the start for each candidate, rather than cloning its program each time. With
+//! the `serde` feature, snapshots and whole machines can also be serialized, e.g. to checkpoint a
+//! long search.
+//!
+//! The machine is also a [`Simulation`], stepping one instruction at a time and keeping what it
+//! outputs; [`simulation`] loads the one in the input.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::{Display, Formatter};
+use std::ops::ControlFlow;
+use itertools::Itertools;
+use nom::bytes::complete::tag;
+use nom::character::complete::{char, digit1, line_ending};
+use nom::combinator::{map, map_res};
+use nom::IResult;
+use nom::multi::separated_list1;
+use nom::sequence::{preceded, tuple};
+use crate::sanitize::{check_sections, Format};
+use crate::search::{first_match, Expect};
+use crate::simulation::{Simulation, StepResult};
+use crate::strategy::Strategies;
+
+type Res<T> = Result<T, String>;
+
+#[derive(Copy, Clone, Debug)]
+enum ComboOperand {
+    LiteralZero,
+    LiteralOne,
+    LiteralTwo,
+    LiteralThree,
+    RegisterA,
+    RegisterB,
+    RegisterC
+}
+use ComboOperand::*;
+
+impl ComboOperand {
+    fn from(data: usize) -> Res<ComboOperand> {
+        match data {
+            0 => Ok(ComboOperand::LiteralZero),
+            1 => Ok(ComboOperand::LiteralOne),
+            2 => Ok(ComboOperand::LiteralTwo),
+            3 => Ok(ComboOperand::LiteralThree),
+            4 => Ok(ComboOperand::RegisterA),
+            5 => Ok(ComboOperand::RegisterB),
+            6 => Ok(ComboOperand::RegisterC),
+            _ => Err("Bad data for combo operand".to_owned())
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Op {
+    Adv(ComboOperand),
+    Bxl(usize),
+    Bst(ComboOperand),
+    Jnz(Option<usize>),
+    Bxc,
+    Out(ComboOperand),
+    Bdv(ComboOperand),
+    Cdv(ComboOperand)
+}
+
+use Op::*;
+
+impl Op {
+    fn from(opcode: usize, operand: Option<usize>) -> Res<Op> {
+        match (opcode, operand) {
+            (0, Some(data)) => Ok(Adv(ComboOperand::from(data)?)),
+            (1, Some(data)) => Ok(Bxl(data)),
+            (2, Some(data)) => Ok(Bst(ComboOperand::from(data)?)),
+            (3, data) => Ok(Jnz(data)),
+            (4, Some(_)) => Ok(Bxc),
+            (5, Some(data)) => Ok(Out(ComboOperand::from(data)?)),
+            (6, Some(data)) => Ok(Bdv(ComboOperand::from(data)?)),
+            (7, Some(data)) => Ok(Cdv(ComboOperand::from(data)?)),
+            (other, _) if other <= 7 => Err(format!("Unknown opcode {other}")),
+            (other, None) => Err(format!("Opcode {other} requires an operand")),
+            _ => unreachable!()
+        }
+    }
+}
+
+/// The state of a [`Machine`] between instructions: everything but its program.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Snapshot {
+    register_a: usize,
+    register_b: usize,
+    register_c: usize,
+    instr_ptr: usize
+}
+
+/// The computer, with its program loaded. [`simulation`] loads the one in the input, and it
+/// displays as its registers, instruction pointer and output so far.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Machine {
+    data: Vec<usize>,
+    register_a: usize,
+    register_b: usize,
+    register_c: usize,
+    instr_ptr: usize,
+    /// What the [`Simulation`] steps have output; running the machine any other way hands its
+    /// output straight back instead.
+    output: Vec<usize>
+}
+
+impl Machine {
+    fn new(data: Vec<usize>, register_a: usize, register_b: usize, register_c: usize) -> Machine {
+        Machine { data, register_a, register_b, register_c, instr_ptr: 0, output: Vec::new() }
+    }
+
+    /// Everything output by [`Simulation`] steps so far.
+    pub fn output(&self) -> &[usize] {
+        &self.output
+    }
+
+    /// The current state, to [`restore`](Machine::restore) later.
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            register_a: self.register_a,
+            register_b: self.register_b,
+            register_c: self.register_c,
+            instr_ptr: self.instr_ptr
+        }
+    }
+
+    /// Rewind (or fast-forward) to the state in `snapshot`.
+    fn restore(&mut self, snapshot: &Snapshot) {
+        self.register_a = snapshot.register_a;
+        self.register_b = snapshot.register_b;
+        self.register_c = snapshot.register_c;
+        self.instr_ptr = snapshot.instr_ptr;
+    }
+
+    fn eval_operand(&self, operand: ComboOperand) -> Res<usize> {
+        match operand {
+            LiteralZero => Ok(0),
+            LiteralOne => Ok(1),
+            LiteralTwo => Ok(2),
+            LiteralThree => Ok(3),
+            RegisterA => Ok(self.register_a),
+            RegisterB => Ok(self.register_b),
+            RegisterC => Ok(self.register_c)
+        }
+    }
+
+    /// Whether the instruction pointer has run off the end of the program.
+    fn halted(&self) -> bool {
+        self.instr_ptr >= self.data.len()
+    }
+
+    /// Execute the next instruction, returning its output if it has one.
+    fn execute(&mut self) -> Res<Option<usize>> {
+        let op_code = self.data[self.instr_ptr];
+        let op_data = self.data.get(self.instr_ptr + 1).copied();
+        let op = Op::from(op_code, op_data)?;
+        log::trace!(
+            "{:>3}: {op:?} with A={} B={} C={}",
+            self.instr_ptr, self.register_a, self.register_b, self.register_c
+        );
+
+        match op {
+            Adv(operand) => {
+                let num = self.register_a;
+                let denom = 1 << self.eval_operand(operand)?;
+                self.register_a = num / denom;
+                self.instr_ptr += 2;
+                Ok(None)
+            }
+            Bxl(data) => {
+                self.register_b ^= data;
+                self.instr_ptr += 2;
+                Ok(None)
+            },
+            Bst(operand) => {
+                self.register_b = self.eval_operand(operand)? % 8;
+                self.instr_ptr += 2;
+                Ok(None)
+            },
+            Jnz(data) => {
+                if self.register_a == 0 {
+                    self.instr_ptr += 2;
+                } else {
+                    self.instr_ptr = data.ok_or("jnz activated without data".to_owned())?;
+                }
+                Ok(None)
+            },
+            Bxc => {
+                self.register_b ^= self.register_c;
+                self.instr_ptr += 2;
+                Ok(None)
+            },
+            Out(operand) => {
+                let result = self.eval_operand(operand)? % 8;
+                self.instr_ptr += 2;
+                Ok(Some(result))
+            },
+            Bdv(operand) => {
+                let num = self.register_a;
+                let denom = 1 << self.eval_operand(operand)?;
+                self.register_b = num / denom;
+                self.instr_ptr += 2;
+                Ok(None)
+            },
+            Cdv(operand) => {
+                let num = self.register_a;
+                let denom = 1 << self.eval_operand(operand)?;
+                self.register_c = num / denom;
+                self.instr_ptr += 2;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Run until the program halts, passing each output to `sink`, or until `sink` breaks.
+    ///
+    /// Returns whether the program ran to completion.
+    fn run_with<F>(&mut self, mut sink: F) -> Res<ControlFlow<()>>
+    where
+        F: FnMut(usize) -> ControlFlow<()>
+    {
+        while !self.halted() {
+            if let Some(output) = self.execute()? {
+                if sink(output).is_break() {
+                    return Ok(ControlFlow::Break(()));
+                }
+            }
+        }
+
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// Run until the program halts, returning its output, comma separated.
+    fn run(&mut self) -> Res<String> {
+        let mut output = Vec::new();
+        // Never breaks, so always runs to completion.
+        let _ = self.run_with(|x| {
+            output.push(x);
+            ControlFlow::Continue(())
+        })?;
+
+        Ok(output.iter().map(|&x| x.to_string()).join(","))
+    }
+}
+
+/// One instruction per step, collecting any output in [`Machine::output`].
+impl Simulation for Machine {
+    fn step(&mut self) -> StepResult {
+        if self.halted() {
+            return StepResult::Finished;
+        }
+
+        match self.execute() {
+            Err(e) => return StepResult::Failed(e),
+            Ok(output) => self.output.extend(output)
+        }
+
+        if self.halted() { StepResult::Finished } else { StepResult::Running }
+    }
+}
+
+impl Display for Machine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f, "A={} B={} C={} ip={} output={}",
+            self.register_a, self.register_b, self.register_c, self.instr_ptr,
+            self.output.iter().join(",")
+        )
+    }
+}
+
+fn number(input: &str) -> IResult<&str, usize> {
+    map_res(digit1, |s: &str| s.parse::<usize>())(input)
+}
+
+/// The input's basic format: the registers, then a program of 3-bit numbers.
+const FORMAT: [Format; 2] = [
+    Format::new().chars("RegistrABC: 0123456789"),
+    Format::new().chars("Program: 0123456789,").numbers(0..=7)
+];
+
+fn parse_machine(input: &str) -> Res<Machine> {
+    check_sections(input, &FORMAT).map_err(|e| e.to_string())?;
+    let parse_result = map(
+        tuple((
+            preceded(tag("Register A: "), number),
+            preceded(tuple((line_ending, tag("Register B: "))), number),
+            preceded(tuple((line_ending, tag("Register C: "))), number),
+            preceded(
+                tuple((line_ending, line_ending, tag("Program: "))),
+                separated_list1(char(','), number)
+            )
+        )),
+        |(register_a, register_b, register_c, data)| {
+            Machine::new(data, register_a, register_b, register_c)
+        }
+    )(input);
+
+    match parse_result {
+        Ok((_, machine)) => Ok(machine),
+        Err(_) => Err("Failed to parse machine input".to_owned())
+    }
+}
+
+/// The machine in the input, before its first instruction.
+pub fn simulation(input: &str) -> Res<Machine> {
+    parse_machine(input)
+}
+
+/// The program's output, comma separated.
+pub fn part1(input: &str) -> Res<String> {
+    parse_machine(input)?.run()
+}
+
+/// Checks candidates for register A against a machine's program, rerunning one copy of the machine
+/// from its starting state for each.
+struct Checker {
+    machine: Machine,
+    start: Snapshot,
+    /// The machine's program, which running it doesn't change.
+    target: Vec<usize>
+}
+
+impl Checker {
+    fn new(machine: &Machine) -> Checker {
+        let target = machine.data.clone();
+        Checker { machine: machine.clone(), start: machine.snapshot(), target }
+    }
+
+    /// Run the machine with register A set to `a`, and check its output against the tail of its
+    /// program: as many values as `a` has octal digits.
+    ///
+    /// Returns `None` if the output isn't that suffix of the program, and otherwise whether it's
+    /// the whole program. The run stops at the first output that doesn't match.
+    fn check(&mut self, a: usize) -> Res<Option<bool>> {
+        let digits = (usize::BITS - a.leading_zeros()).div_ceil(3) as usize;
+        let target = &self.target;
+        if digits > target.len() {
+            return Ok(None);
+        }
+
+        let mut expect = Expect::new(&target[target.len() - digits..]);
+        self.machine.restore(&self.start);
+        self.machine.register_a = a;
+        let flow = self.machine.run_with(|x| expect.check(&x))?;
+
+        if flow.is_continue() && expect.is_complete() {
+            Ok(Some(digits == target.len()))
+        }
+        else {
+            Ok(None)
+        }
+    }
+}
+
+/// A strategy for finding the smallest value of register A that makes a machine output its own
+/// program.
+///
+/// Every backend relies on the program consuming A three bits (one octal digit) per output, with
+/// the most significant digit determining the last output: so A is built up one octal digit at a
+/// time, keeping only prefixes whose output matches the end of the program. Checking a prefix stops
+/// the machine as soon as its output goes wrong.
+trait QuineSearch {
+    fn find(&self, machine: &Machine) -> Res<usize>;
+}
+
+/// Depth-first search over octal digits, trying smaller digits first.
+struct DepthFirst;
+
+#[derive(Debug)]
+struct Step {
+    cur_choice: usize,
+    rem_choices: Vec<usize>
+}
+
+impl Step {
+    fn next(&mut self) -> bool {
+        if self.rem_choices.is_empty() {
+            false
+        }
+        else {
+            self.cur_choice = self.rem_choices.pop().unwrap();
+            true
+        }
+    }
+}
+
+impl Step {
+    fn new() -> Step {
+        Step { cur_choice: 0, rem_choices: (1..8).rev().collect() }
+    }
+
+    /// The leading digit: a leading zero would just be a shorter A, so start from one.
+    fn leading() -> Step {
+        Step { cur_choice: 1, rem_choices: (2..8).rev().collect() }
+    }
+}
+
+impl QuineSearch for DepthFirst {
+    fn find(&self, machine: &Machine) -> Res<usize> {
+        let mut checker = Checker::new(machine);
+        let mut stack = vec![Step::leading()];
+
+        loop {
+            let cur = stack.iter().fold(0, |acc, x| 8*acc + x.cur_choice);
+
+            match checker.check(cur)? {
+                Some(true) => return Ok(cur),
+                Some(false) => stack.push(Step::new()),
+                None => {
+                    while !stack.last_mut().ok_or("No value of A gives a quine".to_owned())?.next() {
+                        stack.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Best-first search, always extending the smallest candidate A seen so far.
+///
+/// Extending a prefix never makes it smaller, so the first full match popped is the smallest.
+struct SmallestFirst;
+
+impl QuineSearch for SmallestFirst {
+    fn find(&self, machine: &Machine) -> Res<usize> {
+        let mut checker = Checker::new(machine);
+        let mut heap: BinaryHeap<Reverse<usize>> = (1..8).map(Reverse).collect();
+
+        while let Some(Reverse(cur)) = heap.pop() {
+            match checker.check(cur)? {
+                Some(true) => return Ok(cur),
+                Some(false) => heap.extend((0..8).map(|d| Reverse(8*cur + d))),
+                None => ()
+            }
+        }
+
+        Err("No value of A gives a quine".to_owned())
+    }
+}
+
+/// Breadth-first search, one octal digit at a time: keeps every prefix that matches so far, then
+/// takes the smallest full match with [`first_match`] (on all cores, with the `parallel` feature).
+struct ByLevel;
+
+impl QuineSearch for ByLevel {
+    fn find(&self, machine: &Machine) -> Res<usize> {
+        let mut checker = Checker::new(machine);
+        let mut prefixes = vec![0];
+
+        for level in 1..=machine.data.len() {
+            // Extending sorted prefixes with each digit in turn keeps the candidates sorted.
+            let candidates: Vec<usize> = prefixes.iter()
+                .flat_map(|&p| (0..8).map(move |d| 8*p + d))
+                .filter(|&a| a > 0)
+                .collect();
+
+            if level == machine.data.len() {
+                // Checked in parallel, so each needs a checker of its own.
+                return first_match(candidates, |a| match Checker::new(machine).check(a) {
+                    Ok(Some(true)) => Some(Ok(a)),
+                    Ok(_) => None,
+                    Err(e) => Some(Err(e))
+                }).unwrap_or(Err("No value of A gives a quine".to_owned()));
+            }
+
+            prefixes = candidates.into_iter()
+                .filter_map(|a| checker.check(a).map(|m| m.map(|_| a)).transpose())
+                .collect::<Res<_>>()?;
+        }
+
+        Err("No value of A gives a quine".to_owned())
+    }
+}
+
+fn part2(input: &str) -> Res<usize> {
+    DepthFirst.find(&parse_machine(input)?)
+}
+
+fn part2_smallest_first(input: &str) -> Res<usize> {
+    SmallestFirst.find(&parse_machine(input)?)
+}
+
+fn part2_by_level(input: &str) -> Res<usize> {
+    ByLevel.find(&parse_machine(input)?)
+}
+
+/// Lowest value of register A that makes the program output a copy of itself.
+pub const PART2: Strategies<Res<usize>> = Strategies(&[
+    ("depth-first", part2),
+    ("smallest-first", part2_smallest_first),
+    ("by-level", part2_by_level)
+]);
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+    use crate::simulation::{Simulation, StepResult};
+    use super::{
+        parse_machine, part1, part2, part2_by_level, part2_smallest_first, simulation,
+        Checker, Machine
+    };
+
+    const TEST_INPUT: &str = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0";
+
+    const QUINE_INPUT: &str = "Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0";
+
+    crate::test_line_endings! { part1, part2; TEST_INPUT, QUINE_INPUT }
+
+    #[test]
+    fn test_instructions() {
+        let mut machine = Machine::new(vec![2, 6], 0, 0, 9);
+        machine.run().unwrap();
+        assert_eq!(machine.register_b, 1);
+
+        let mut machine = Machine::new(vec![5, 0, 5, 1, 5, 4], 10, 0, 0);
+        assert_eq!(machine.run().unwrap(), "0,1,2");
+
+        let mut machine = Machine::new(vec![0, 1, 5, 4, 3, 0], 2024, 0, 0);
+        assert_eq!(machine.run().unwrap(), "4,2,5,6,7,7,7,7,3,1,0");
+        assert_eq!(machine.register_a, 0);
+
+        let mut machine = Machine::new(vec![4, 0], 0, 2024, 43690);
+        machine.run().unwrap();
+        assert_eq!(machine.register_b, 44354);
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT).unwrap(), "4,6,3,5,6,3,5,2,1,0");
+    }
+
+    #[test]
+    fn test_malformed() {
+        let input = "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,9,3,0";
+        assert_eq!(
+            parse_machine(input).err().unwrap(),
+            "Failed to parse line 5, column 16: 9 is outside 0..=7 in \"Program: 0,1,5,9,3,0\""
+        );
+
+        let input = "Register A: 729\nRegister B: -1\nRegister C: 0\n\nProgram: 0,1";
+        assert_eq!(
+            parse_machine(input).err().unwrap(),
+            "Failed to parse line 2, column 13: unexpected '-' in \"Register B: -1\""
+        );
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = QUINE_INPUT;
+        assert_eq!(part2(input).unwrap(), 117440);
+        assert_eq!(part2_smallest_first(input).unwrap(), 117440);
+        assert_eq!(part2_by_level(input).unwrap(), 117440);
+
+        let mut machine = parse_machine(input).unwrap();
+        machine.register_a = 117440;
+        assert_eq!(machine.run().unwrap(), "0,3,5,4,3,0");
+    }
+
+    #[test]
+    fn test_run_with_stops_early() {
+        let input = "Register A: 2024\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,5,4,3,0";
+        let base = parse_machine(input).unwrap();
+
+        let mut machine = base.clone();
+        let mut outputs = Vec::new();
+        let flow = machine.run_with(|x| {
+            outputs.push(x);
+            if outputs.len() == 2 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        });
+        assert_eq!(flow, Ok(ControlFlow::Break(())));
+        assert_eq!(outputs, vec![5, 7]);
+        assert_eq!(machine.register_a, 2024 >> 6);
+
+        // Each output is the next octal digit of A, and the last output is always 0.
+        let mut checker = Checker::new(&base);
+        assert_eq!(checker.check(0o345), Ok(Some(false)));
+        assert_eq!(checker.check(0o3453), Ok(Some(false)));
+        assert_eq!(checker.check(0o355), Ok(None));
+        assert_eq!(checker.check(0o1234567), Ok(None));
+        assert_eq!(checker.check(117440), Ok(Some(true)));
+    }
+
+    /// The example from part 1, stopped after its first two outputs.
+    fn mid_execution() -> Machine {
+        let input = "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0";
+        let mut machine = parse_machine(input).unwrap();
+        let mut outputs = 0;
+        let _ = machine.run_with(|_| {
+            outputs += 1;
+            if outputs == 2 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        });
+        machine
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut machine = mid_execution();
+        let snapshot = machine.snapshot();
+        assert_eq!(snapshot.instr_ptr, 4);
+
+        let rest = machine.run().unwrap();
+        assert_eq!(rest, "3,5,6,3,5,2,1,0");
+        assert_ne!(machine.snapshot(), snapshot);
+
+        // Rewinding replays the rest of the run exactly.
+        machine.restore(&snapshot);
+        assert_eq!(machine, mid_execution());
+        assert_eq!(machine.run().unwrap(), rest);
+    }
+
+    #[test]
+    fn test_simulation() {
+        // Three instructions per loop, halving A each time; one loop per output, so ten in all.
+        let input = "Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0";
+        let mut machine = simulation(input).unwrap();
+        assert_eq!(machine.run_for(6), (6, StepResult::Running));
+        assert_eq!(machine.to_string(), "A=182 B=0 C=0 ip=0 output=4,6");
+        assert_eq!(machine.run_until(|_| false), (24, StepResult::Finished));
+        assert_eq!(machine.register_a, 0);
+        assert_eq!(machine.output(), [4, 6, 3, 5, 6, 3, 5, 2, 1, 0]);
+        assert_eq!(machine.step(), StepResult::Finished);
+
+        let mut machine = Machine::new(vec![7], 0, 0, 0);
+        assert!(matches!(machine.step(), StepResult::Failed(_)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let machine = mid_execution();
+
+        let bytes = bincode::serialize(&machine.snapshot()).unwrap();
+        let snapshot: super::Snapshot = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(snapshot, machine.snapshot());
+
+        let bytes = bincode::serialize(&machine).unwrap();
+        let mut restored: Machine = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(restored, machine);
+        assert_eq!(restored.run().unwrap(), "3,5,6,3,5,2,1,0");
+    }
+
+    #[test]
+    fn test_no_quine() {
+        // Always outputs 1, so can never reproduce a program ending in 0.
+        let input = "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: 0,3,5,1,3,0";
+        assert!(part2(input).is_err());
+        assert!(part2_smallest_first(input).is_err());
+        assert!(part2_by_level(input).is_err());
+    }
+}
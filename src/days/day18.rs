@@ -0,0 +1,410 @@
+//! Day 18: RAM Run
+//!
+//! Bytes fall one by one into a grid, corrupting cells; find paths from the top left to the bottom
+//! right.
+//!
+//! # Approach
+//!
+//! A shortest path is kept up to date as bytes fall: it only needs recomputing (with Dijkstra's
+//! algorithm) when a byte lands on it. Most bytes miss the current path, so part 2 needs far fewer
+//! searches than there are bytes.
+//!
+//! [`Map`] is a grid of obstacles that come and go: cells can also be cleared again, and a batch of
+//! bytes can fall at once, with at most one search for the lot.
+//!
+//! With `--render`, prints the memory space after the first kilobyte, with a shortest path marked
+//! as in the puzzle's illustration.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt::{Display, Formatter};
+use itertools::Itertools;
+use nom::character::complete::{char, digit1, line_ending};
+use nom::combinator::map_res;
+use nom::IResult;
+use nom::multi::separated_list1;
+use nom::sequence::separated_pair;
+use crate::hash::{HashMap, HashSet};
+
+crate::day_args! { size: usize = 71, bytes: usize = 1024 }
+
+pub type Pos = (usize, usize);
+
+fn parse_input(input: &str) -> Vec<Pos> {
+    let result: IResult<&str, Vec<Pos>> = separated_list1(
+        line_ending,
+        separated_pair(
+            map_res(digit1, str::parse::<usize>),
+            char(','),
+            map_res(digit1, str::parse::<usize>)
+        )
+    )(input);
+
+    result.unwrap().1
+}
+
+#[derive(Eq, PartialEq)]
+struct HeapElem { node: Pos, distance: usize }
+
+impl Ord for HeapElem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.distance.cmp(&self.distance)
+            .then_with(|| self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for HeapElem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+/// A shortest path from the top left to the bottom right of the memory space.
+#[derive(Debug, Clone)]
+pub struct Path {
+    /// Every position along the path, from the start to the exit.
+    pub positions: Vec<Pos>,
+    nodes: HashSet<Pos>
+}
+
+impl Path {
+    fn new(positions: Vec<Pos>) -> Path {
+        let nodes = positions.iter().copied().collect();
+        Path { positions, nodes }
+    }
+
+    /// Number of steps along the path: one fewer than the positions on it, which include both
+    /// endpoints.
+    pub fn steps(&self) -> usize {
+        self.positions.len() - 1
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.nodes.contains(&pos)
+    }
+}
+
+/// A memory space whose cells can be corrupted and cleared, keeping a shortest path from the top
+/// left to the bottom right up to date.
+pub struct Map {
+    rows: usize,
+    cols: usize,
+    corrupted: HashSet<Pos>,
+    best_path: Option<Path>,
+}
+
+impl Map {
+    pub fn new(rows: usize, cols: usize) -> Map {
+        // With nothing corrupted yet, going along the top edge and then down the right edge is a
+        // shortest path.
+        let positions = (0..cols).map(|j| (0, j))
+            .chain((1..rows).map(|i| (i, cols - 1)))
+            .collect();
+        Map { rows, cols, corrupted: HashSet::default(), best_path: Some(Path::new(positions)) }
+    }
+
+    /// A shortest path across, if there's still a way through.
+    pub fn best_path(&self) -> Option<&Path> {
+        self.best_path.as_ref()
+    }
+
+    /// Length of a shortest path across, if there's still a way through.
+    pub fn distance(&self) -> Option<usize> {
+        self.best_path.as_ref().map(Path::steps)
+    }
+
+    pub fn is_corrupted(&self, pos: Pos) -> bool {
+        self.corrupted.contains(&pos)
+    }
+
+    pub fn corrupt(&mut self, pos: Pos) {
+        self.corrupt_many(&[pos]);
+    }
+
+    /// Corrupt every one of `positions`, searching for a new path at most once, and only if one of
+    /// them lands on the current one.
+    pub fn corrupt_many(&mut self, positions: &[Pos]) {
+        self.corrupted.extend(positions);
+
+        let on_path = |path: &Path| positions.iter().any(|&pos| path.contains(pos));
+        if self.best_path.as_ref().is_some_and(on_path) {
+            self.update_best_path();
+        }
+    }
+
+    /// Clear `pos` again.
+    ///
+    /// Only paths through `pos` are new, and none of them is shorter than the Manhattan distance
+    /// via `pos`; so unless the current path (if any) is longer than that, it's still a shortest.
+    pub fn decorrupt(&mut self, pos: Pos) {
+        if !self.corrupted.remove(&pos) {
+            return;
+        }
+
+        let (start, end) = self.endpoints();
+        let shortest_via = start.0.abs_diff(pos.0) + start.1.abs_diff(pos.1)
+            + end.0.abs_diff(pos.0) + end.1.abs_diff(pos.1);
+        if self.distance().is_none_or(|distance| distance > shortest_via) {
+            self.update_best_path();
+        }
+    }
+
+    /// The top left and bottom right corners.
+    fn endpoints(&self) -> (Pos, Pos) {
+        ((0, 0), (self.rows - 1, self.cols - 1))
+    }
+
+    fn update_best_path(&mut self) {
+        let (start, end) = self.endpoints();
+        self.best_path = self.shortest_path(start, end);
+    }
+
+    fn neighbors(&self, pos: Pos) -> Vec<Pos> {
+        let mut opts = Vec::new();
+        let (i, j) = pos;
+        if i > 0 { opts.push((i - 1, j)); }
+        if i < self.rows - 1 { opts.push((i + 1, j)); }
+        if j > 0 { opts.push((i, j - 1)); }
+        if j < self.cols - 1 { opts.push((i, j + 1)); }
+
+        opts.iter().filter(|&x| !self.corrupted.contains(x)).cloned().collect()
+    }
+
+    fn shortest_path(&self, from: Pos, to: Pos) -> Option<Path> {
+        if self.corrupted.contains(&from) || self.corrupted.contains(&to) {
+            return None;
+        }
+
+        let mut result: HashMap<Pos, (usize, Option<Pos>)> =
+            (0..self.rows).cartesian_product(0..self.cols)
+                .filter(|pos| !self.corrupted.contains(pos))
+                .map(|pos| (pos, if pos == from { 0 } else { usize::MAX }))
+                .map(|(pos, dist)| (pos, (dist, None)))
+                .collect();
+
+        let mut heap = BinaryHeap::new();
+        heap.push(HeapElem { node: from, distance: 0 });
+
+        while let Some(HeapElem { node, distance }) = heap.pop() {
+            if node == to {
+                break;
+            }
+
+            if result[&node].0 < distance { continue; }
+
+            self.neighbors(node).iter().for_each(|&n| {
+                let (cur_dist, cur_pred) = result.get_mut(&n).unwrap();
+                if *cur_dist > distance + 1 {
+                    *cur_dist = distance + 1;
+                    *cur_pred = Some(node);
+                    heap.push(HeapElem { node: n, distance: distance + 1 });
+                }
+            })
+        }
+
+        let distance = result[&to].0;
+
+        if distance == usize::MAX {
+            None
+        } else {
+            let mut positions = vec![to];
+            let mut cur = to;
+            while let Some(n) = result[&cur].1 {
+                positions.push(n);
+                cur = n;
+            }
+            positions.reverse();
+
+            Some(Path::new(positions))
+        }
+    }
+}
+
+/// The memory space as in the puzzle's illustrations: `#` for corrupted bytes, `O` for the
+/// current shortest path, and `.` for everything else.
+impl Display for Map {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // Positions are `(x, y)`, so each line of the picture has a fixed `y`.
+        for y in 0..self.cols {
+            for x in 0..self.rows {
+                let c = if self.corrupted.contains(&(x, y)) {
+                    '#'
+                }
+                else if self.best_path.as_ref().is_some_and(|path| path.contains((x, y))) {
+                    'O'
+                }
+                else {
+                    '.'
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A `size`×`size` memory space after the first `num_bytes` bytes have fallen.
+fn map_after(input: &str, size: usize, num_bytes: usize) -> Map {
+    let mut map = Map::new(size, size);
+    map.corrupt_many(&parse_input(input)[..num_bytes]);
+    map
+}
+
+/// A shortest path across a `size`×`size` memory space after `num_bytes` have fallen, if there's
+/// still a way through.
+pub fn shortest_path(input: &str, size: usize, num_bytes: usize) -> Option<Path> {
+    map_after(input, size, num_bytes).best_path
+}
+
+/// Length of the shortest path across a `size`×`size` memory space after `num_bytes` have fallen.
+fn min_steps(input: &str, size: usize, num_bytes: usize) -> usize {
+    shortest_path(input, size, num_bytes).expect("There should be a path").steps()
+}
+
+/// Coordinates of the first byte to cut off the exit of a `size`×`size` memory space.
+fn first_blocking_byte(input: &str, size: usize) -> String {
+    let mut map = Map::new(size, size);
+    let corrupted = parse_input(input);
+
+    for pos in corrupted {
+        map.corrupt(pos);
+        if map.best_path.is_none() { return format!("{},{}", pos.0, pos.1) }
+    }
+
+    panic!("We didn't ever block the path!")
+}
+
+/// Fewest steps to the exit after the first kilobyte has fallen.
+pub fn part1(input: &str, args: &Args) -> usize {
+    min_steps(input, args.size, args.bytes)
+}
+
+/// Coordinates of the first byte that cuts off the exit.
+pub fn part2(input: &str, args: &Args) -> String {
+    first_blocking_byte(input, args.size)
+}
+
+/// The memory space after the first kilobyte has fallen, with a shortest path marked.
+pub fn render(input: &str, args: &Args) -> String {
+    map_after(input, args.size, args.bytes).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::assert_snapshot;
+    use super::{first_blocking_byte, map_after, min_steps, parse_input, render, shortest_path};
+    use super::{part1, part2, Args, Map};
+
+    const TEST_INPUT: &str = "5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0";
+
+    crate::test_line_endings! {
+        |input: &str| part1(input, &Args { size: 7, bytes: 12 }),
+        |input: &str| part2(input, &Args { size: 7, bytes: 12 });
+        TEST_INPUT
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(min_steps(TEST_INPUT, 7, 12), 22);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(first_blocking_byte(TEST_INPUT, 7), "6,1");
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let path = shortest_path(TEST_INPUT, 7, 12).unwrap();
+        assert_eq!(path.steps(), 22);
+        assert_eq!((path.positions[0], path.positions[22]), ((0, 0), (6, 6)));
+        assert!(path.positions.windows(2).all(|w| {
+            w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1) == 1
+        }));
+
+        assert!(shortest_path(TEST_INPUT, 7, 21).is_none());
+    }
+
+    #[test]
+    fn test_corrupt_many() {
+        let bytes = parse_input(TEST_INPUT);
+        let mut one_by_one = Map::new(7, 7);
+        bytes[..12].iter().for_each(|&pos| one_by_one.corrupt(pos));
+
+        let mut batched = Map::new(7, 7);
+        batched.corrupt_many(&bytes[..12]);
+        assert_eq!(batched.corrupted, one_by_one.corrupted);
+        assert_eq!(batched.distance(), Some(22));
+        assert_eq!(one_by_one.distance(), Some(22));
+    }
+
+    #[test]
+    fn test_decorrupt() {
+        let bytes = parse_input(TEST_INPUT);
+
+        // Clearing bytes in reverse order gives the same distances as never dropping them.
+        let mut map = map_after(TEST_INPUT, 7, bytes.len());
+        assert_eq!(map.distance(), None);
+        for n in (0..bytes.len()).rev() {
+            map.decorrupt(bytes[n]);
+            assert!(!map.is_corrupted(bytes[n]));
+            assert_eq!(map.distance(), map_after(TEST_INPUT, 7, n).distance(), "after {n} bytes");
+
+            if let Some(path) = map.best_path() {
+                assert!(path.positions.iter().all(|&pos| !map.is_corrupted(pos)));
+            }
+        }
+
+        // Clearing a cell off every shortest path leaves the path alone.
+        let mut map = map_after(TEST_INPUT, 7, 12);
+        map.decorrupt((6, 6));
+        assert_eq!(map.distance(), Some(22));
+
+        // As does clearing the only byte in a corner that no shortest path could go through.
+        let mut map = Map::new(7, 7);
+        map.corrupt((6, 0));
+        map.decorrupt((6, 0));
+        assert_eq!(map.distance(), Some(12));
+    }
+
+    #[test]
+    fn test_blocked_endpoints() {
+        let mut map = Map::new(3, 3);
+        map.corrupt((2, 2));
+        assert_eq!(map.distance(), None);
+        map.decorrupt((2, 2));
+        assert_eq!(map.distance(), Some(4));
+    }
+
+    #[test]
+    fn test_render() {
+        let rendered = render(TEST_INPUT, &Args { size: 7, bytes: 12 });
+        assert_eq!(rendered.matches('#').count(), 12);
+        assert_eq!(rendered.matches('O').count(), 23);
+        assert_snapshot!("day18_example_path", rendered);
+    }
+}
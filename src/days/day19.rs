@@ -0,0 +1,228 @@
+//! Day 19: Linen Layout
+//!
+//! Count the ways to build each design out of the available towel patterns.
+//!
+//! # Approach
+//!
+//! Dynamic programming over suffixes of the design: the decompositions of the suffix starting at
+//! `n` combine those of the suffixes after each pattern that matches at `n`, which are found
+//! together by walking a trie of the patterns ([`MultiPattern`]). Along with the count, the DP
+//! tracks the fewest and most towels used, for `--stats`.
+//!
+//! # Complexity
+//!
+//! `O(len × longest pattern)` per design.
+
+use nom::bytes::complete::tag;
+use nom::character::complete::{alpha1, line_ending};
+use nom::combinator::map;
+use nom::IResult;
+use nom::multi::separated_list1;
+use crate::parse::two_sections;
+use crate::strings::MultiPattern;
+
+struct Input {
+    components: Vec<String>,
+    targets: Vec<String>
+}
+
+fn parse_input(input: &str) -> IResult<&str, Input> {
+    map(
+        two_sections(
+            separated_list1(tag(", "), map(alpha1, |s: &str| s.to_owned())),
+            separated_list1(line_ending, map(alpha1, |s: &str| s.to_owned()))
+        ),
+        |(components, targets)| Input { components, targets }
+    )(input)
+}
+
+/// Summary of every way to build a design: how many there are, and the fewest and most towels
+/// used by any of them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Decompositions { ways: usize, fewest: usize, most: usize }
+
+impl Decompositions {
+    /// No way to build the design at all.
+    const IMPOSSIBLE: Decompositions = Decompositions { ways: 0, fewest: usize::MAX, most: 0 };
+
+    /// The one way to build an empty design: with no towels.
+    const EMPTY: Decompositions = Decompositions { ways: 1, fewest: 0, most: 0 };
+
+    /// The same decompositions, with one more towel on the front of each.
+    fn after_towel(self) -> Decompositions {
+        match self.ways {
+            0 => self,
+            _ => Decompositions { ways: self.ways, fewest: self.fewest + 1, most: self.most + 1 }
+        }
+    }
+
+    /// Decompositions that are either in `self` or in `other`.
+    fn or(self, other: Decompositions) -> Decompositions {
+        Decompositions {
+            ways: self.ways + other.ways,
+            fewest: self.fewest.min(other.fewest),
+            most: self.most.max(other.most)
+        }
+    }
+}
+
+fn decompositions(target: &str, from: &MultiPattern) -> Decompositions {
+    let target = target.as_bytes();
+    let mut results = vec![Decompositions::IMPOSSIBLE; target.len() + 1];
+    results[target.len()] = Decompositions::EMPTY;
+
+    for n in (0..target.len()).rev() {
+        results[n] = from.prefix_lengths(&target[n..])
+            .map(|len| results[n + len].after_towel())
+            .fold(Decompositions::IMPOSSIBLE, Decompositions::or);
+    }
+
+    results[0]
+}
+
+/// Number of designs that can be built.
+pub fn part1(input: &str) -> usize {
+    let Input { components, targets } = parse_input(input).expect("Input is valid").1;
+    let towels = MultiPattern::new(&components);
+
+    targets.iter().filter(|target| towels.can_decompose(target)).count()
+}
+
+/// Total number of ways to build all the designs.
+pub fn part2(input: &str) -> usize {
+    let Input { components, targets } = parse_input(input).expect("Input is valid").1;
+    let towels = MultiPattern::new(&components);
+
+    targets.iter().map(|target| towels.count_decompositions(target)).sum()
+}
+
+/// For each design, the number of ways to build it and the fewest and most towels it can take.
+pub fn stats(input: &str) -> String {
+    let Input { components, targets } = parse_input(input).expect("Input is valid").1;
+    let towels = MultiPattern::new(&components);
+
+    targets.iter()
+        .map(|target| match decompositions(target, &towels) {
+            Decompositions { ways: 0, .. } => format!("{target}: impossible"),
+            Decompositions { ways: 1, fewest, .. } => {
+                format!("{target}: 1 way, using {fewest} towels")
+            },
+            Decompositions { ways, fewest, most } => {
+                format!("{target}: {ways} ways, using {fewest} to {most} towels")
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dp::count_compositions;
+    use crate::gen::Rng;
+    use crate::strings::MultiPattern;
+    use super::{decompositions, parse_input, part1, part2, Decompositions, Input};
+
+    const TEST_INPUT: &str = "r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb";
+
+    crate::test_samples! {
+        part1, part2;
+        (TEST_INPUT, 6, 16),
+        ("r, wr, b, g, bwu, rb, gb, br\n\nbrwrr", 1, 2),
+        ("r, wr, b, g, bwu, rb, gb, br\n\nbggr", 1, 1),
+        ("r, wr, b, g, bwu, rb, gb, br\n\ngbbr", 1, 4),
+        ("r, wr, b, g, bwu, rb, gb, br\n\nrrbgbr", 1, 6),
+        ("r, wr, b, g, bwu, rb, gb, br\n\nubwu", 0, 0),
+        ("r, wr, b, g, bwu, rb, gb, br\n\nbwurrg", 1, 1),
+        ("r, wr, b, g, bwu, rb, gb, br\n\nbrgr", 1, 2),
+        ("r, wr, b, g, bwu, rb, gb, br\n\nbbrgwb", 0, 0),
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 6);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), 16);
+    }
+
+    /// Every way to build `target`, as the number of towels each one uses.
+    fn all_lengths(target: &str, from: &[String]) -> Vec<usize> {
+        if target.is_empty() {
+            return vec![0];
+        }
+
+        from.iter()
+            .filter(|&s| target.starts_with(s.as_str()))
+            .flat_map(|s| all_lengths(&target[s.len()..], from))
+            .map(|n| n + 1)
+            .collect()
+    }
+
+    #[test]
+    fn test_decompositions() {
+        let Input { components, targets } = parse_input(TEST_INPUT).unwrap().1;
+        let towels = MultiPattern::new(&components);
+
+        let expected = |ways, fewest, most| Decompositions { ways, fewest, most };
+        assert_eq!(decompositions("brwrr", &towels), expected(2, 3, 4));
+        assert_eq!(decompositions("bggr", &towels), expected(1, 4, 4));
+        assert_eq!(decompositions("gbbr", &towels), expected(4, 2, 4));
+        assert_eq!(decompositions("ubwu", &towels).ways, 0);
+
+        for target in targets.iter() {
+            let lengths = all_lengths(target, &components);
+            let result = decompositions(target, &towels);
+            assert_eq!(result.ways, lengths.len(), "{target}");
+            if let (Some(&fewest), Some(&most)) = (lengths.iter().min(), lengths.iter().max()) {
+                assert_eq!((result.fewest, result.most), (fewest, most), "{target}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_designs() {
+        let mut rng = Rng::new(19);
+        // A small alphabet, so that designs have plenty of decompositions.
+        let word = |rng: &mut Rng, len: usize| -> String {
+            (0..len).map(|_| b"wub"[rng.below(3)] as char).collect()
+        };
+
+        for _ in 0..100 {
+            let mut components: Vec<String> = (0..rng.between(1, 8))
+                .map(|_| {
+                    let len = rng.between(1, 4);
+                    word(&mut rng, len)
+                })
+                .collect();
+            components.sort();
+            components.dedup();
+            let towels = MultiPattern::new(&components);
+
+            for _ in 0..10 {
+                let len = rng.between(0, 30);
+                let target = word(&mut rng, len);
+                // Matching each towel in turn, without the trie.
+                let ways = count_compositions(target.len(), |n| {
+                    components.iter()
+                        .filter(|towel| target[n..].starts_with(towel.as_str()))
+                        .map(String::len)
+                        .collect::<Vec<_>>()
+                });
+
+                assert_eq!(towels.count_decompositions(&target), ways, "{target} {components:?}");
+                assert_eq!(decompositions(&target, &towels).ways, ways, "{target} {components:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,437 @@
+//! Day 20: Race Condition
+//!
+//! There's a single track from start to end; a cheat lets you pass through walls once.
+//!
+//! # Approach
+//!
+//! The track is a single path, so one walk along it gives every position's distance from the start:
+//! its index in the path. A cheat from the `i`th position to the `j`th then saves `j - i` less the
+//! Manhattan distance between them, so counting good cheats is a matter of comparing pairs of path
+//! positions within the cheat's radius, with no searching at all. Both parts count cheats on the
+//! same walk, a [`Race`]; part 1's cheats are just the ones of at most 2 picoseconds. Each part
+//! builds the race for itself, unless the `parse-cache` feature is on: then it goes through the
+//! on-disk parse cache, so that it's only built once.
+//!
+//! # Complexity
+//!
+//! Quadratic in the length of the track at worst, but positions too far away to reach are skipped
+//! in bulk, since the distance changes by at most one per step along the path.
+//!
+//! [`layouts`] instead scans a diamond around each position of a grid of distances, in each grid
+//! layout, for the bench to time. With `--dump DIR`, each part writes how many cheats save each
+//! amount of time to `DIR/day20/savings-2.csv` or `savings-20.csv`.
+
+use std::collections::BTreeMap;
+use std::ops::Index;
+use nom::branch::alt;
+use nom::character::complete::{char, line_ending, multispace0};
+use nom::combinator::{all_consuming, map, value};
+use nom::sequence::terminated;
+use nom::IResult;
+use nom::multi::{many1, separated_list1};
+use crate::cache::try_parse_cached;
+use crate::dump::{csv, DebugSink};
+use crate::grid::{diamond, ColumnMajor, Grid, Layout, LayoutKernels, Nested, RowMajor};
+use crate::parse::unexpected_in;
+use crate::sanitize::Format;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum SpaceType { Track, Wall }
+use SpaceType::*;
+
+crate::day_args! { min_saving: usize = 100 }
+
+type Pos = (usize, usize);
+
+struct Maze {
+    start: Pos,
+    end: Pos,
+    rows: usize,
+    cols: usize,
+    spaces: Vec<Vec<SpaceType>>
+}
+
+impl Index<Pos> for Maze {
+    type Output = SpaceType;
+    fn index(&self, (i, j): Pos) -> &Self::Output { &self.spaces[i][j] }
+}
+
+impl Maze {
+    fn adj_tracks(&self, (i, j): Pos) -> Vec<Pos> {
+        let mut opts = Vec::new();
+        if i > 0 { opts.push((i - 1, j)); }
+        if i < self.rows - 1 { opts.push((i + 1, j)); }
+        if j > 0 { opts.push((i, j - 1)); }
+        if j < self.cols - 1 { opts.push((i, j + 1)); }
+
+        opts.into_iter().filter(|&pos| self[pos] == Track).collect()
+    }
+}
+
+/// The map's basic format, checked before parsing it.
+const FORMAT: Format = Format::grid("#.SE").once("SE");
+
+/// The maze in `input`, failing with where it's malformed if it doesn't look like a map.
+fn parse_maze(input: &str) -> Result<Maze, String> {
+    FORMAT.check(input).map_err(|e| e.to_string())?;
+    all_consuming(terminated(parse_input, multispace0))(input)
+        .map(|(_, maze)| maze)
+        .map_err(|e| match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => unexpected_in(input, e.input).to_string(),
+            nom::Err::Incomplete(_) => unexpected_in(input, "").to_string()
+        })
+}
+
+fn parse_input(input: &str) -> IResult<&str, Maze> {
+    let space = alt((
+        value((false, false, Wall), char('#')),
+        value((false, false, Track), char('.')),
+        value((true, false, Track), char('S')),
+        value((false, true, Track), char('E'))
+    ));
+
+    let line = map(
+        many1(space),
+        |spaces| {
+            spaces.into_iter()
+                .enumerate()
+                .fold(
+                    (None, None, Vec::new()),
+                    |(start, end, mut spaces), (j, (is_start, is_end, space))| {
+                        let this_start = if is_start { Some(j) } else { None };
+                        let this_end = if is_end { Some(j) } else { None };
+                        let new_start = start.or(this_start);
+                        let new_end = end.or(this_end);
+                        spaces.push(space);
+                        (new_start, new_end, spaces)
+                    }
+                )
+        }
+    );
+
+    map(
+        separated_list1(line_ending, line),
+        |lines| {
+            let (start_opt, end_opt, spaces) = lines.into_iter()
+                .enumerate()
+                .fold(
+                    (None, None, Vec::new()),
+                    |(start, end, mut spaces), (i, (start0, end0, line))| {
+                        let this_start = start0.map(|j| (i, j));
+                        let this_end = end0.map(|j| (i, j));
+                        let new_start = start.or(this_start);
+                        let new_end = end.or(this_end);
+                        spaces.push(line);
+                        (new_start, new_end, spaces)
+                    }
+                );
+
+            let start = start_opt.expect("Must have start");
+            let end = end_opt.expect("Must have end");
+            let rows = spaces.len();
+            let cols = spaces[0].len();
+            Maze { start, end, rows, cols, spaces }
+        }
+    )(input)
+}
+
+/// The track from start to end, in order. The track never branches, so each position's index is
+/// its distance from the start.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "parse-cache", derive(serde::Serialize, serde::Deserialize))]
+struct Race {
+    path: Vec<Pos>
+}
+
+impl Race {
+    /// The race along the track from the maze's start, failing if the track ends before reaching
+    /// the end, or goes on for longer than the maze has cells (round a loop, where it branches).
+    fn new(maze: &Maze) -> Result<Race, String> {
+        let mut path = vec![maze.start];
+        let mut prev = None;
+
+        while let Some(&(i, j)) = path.last().filter(|&&pos| pos != maze.end) {
+            if path.len() > maze.rows * maze.cols {
+                return Err("The track goes round in a loop without reaching the end".to_owned());
+            }
+
+            let next = maze.adj_tracks((i, j)).into_iter()
+                .find(|&p| Some(p) != prev)
+                .ok_or_else(|| {
+                    format!("The track stops at line {}, column {}, short of the end", i + 1, j + 1)
+                })?;
+            prev = Some((i, j));
+            path.push(next);
+        }
+
+        Ok(Race { path })
+    }
+
+    /// The race around the track in `input`, via the parse cache (if it's enabled). Malformed
+    /// input isn't cached.
+    fn parse(input: &str) -> Result<Race, String> {
+        try_parse_cached(input, |input| Race::new(&parse_maze(input)?))
+    }
+
+    /// Number of cheats of up to `max_cheat` picoseconds that save at least `min_saving`.
+    fn count_cheats(&self, max_cheat: usize, min_saving: usize) -> usize {
+        let mut count = 0;
+        self.for_each_saving(max_cheat, min_saving, |_| count += 1);
+        count
+    }
+
+    /// How many cheats of up to `max_cheat` picoseconds save each amount of time (at least one).
+    fn savings(&self, max_cheat: usize) -> BTreeMap<usize, usize> {
+        let mut savings = BTreeMap::new();
+        self.for_each_saving(max_cheat, 1, |saving| *savings.entry(saving).or_default() += 1);
+        savings
+    }
+
+    /// Call `f` with the time saved by each cheat of up to `max_cheat` picoseconds that saves at
+    /// least `min_saving`.
+    ///
+    /// A cheat from `path[i]` to `path[j]` takes their Manhattan distance `d` instead of `j - i`.
+    fn for_each_saving<F: FnMut(usize)>(&self, max_cheat: usize, min_saving: usize, mut f: F) {
+        let path = &self.path;
+
+        for (i, &(i0, j0)) in path.iter().enumerate() {
+            // Each step along the path changes the distance from `path[i]` by at most one, so when
+            // a position is too far away, the next few can be skipped.
+            let mut j = i.saturating_add(min_saving).saturating_add(1);
+            while let Some(&(i1, j1)) = path.get(j) {
+                let dist = i0.abs_diff(i1) + j0.abs_diff(j1);
+                if dist > max_cheat {
+                    j += dist - max_cheat;
+                    continue;
+                }
+
+                if j - i - dist >= min_saving {
+                    f(j - i - dist);
+                }
+                j += 1;
+            }
+        }
+    }
+
+    /// Count the cheats of up to `max_cheat` picoseconds saving at least `min_saving`, first
+    /// dumping a histogram of all their savings if `--dump` was given.
+    fn count_and_dump(&self, max_cheat: usize, min_saving: usize) -> usize {
+        DebugSink::new("day20").write(&format!("savings-{max_cheat}.csv"), || {
+            let header = ["saving".to_owned(), "cheats".to_owned()];
+            let rows = self.savings(max_cheat).into_iter()
+                .map(|(saving, cheats)| [saving.to_string(), cheats.to_string()]);
+            csv(std::iter::once(header).chain(rows))
+        });
+
+        self.count_cheats(max_cheat, min_saving)
+    }
+}
+
+/// Number of 2-picosecond cheats that save at least `--min-saving` picoseconds (100 by default).
+pub fn part1(input: &str, args: &Args) -> Result<usize, String> {
+    Ok(Race::parse(input)?.count_and_dump(2, args.min_saving))
+}
+
+/// Number of cheats of up to 20 picoseconds that save at least `--min-saving` picoseconds.
+pub fn part2(input: &str, args: &Args) -> Result<usize, String> {
+    Ok(Race::parse(input)?.count_and_dump(20, args.min_saving))
+}
+
+/// [`Race::count_cheats`], by looking up the distances from the start of every position within
+/// `max_cheat` of each track position in `distances`.
+fn count_cheats_by_grid<L: Layout>(
+    race: &Race,
+    distances: &Grid<Option<usize>, L>,
+    max_cheat: usize,
+    min_saving: usize
+) -> usize {
+    let mut count = 0;
+    for (i, &(i0, j0)) in race.path.iter().enumerate() {
+        for (i1, j1) in diamond((i0, j0), max_cheat, distances.bounds()) {
+            if let Some(j) = distances[(i1, j1)] {
+                let dist = i0.abs_diff(i1) + j0.abs_diff(j1);
+                if j >= (i + dist).saturating_add(min_saving) {
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    count
+}
+
+/// The part 2 answer from [`count_cheats_by_grid`], with distances laid out in `L` up front.
+fn cheats_in<L: Layout + 'static>(
+    maze: &Maze,
+    race: &Race,
+    min_saving: usize
+) -> Box<dyn Fn() -> usize> {
+    let mut distances: Grid<Option<usize>, L> = Grid::from_fn(maze.rows, maze.cols, |_| None);
+    for (i, &pos) in race.path.iter().enumerate() {
+        distances[pos] = Some(i);
+    }
+
+    let race = race.clone();
+    Box::new(move || count_cheats_by_grid(&race, &distances, 20, min_saving))
+}
+
+/// Counting part 2's cheats that save at least `min_saving` over a grid of distances, in each
+/// grid layout.
+pub fn layouts(input: &str, min_saving: usize) -> Result<LayoutKernels, String> {
+    let maze = parse_maze(input)?;
+    let race = Race::new(&maze)?;
+    Ok(vec![
+        (RowMajor::NAME, cheats_in::<RowMajor>(&maze, &race, min_saving)),
+        (ColumnMajor::NAME, cheats_in::<ColumnMajor>(&maze, &race, min_saving)),
+        (Nested::NAME, cheats_in::<Nested>(&maze, &race, min_saving))
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{layouts, parse_input, part1, part2, Args, Race};
+
+    const TEST_INPUT: &str = "###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############";
+
+    #[test]
+    fn test_malformed() {
+        assert_eq!(
+            part1("#S.#\n#.x#\n#E##", &Args { min_saving: 1 }),
+            Err("Failed to parse line 2, column 3: unexpected 'x' in \"#.x#\"".to_owned())
+        );
+        let expected =
+            "Failed to parse line 2, column 4: expected 4 characters, found 3 in \"#.#\"";
+        assert_eq!(part2("#S.#\n#.#\n#E##", &Args { min_saving: 1 }), Err(expected.to_owned()));
+        let expected = "Failed to parse line 1: found no 'S' by the end in \"\"";
+        assert_eq!(part1("", &Args { min_saving: 1 }), Err(expected.to_owned()));
+    }
+
+    #[test]
+    fn test_missing_start_or_end() {
+        let args = Args { min_saving: 1 };
+        let expected = "Failed to parse line 3: found no 'S' by the end in \"#E##\"";
+        assert_eq!(part1("#..#\n#.##\n#E##", &args), Err(expected.to_owned()));
+        let expected = "Failed to parse line 3: found no 'E' by the end in \"#.##\"";
+        assert_eq!(part2("#S.#\n#.##\n#.##", &args), Err(expected.to_owned()));
+        let expected = "Failed to parse line 3, column 2: a second 'S' in \"#S##\"";
+        assert_eq!(part1("#S.#\n#.E#\n#S##", &args), Err(expected.to_owned()));
+    }
+
+    #[test]
+    fn test_broken_track() {
+        let args = Args { min_saving: 1 };
+        let expected = "The track stops at line 2, column 3, short of the end";
+        assert_eq!(part1("#####\n#S.##\n###E#\n#####", &args), Err(expected.to_owned()));
+
+        // Turning back into the open square, the walk never finds the end.
+        let expected = "The track goes round in a loop without reaching the end";
+        assert_eq!(part2("######\n#S..E#\n#..###\n######", &args), Err(expected.to_owned()));
+    }
+
+    #[test]
+    fn test_huge_min_saving() {
+        assert_eq!(part1(TEST_INPUT, &Args { min_saving: usize::MAX }), Ok(0));
+        assert!(layouts(TEST_INPUT, usize::MAX).unwrap().iter().all(|(_, count)| count() == 0));
+    }
+
+    /// The puzzle's tally of cheats on the example, as `(saving, cheats)`, for part 1's two-step
+    /// cheats and part 2's twenty-step ones saving at least 50.
+    const PART1_CHEATS: &[(usize, usize)] = &[
+        (2, 14), (4, 14), (6, 2), (8, 4), (10, 2), (12, 3), (20, 1), (36, 1), (38, 1), (40, 1),
+        (64, 1)
+    ];
+    const PART2_CHEATS: &[(usize, usize)] = &[
+        (50, 32), (52, 31), (54, 29), (56, 39), (58, 25), (60, 23), (62, 20), (64, 19), (66, 12),
+        (68, 14), (70, 12), (72, 22), (74, 4), (76, 3)
+    ];
+
+    /// The number of cheats in `tally` saving at least `min_saving`.
+    fn at_least(tally: &[(usize, usize)], min_saving: usize) -> usize {
+        tally.iter().filter(|&&(saving, _)| saving >= min_saving).map(|&(_, cheats)| cheats).sum()
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT, &Args { min_saving: 64 }), Ok(1));
+        assert_eq!(part1(TEST_INPUT, &Args { min_saving: 20 }), Ok(5));
+        for min_saving in 1..=65 {
+            let expected = at_least(PART1_CHEATS, min_saving);
+            assert_eq!(part1(TEST_INPUT, &Args { min_saving }), Ok(expected), "at {min_saving}");
+        }
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT, &Args { min_saving: 76 }), Ok(3));
+        assert_eq!(part2(TEST_INPUT, &Args { min_saving: 50 }), Ok(285));
+        for min_saving in 50..=77 {
+            let expected = at_least(PART2_CHEATS, min_saving);
+            assert_eq!(part2(TEST_INPUT, &Args { min_saving }), Ok(expected), "at {min_saving}");
+        }
+    }
+
+    #[test]
+    fn test_layouts() {
+        assert!(layouts(TEST_INPUT, 50).unwrap().iter().all(|(_, count)| count() == 285));
+    }
+
+    crate::test_line_endings! {
+        |input: &str| part1(input, &Args { min_saving: 1 }),
+        |input: &str| part2(input, &Args { min_saving: 50 });
+        TEST_INPUT
+    }
+
+    #[test]
+    fn test_race_path() {
+        let maze = parse_input(TEST_INPUT).unwrap().1;
+        let path = Race::new(&maze).unwrap().path;
+        assert_eq!(path.len(), 85);
+        assert_eq!((path[0], path[84]), (maze.start, maze.end));
+        assert!(path.windows(2).all(|w| w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1) == 1));
+    }
+
+    #[test]
+    fn test_count_cheats() {
+        let race = Race::parse(TEST_INPUT).unwrap();
+
+        // Cheats saving exactly each amount, from the puzzle's examples.
+        let exactly = |max_cheat: usize, saving: usize| {
+            race.count_cheats(max_cheat, saving) - race.count_cheats(max_cheat, saving + 1)
+        };
+        let short = [(2, 14), (4, 14), (6, 2), (8, 4), (10, 2), (12, 3), (20, 1), (64, 1)];
+        for (saving, count) in short {
+            assert_eq!(exactly(2, saving), count, "saving {saving}");
+        }
+        assert_eq!(race.count_cheats(2, 1), 44);
+
+        let long = [(50, 32), (52, 31), (54, 29), (56, 39), (62, 20), (72, 22), (74, 4), (76, 3)];
+        for (saving, count) in long {
+            assert_eq!(exactly(20, saving), count, "saving {saving}");
+        }
+    }
+
+    #[test]
+    fn test_savings() {
+        let race = Race::parse(TEST_INPUT).unwrap();
+        let savings: Vec<(usize, usize)> = race.savings(2).into_iter().collect();
+        assert_eq!(savings, vec![
+            (2, 14), (4, 14), (6, 2), (8, 4), (10, 2), (12, 3), (20, 1), (36, 1), (38, 1), (40, 1),
+            (64, 1)
+        ]);
+        assert_eq!(race.savings(20).range(50..).map(|(_, cheats)| cheats).sum::<usize>(), 285);
+    }
+}
@@ -0,0 +1,594 @@
+//! Day 21: Keypad Conundrum
+//!
+//! Robots type codes on a numeric keypad, directed through layers of directional keypads.
+//!
+//! # Approach
+//!
+//! Every robot's arm starts and ends on `A`, so the cost of each move on one keypad only depends on
+//! the cost table for the layer below it. The tables are built up one layer at a time, so the work
+//! is linear in the number of robots; the tables for up to 25 robots are even computed at compile
+//! time. A memoized search over whole key sequences is kept as a cross-check, available via
+//! `--strategy memo`.
+//!
+//! Each extra robot multiplies the presses needed by roughly the same factor; `--growth` writes
+//! every code's cost at each depth up to `--robots` as CSV, with the fitted factor.
+//!
+//! That factor is about 2.5, so the presses are counted in `u128`s, which saturate rather than
+//! overflow; a count that reaches `u128::MAX` (past about 85 robots) is reported as an error
+//! instead of a wrong answer.
+
+use std::collections::HashMap;
+use itertools::Itertools;
+use crate::dump::csv;
+use crate::strategy::{ArgStrategies, Strategies};
+
+crate::day_args! { robots: usize = 25 }
+
+type Res<T> = Result<T, String>;
+
+/// A count of presses, or [`TOO_MANY`] if it's too big to count.
+type Presses = u128;
+
+/// The count of presses that stands for any too big to count: arithmetic on [`Presses`]
+/// saturates at it.
+const TOO_MANY: Presses = Presses::MAX;
+
+/// `presses`, unless it's [`TOO_MANY`] to answer with `robots` robots.
+fn counted(presses: Presses, robots: usize) -> Res<Presses> {
+    match presses {
+        TOO_MANY => Err(format!("Too many presses to count with {robots} robots")),
+        presses => Ok(presses)
+    }
+}
+
+fn numpad_pos(key: char) -> (usize, usize) {
+    match key {
+        '7' => (0, 0),
+        '8' => (0, 1),
+        '9' => (0, 2),
+        '4' => (1, 0),
+        '5' => (1, 1),
+        '6' => (1, 2),
+        '1' => (2, 0),
+        '2' => (2, 1),
+        '3' => (2, 2),
+        '0' => (3, 1),
+        'A' => (3, 2),
+        _ => panic!("Invalid numpad key")
+    }
+}
+
+fn numpad_paths(from: char, to: char) -> Vec<String> {
+    let (i0, j0) = numpad_pos(from);
+    let (i1, j1) = numpad_pos(to);
+
+    let vert_char = if i0 < i1 { 'v' } else { '^' };
+    let horiz_char = if j0 < j1 { '>' } else { '<' };
+
+    let vert = (0..i0.abs_diff(i1)).map(|_| vert_char).collect::<String>();
+    let horiz = (0..j0.abs_diff(j1)).map(|_| horiz_char).collect::<String>();
+
+    if i0 == 3 && j1 == 0 {
+        vec![format!("{vert}{horiz}A")]
+    }
+    else if (i1 == 3 && j0 == 0) || vert.is_empty() || horiz.is_empty() {
+        vec![format!("{horiz}{vert}A")]
+    }
+    else {
+        let vh = format!("{vert}{horiz}A");
+        let hv = format!("{horiz}{vert}A");
+        vec![vh, hv]
+    }
+}
+
+/// Keys of the dirpad, in the order used to index [`CostTable`].
+const DIRPAD_KEYS: [char; 5] = ['^', 'A', '<', 'v', '>'];
+
+const fn dirpad_index(key: char) -> usize {
+    match key {
+        '^' => 0,
+        'A' => 1,
+        '<' => 2,
+        'v' => 3,
+        '>' => 4,
+        _ => panic!("Invalid dirpad key")
+    }
+}
+
+/// The shortest ways to move between two dirpad keys and press the second, indexed like
+/// [`DIRPAD_KEYS`]. Paths never pass over the gap, and never zigzag (which only costs more).
+const DIRPAD_PATHS: [[&[&str]; 5]; 5] = [
+    // From '^'
+    [&["A"], &[">A"], &["v<A"], &["vA"], &[">vA", "v>A"]],
+    // From 'A'
+    [&["<A"], &["A"], &["v<<A"], &["<vA", "v<A"], &["vA"]],
+    // From '<'
+    [&[">^A"], &[">>^A"], &["A"], &[">A"], &[">>A"]],
+    // From 'v'
+    [&["^A"], &["^>A", ">^A"], &["<A"], &["A"], &[">A"]],
+    // From '>'
+    [&["<^A", "^<A"], &["^A"], &["<<A"], &["<A"], &["A"]]
+];
+
+fn dirpad_paths(from: char, to: char) -> &'static [&'static str] {
+    DIRPAD_PATHS[dirpad_index(from)][dirpad_index(to)]
+}
+
+/// [`CostTable`]'s tables for up to 25 intermediate robots, computed at compile time.
+const COSTS: [[[Presses; 5]; 5]; 26] = const_costs();
+
+/// The cost tables for `0..N` intermediate robots, as [`CostTable::extended`] builds them.
+const fn const_costs<const N: usize>() -> [[[Presses; 5]; 5]; N] {
+    let mut costs = [[[1; 5]; 5]; N];
+
+    let mut depth = 1;
+    while depth < N {
+        let mut i = 0;
+        while i < 5 {
+            let mut j = 0;
+            while j < 5 {
+                let paths = DIRPAD_PATHS[i][j];
+                let mut best = TOO_MANY;
+                let mut k = 0;
+                while k < paths.len() {
+                    let cost = const_seq_cost(&costs[depth - 1], paths[k].as_bytes());
+                    if cost < best {
+                        best = cost;
+                    }
+                    k += 1;
+                }
+                costs[depth][i][j] = best;
+                j += 1;
+            }
+            i += 1;
+        }
+        depth += 1;
+    }
+
+    costs
+}
+
+/// [`CostTable::seq_cost_with`], for const evaluation.
+const fn const_seq_cost(table: &[[Presses; 5]; 5], seq: &[u8]) -> Presses {
+    let mut from = dirpad_index('A');
+    let mut total = 0;
+
+    let mut k = 0;
+    while k < seq.len() {
+        let to = dirpad_index(seq[k] as char);
+        total = Presses::saturating_add(total, table[from][to]);
+        from = to;
+        k += 1;
+    }
+
+    total
+}
+
+/// Cost of moving between any two dirpad keys and pressing the second, for each robot depth.
+///
+/// `costs[d][from][to]` is the number of presses you must make for the robot `d` dirpads in to
+/// move its arm from `from` to `to` and press it; at depth 0 you press the key yourself, so every
+/// entry is 1.  Each depth's table is built from the previous one, so there are no string keys
+/// and the work grows only linearly with the number of robots. Costs too big to count saturate at
+/// [`TOO_MANY`].
+struct CostTable {
+    costs: Vec<[[Presses; 5]; 5]>
+}
+
+impl CostTable {
+    /// The tables up to `intermediate_robots`, starting from the precomputed [`COSTS`].
+    fn new(intermediate_robots: usize) -> CostTable {
+        let precomputed = COSTS[..=intermediate_robots.min(COSTS.len() - 1)].to_vec();
+        CostTable::extended(precomputed, intermediate_robots)
+    }
+
+    /// Add tables to `costs`, which must start with depth 0's, until it reaches depth
+    /// `intermediate_robots`.
+    fn extended(mut costs: Vec<[[Presses; 5]; 5]>, intermediate_robots: usize) -> CostTable {
+        while costs.len() <= intermediate_robots {
+            let prev = costs.last().unwrap();
+            let mut next = [[0; 5]; 5];
+
+            for (i, &from) in DIRPAD_KEYS.iter().enumerate() {
+                for (j, &to) in DIRPAD_KEYS.iter().enumerate() {
+                    next[i][j] = dirpad_paths(from, to).iter()
+                        .map(|path| Self::seq_cost_with(prev, path))
+                        .min()
+                        .expect("No paths found");
+                }
+            }
+
+            costs.push(next);
+        }
+
+        CostTable { costs }
+    }
+
+    fn seq_cost_with(table: &[[Presses; 5]; 5], seq: &str) -> Presses {
+        "A".chars().chain(seq.chars())
+            .map(dirpad_index)
+            .tuple_windows()
+            .map(|(from, to)| table[from][to])
+            .fold(0, Presses::saturating_add)
+    }
+
+    /// Presses needed for the robot `intermediate_robots` dirpads in to press `seq`.
+    fn dirpad_cost_for_seq(&self, seq: &str, intermediate_robots: usize) -> Presses {
+        Self::seq_cost_with(&self.costs[intermediate_robots], seq)
+    }
+
+    /// [`numpad_cost_for_seq`](Self::numpad_cost_for_seq) at every depth this table covers, from
+    /// no intermediate robots up.
+    fn numpad_costs_by_depth(&self, seq: &str) -> Vec<Presses> {
+        (0..self.costs.len()).map(|depth| self.numpad_cost_for_seq(seq, depth)).collect()
+    }
+
+    fn numpad_cost_for_seq(&self, seq: &str, intermediate_robots: usize) -> Presses {
+        "A".chars().chain(seq.chars())
+            .tuple_windows()
+            .map(|(from, to)| {
+                numpad_paths(from, to).iter()
+                    .map(|path| self.dirpad_cost_for_seq(path, intermediate_robots))
+                    .min()
+                    .unwrap()
+            })
+            .fold(0, Presses::saturating_add)
+    }
+}
+
+/// Memoized costs keyed on whole dirpad sequences; superseded by [`CostTable`] for the answers,
+/// but kept for cross-checking and for reconstructing the actual press sequences.
+struct Cache {
+    lookup: HashMap<(String, usize), Presses>
+}
+
+impl Cache {
+    fn new() -> Cache {
+        Cache { lookup: HashMap::new() }
+    }
+    fn dirpad_cost_for_seq(&mut self, seq: &str, intermediate_robots: usize) -> Presses {
+        if intermediate_robots == 0 {
+            return seq.len() as Presses
+        }
+
+        let key = (seq.to_owned(), intermediate_robots);
+        if self.lookup.contains_key(&key) {
+            self.lookup[&key]
+        } else {
+            let mut s = "A".to_owned();
+            s.push_str(seq);
+
+            let mut result = 0;
+
+            for (from, to) in s.chars().tuple_windows() {
+                let paths = dirpad_paths(from, to);
+                let min_cost = if intermediate_robots == 0 {
+                    paths.iter()
+                        .map(|s| s.len() as Presses)
+                        .min()
+                        .expect("No paths found")
+                } else {
+                    paths.iter()
+                        .map(|path| self.dirpad_cost_for_seq(path, intermediate_robots - 1))
+                        .min()
+                        .expect("No paths found")
+                };
+                result = Presses::saturating_add(result, min_cost);
+            }
+
+            self.lookup.insert(key, result);
+            result
+        }
+    }
+
+    fn numpad_cost_for_seq(&mut self, seq: &str, intermediate_robots: usize) -> Presses {
+        let mut result = 0;
+        let mut s = "A".to_owned();
+        s.push_str(seq);
+
+        for (from, to) in s.chars().tuple_windows() {
+            let best_cost = numpad_paths(from, to).into_iter()
+                .map(|path| {
+                    if intermediate_robots > 0 {
+                        self.dirpad_cost_for_seq(&path, intermediate_robots)
+                    }
+                    else {
+                        path.len() as Presses
+                    }
+                })
+                .min()
+                .unwrap();
+
+            result = Presses::saturating_add(result, best_cost);
+        }
+
+        result
+    }
+
+    /// One optimal sequence of presses on the outermost dirpad that makes the robot
+    /// `intermediate_robots` levels in press `seq` on its dirpad.
+    ///
+    /// The result has length `self.dirpad_cost_for_seq(seq, intermediate_robots)`, so this is only
+    /// practical for small depths.
+    fn expand_dirpad_seq(&mut self, seq: &str, intermediate_robots: usize) -> String {
+        if intermediate_robots == 0 {
+            return seq.to_owned()
+        }
+
+        let mut s = "A".to_owned();
+        s.push_str(seq);
+
+        let mut result = String::new();
+
+        for (from, to) in s.chars().tuple_windows() {
+            let best_path = dirpad_paths(from, to).iter()
+                .min_by_key(|path| self.dirpad_cost_for_seq(path, intermediate_robots - 1))
+                .expect("No paths found");
+
+            result.push_str(&self.expand_dirpad_seq(best_path, intermediate_robots - 1));
+        }
+
+        result
+    }
+
+    /// One optimal sequence of presses on the outermost dirpad that types `seq` on the numpad.
+    fn expand_numpad_seq(&mut self, seq: &str, intermediate_robots: usize) -> String {
+        let mut s = "A".to_owned();
+        s.push_str(seq);
+
+        let mut result = String::new();
+
+        for (from, to) in s.chars().tuple_windows() {
+            let best_path = numpad_paths(from, to).into_iter()
+                .min_by_key(|path| self.dirpad_cost_for_seq(path, intermediate_robots))
+                .unwrap();
+
+            result.push_str(&self.expand_dirpad_seq(&best_path, intermediate_robots));
+        }
+
+        result
+    }
+}
+
+fn numeric_part(seq: &str) -> Presses {
+    seq.chars()
+        .filter_map(|c| c.to_digit(10).map(Presses::from))
+        .fold(0, |cur, next| 10 * cur + next)
+}
+
+/// Sum of the codes' complexities, given the presses each code needs.
+fn complexity<F>(input: &str, robots: usize, mut presses: F) -> Res<Presses>
+where
+    F: FnMut(&str) -> Presses
+{
+    let total = input.lines()
+        .map(|seq| numeric_part(seq).saturating_mul(presses(seq)))
+        .fold(0, Presses::saturating_add);
+    counted(total, robots)
+}
+
+fn part1(input: &str) -> Res<Presses> {
+    let table = CostTable::new(2);
+    complexity(input, 2, |seq| table.numpad_cost_for_seq(seq, 2))
+}
+
+fn part2(input: &str, args: &Args) -> Res<Presses> {
+    let table = CostTable::new(args.robots);
+    complexity(input, args.robots, |seq| table.numpad_cost_for_seq(seq, args.robots))
+}
+
+/// Sum of complexities with `intermediate_robots` robots, using the memoized [`Cache`].
+fn complexity_memoized(input: &str, intermediate_robots: usize) -> Res<Presses> {
+    let mut cache = Cache::new();
+    complexity(input, intermediate_robots, |seq| {
+        cache.numpad_cost_for_seq(seq, intermediate_robots)
+    })
+}
+
+/// Sum of complexities with two intermediate robots.
+pub const PART1: Strategies<Res<Presses>> = Strategies(&[
+    ("table", part1),
+    ("memo", |input| complexity_memoized(input, 2))
+]);
+
+/// Sum of complexities with twenty-five intermediate robots (or `--robots`).
+pub const PART2: ArgStrategies<Res<Presses>, Args> = ArgStrategies(&[
+    ("table", part2),
+    ("memo", |input, args| complexity_memoized(input, args.robots))
+]);
+
+/// The factor by which `costs` grow with each extra robot: `e` to the slope of the least-squares
+/// line through their logarithms, from depth 1 on (depth 0's cost is just the numpad path).
+fn growth_factor(costs: &[Presses]) -> f64 {
+    let points: Vec<(f64, f64)> = costs.iter().enumerate().skip(1)
+        .map(|(depth, &cost)| (depth as f64, (cost as f64).ln()))
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+
+    let covariance: f64 = points.iter().map(|&(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.iter().map(|&(x, _)| (x - mean_x).powi(2)).sum();
+    (covariance / variance).exp()
+}
+
+/// For each code, the presses needed with every number of intermediate robots up to `--robots`
+/// (at least 2), and the factor they grow by per robot, as CSV.
+pub fn growth(input: &str, args: &Args) -> Res<String> {
+    let robots = args.robots.max(2);
+    let table = CostTable::new(robots);
+
+    let header = ["code".to_owned()].into_iter()
+        .chain((0..=robots).map(|depth| format!("depth {depth}")))
+        .chain(["factor".to_owned()]);
+
+    let rows = input.lines()
+        .map(|code| {
+            let costs = table.numpad_costs_by_depth(code);
+            counted(*costs.last().unwrap(), robots)?;
+            let factor = growth_factor(&costs);
+            Ok([code.to_owned()].into_iter()
+                .chain(costs.into_iter().map(|cost| cost.to_string()))
+                .chain([format!("{factor:.4}")])
+                .collect::<Vec<String>>())
+        })
+        .collect::<Res<Vec<_>>>()?;
+
+    Ok(csv(std::iter::once(header.collect()).chain(rows)))
+}
+
+/// For each code, one shortest sequence of button presses you could make for part 1.
+pub fn sequences(input: &str) -> String {
+    let mut cache = Cache::new();
+
+    input.lines()
+        .map(|seq| format!("{seq}: {}", cache.expand_numpad_seq(seq, 2)))
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::strategy::Solver;
+    use super::*;
+
+    const TEST_INPUT: &str = "029A
+980A
+179A
+456A
+379A";
+
+    crate::test_line_endings! { PART1, |input: &str| part2(input, &Args { robots: 2 }); TEST_INPUT }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), Ok(126384));
+
+        // Part 2 is part 1 with more robots.
+        for strategy in ["table", "memo"] {
+            let answer = PART2.solve(TEST_INPUT, Some(strategy), &Args { robots: 2 });
+            assert_eq!(answer, Ok(Ok(126384)), "{strategy}");
+        }
+    }
+
+    #[test]
+    fn test_many_robots() {
+        // Still exact well past what a u64 holds...
+        let args = Args { robots: 60 };
+        let table = PART2.solve(TEST_INPUT, Some("table"), &args).unwrap().unwrap();
+        let memo = PART2.solve(TEST_INPUT, Some("memo"), &args).unwrap().unwrap();
+        assert_eq!(table, memo);
+        assert!(table > u64::MAX as Presses);
+
+        // ...but an error, rather than a wrong answer, once too big to count.
+        let too_many = "Too many presses to count with 100 robots".to_owned();
+        for strategy in ["table", "memo"] {
+            let answer = PART2.solve(TEST_INPUT, Some(strategy), &Args { robots: 100 });
+            assert_eq!(answer, Ok(Err(too_many.clone())), "{strategy}");
+        }
+        assert_eq!(growth(TEST_INPUT, &Args { robots: 100 }), Err(too_many));
+    }
+
+    #[test]
+    fn test_const_costs_match_runtime() {
+        let runtime = CostTable::extended(vec![[[1; 5]; 5]], 25);
+        assert_eq!(runtime.costs, COSTS.to_vec());
+        assert_eq!(CostTable::new(2).costs, COSTS[..=2].to_vec());
+        assert_eq!(CostTable::new(30).costs[..=25], COSTS);
+        assert_eq!(CostTable::new(30).costs.len(), 31);
+    }
+
+    #[test]
+    fn test_dirpad_paths() {
+        // Every path ends at its target without passing over the gap.
+        for (from, to) in DIRPAD_KEYS.into_iter().cartesian_product(DIRPAD_KEYS) {
+            for path in dirpad_paths(from, to) {
+                let pressed = press(&format!("{}{path}", dirpad_paths('A', from)[0]), &DIRPAD);
+                assert_eq!(pressed, Some(format!("{from}{to}")), "{from} to {to} via {path}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_cost_table_matches_cache() {
+        let table = CostTable::new(25);
+        let mut cache = Cache::new();
+
+        for depth in 0..=25 {
+            for code in TEST_INPUT.lines() {
+                assert_eq!(table.numpad_cost_for_seq(code, depth), cache.numpad_cost_for_seq(code, depth));
+            }
+        }
+    }
+
+    #[test]
+    fn test_growth() {
+        let table = CostTable::new(3);
+        assert_eq!(table.numpad_costs_by_depth("029A"), vec![12, 28, 68, 164]);
+
+        assert!((growth_factor(&[12, 20, 40, 80]) - 2.0).abs() < 1e-9);
+
+        let csv = growth("029A\n980A", &Args { robots: 2 });
+        assert_eq!(csv, Ok("code,depth 0,depth 1,depth 2,factor
+029A,12,28,68,2.4286
+980A,12,26,60,2.3077
+".to_owned()));
+    }
+
+    /// Simulate pressing `presses` on a keypad with the given layout (`' '` marking the gap),
+    /// starting with the arm over `A`. Returns the keys pressed, or `None` if the arm ever
+    /// points at the gap.
+    fn press(presses: &str, layout: &[&str]) -> Option<String> {
+        let grid: Vec<Vec<char>> = layout.iter().map(|row| row.chars().collect()).collect();
+        let (mut i, mut j) = (0..grid.len()).cartesian_product(0..grid[0].len())
+            .find(|&(i, j)| grid[i][j] == 'A')
+            .unwrap();
+
+        let mut result = String::new();
+        for c in presses.chars() {
+            match c {
+                '^' => i -= 1,
+                'v' => i += 1,
+                '<' => j -= 1,
+                '>' => j += 1,
+                'A' => result.push(grid[i][j]),
+                _ => panic!("Invalid dirpad key")
+            }
+            if grid[i][j] == ' ' {
+                return None
+            }
+        }
+
+        Some(result)
+    }
+
+    const NUMPAD: [&str; 4] = ["789", "456", "123", " 0A"];
+    const DIRPAD: [&str; 2] = [" ^A", "<v>"];
+
+    #[test]
+    fn test_expand_matches_example() {
+        let mut cache = Cache::new();
+
+        let direct = cache.expand_numpad_seq("029A", 0);
+        assert!(["<A^A>^^AvvvA", "<A^A^>^AvvvA", "<A^A^^>AvvvA"].contains(&direct.as_str()));
+
+        let one_robot = cache.expand_numpad_seq("029A", 1);
+        assert_eq!(one_robot.len(), "v<<A>>^A<A>AvA<^AA>A<vAAA>^A".len());
+        assert_eq!(press(&one_robot, &DIRPAD).and_then(|s| press(&s, &NUMPAD)), Some("029A".to_owned()));
+    }
+
+    #[test]
+    fn test_expand_round_trips() {
+        let mut cache = Cache::new();
+
+        for (code, len) in TEST_INPUT.lines().zip([68, 60, 68, 64, 64]) {
+            let presses = cache.expand_numpad_seq(code, 2);
+            assert_eq!(presses.len(), len);
+            assert_eq!(presses.len() as Presses, cache.numpad_cost_for_seq(code, 2));
+
+            let typed = press(&presses, &DIRPAD)
+                .and_then(|s| press(&s, &DIRPAD))
+                .and_then(|s| press(&s, &NUMPAD));
+            assert_eq!(typed, Some(code.to_owned()));
+        }
+    }
+}
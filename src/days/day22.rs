@@ -0,0 +1,504 @@
+//! Day 22: Monkey Market
+//!
+//! Each buyer's secret numbers follow a pseudorandom sequence, and their prices are its last
+//! digits.
+//!
+//! # Approach
+//!
+//! Each step of the sequence (see [`Prng`]) is three xorshifts modulo a power of two, each of which
+//! is invertible, so the sequence can also be run backwards.
+//!
+//! Part 1 just runs each sequence. For part 2, every window of four consecutive price changes is
+//! packed into an index in `0..19⁴`, and one array accumulates, for each window, the bananas from
+//! each buyer's first occurrence of it.
+//!
+//! Nothing is allocated per buyer: windows are read straight off the price iterator, and which
+//! windows a buyer has already offered is kept in one [`SeenWindows`] shared by all of them. The
+//! original version, which allocated a fresh `seen` array for each buyer, is kept as the
+//! "allocating" strategy for `cargo bench --bench days` to compare against, in time and in bytes
+//! allocated.
+//!
+//! The accumulated array can't say which buyers a window's bananas came from, so for `--sellers`
+//! a [`Market`] keeps each buyer's first offer of every window instead, and answers who would sell
+//! on a given window (by default the best one), and at what price.
+
+use std::fmt::Write;
+use crate::hash::HashMap;
+use crate::iter::{diffs, windowed_map};
+use crate::parse::{parse_lines, unsigned};
+use crate::strategy::Strategies;
+use itertools::Itertools;
+
+crate::day_args! {
+    seed: usize = 123, steps: usize = 10, secret: usize = 5908254, sequence: String = String::new()
+}
+
+/// One stage of a [`Prng`] step: the secret, multiplied (`Left`) or divided (`Right`) by 2 to the
+/// given power, is mixed into the secret, which is then pruned.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Shift { Left(u32), Right(u32) }
+
+/// The generator of secret numbers: each step applies its shifts in order.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Prng {
+    /// Secrets are pruned modulo this, which must be a power of two.
+    modulus: usize,
+    shifts: [Shift; 3]
+}
+
+impl Prng {
+    /// The monkeys' generator.
+    const MONKEY: Prng = Prng::new(16777216, [Shift::Left(6), Shift::Right(5), Shift::Left(11)]);
+
+    const fn new(modulus: usize, shifts: [Shift; 3]) -> Prng {
+        assert!(modulus.is_power_of_two(), "The modulus must be a power of two");
+        Prng { modulus, shifts }
+    }
+
+    fn mix(value: usize, secret: usize) -> usize {
+        value ^ secret
+    }
+
+    fn prune(&self, secret: usize) -> usize {
+        secret % self.modulus
+    }
+
+    fn shifted(secret: usize, shift: Shift) -> usize {
+        match shift {
+            Shift::Left(k) => secret << k,
+            Shift::Right(k) => secret >> k
+        }
+    }
+
+    /// The secret after `secret`.
+    fn step(&self, secret: usize) -> usize {
+        self.shifts.iter().fold(secret, |secret, &shift| {
+            self.prune(Prng::mix(Prng::shifted(secret, shift), secret))
+        })
+    }
+
+    /// The secret before `secret`, undoing the shifts in reverse order.
+    ///
+    /// If `y = x ^ (x << k)` (pruned), then the low `k` bits of `x` and `y` agree, and each further
+    /// `k` bits of `x` follow from `y` and the bits below them; so repeating `x = y ^ (x << k)`
+    /// fixes `k` more bits each time. Right shifts work the same way from the top bit down.
+    fn inverse_step(&self, secret: usize) -> usize {
+        let bits = self.modulus.trailing_zeros();
+
+        self.shifts.iter().rev().fold(secret, |y, &shift| {
+            let (Shift::Left(k) | Shift::Right(k)) = shift;
+            let rounds = if k == 0 { 0 } else { bits.div_ceil(k) };
+            (0..rounds).fold(y, |x, _| self.prune(Prng::mix(Prng::shifted(x, shift), y)))
+        })
+    }
+
+    /// Every secret from `seed` on, starting with `seed` itself.
+    fn secrets(self, seed: usize) -> impl Iterator<Item=usize> {
+        std::iter::successors(Some(seed), move |&secret| Some(self.step(secret)))
+    }
+
+    /// The secret `steps` steps before `secret`.
+    fn recover_seed(&self, secret: usize, steps: usize) -> usize {
+        (0..steps).fold(secret, |secret, _| self.inverse_step(secret))
+    }
+}
+
+/// A buyer, whose secret numbers (and so prices) are determined by their initial secret.
+struct Buyer {
+    seed: usize
+}
+
+impl Buyer {
+    fn new(seed: usize) -> Buyer {
+        Buyer { seed }
+    }
+
+    /// The buyer's secret numbers, starting with the initial one.
+    fn secrets(&self) -> impl Iterator<Item=usize> {
+        Prng::MONKEY.secrets(self.seed)
+    }
+
+    /// The price offered at each secret: its ones digit.
+    fn prices(&self) -> impl Iterator<Item=isize> {
+        self.secrets().map(|n| (n % 10) as isize)
+    }
+
+    /// The change in price from each secret to the next.
+    fn changes(&self) -> impl Iterator<Item=isize> {
+        diffs(self.prices())
+    }
+
+    /// The puzzle's worked table of secret, price and change for the first `steps` secrets.
+    fn table(&self, steps: usize) -> String {
+        let changes = std::iter::once(None).chain(self.changes().map(Some));
+
+        self.secrets().zip(self.prices()).zip(changes)
+            .take(steps)
+            .map(|((secret, price), change)| match change {
+                Some(change) => format!("{secret:>8}: {price} ({change})"),
+                None => format!("{secret:>8}: {price}")
+            })
+            .join("\n")
+    }
+}
+
+/// The number of windows of four price changes, each in `-9..=9`.
+const WINDOWS: usize = 19 * 19 * 19 * 19;
+
+/// The window of changes `[a, b, c, d]` as a base-19 number.
+fn to_index([a, b, c, d]: [isize; 4]) -> usize {
+    (6859 * (a + 9) + 361 * (b + 9) + 19 * (c + 9) + (d + 9)) as usize
+}
+
+/// The window of changes with index `index`; the inverse of [`to_index`].
+fn from_index(index: usize) -> [isize; 4] {
+    let digit = |place: usize| (index / place % 19) as isize - 9;
+    [digit(6859), digit(361), digit(19), digit(1)]
+}
+
+/// A window of four changes written like `-2,1,-1,3`.
+fn parse_window(text: &str) -> Result<[isize; 4], String> {
+    let changes: Vec<isize> = text.split(',')
+        .map(|change| change.trim().parse().ok().filter(|change: &isize| change.abs() <= 9))
+        .collect::<Option<_>>()
+        .ok_or_else(|| format!("Invalid sequence {text:?}"))?;
+
+    changes.try_into().map_err(|_| format!("Expected four changes, got {text:?}"))
+}
+
+/// Which windows the current buyer has offered so far, for one buyer after another.
+///
+/// Rather than clearing a bitmap for each buyer, each window records the generation (buyer) that
+/// last saw it, and moving on to the next buyer just bumps the generation.
+struct SeenWindows {
+    generations: Vec<u16>,
+    current: u16
+}
+
+impl SeenWindows {
+    fn new() -> SeenWindows {
+        SeenWindows { generations: vec![0; WINDOWS], current: 0 }
+    }
+
+    /// Forget every window, for the next buyer.
+    fn next_buyer(&mut self) {
+        if self.current == u16::MAX {
+            self.generations.fill(0);
+            self.current = 0;
+        }
+        self.current += 1;
+    }
+
+    /// Mark `window` as seen, returning whether it's the first time for this buyer.
+    fn insert(&mut self, window: usize) -> bool {
+        let first = self.generations[window] != self.current;
+        self.generations[window] = self.current;
+        first
+    }
+}
+
+/// One buyer selling on a window: which line of the input they're on, and their price.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Sale {
+    pub buyer: usize,
+    pub price: usize
+}
+
+/// Every buyer's first offer of each window, kept per buyer rather than summed, so that a window's
+/// bananas can be traced back to the buyers they came from.
+pub struct Market {
+    /// For each buyer, the price at which they first offer each window that they offer at all.
+    offers: Vec<HashMap<u32, u8>>
+}
+
+impl Market {
+    pub fn new(input: &str) -> Market {
+        let offers = parse_input(input)
+            .map(|seed| {
+                let mut first = HashMap::default();
+                let windows = Buyer::new(seed).prices().take(2001).tuple_windows();
+                for (p0, p1, p2, p3, p4) in windows {
+                    let window = to_index([p1 - p0, p2 - p1, p3 - p2, p4 - p3]) as u32;
+                    first.entry(window).or_insert(p4 as u8);
+                }
+                first
+            })
+            .collect();
+
+        Market { offers }
+    }
+
+    /// The buyers who would sell on the window `changes`, in input order, with their prices.
+    pub fn sellers(&self, changes: [isize; 4]) -> Vec<Sale> {
+        let window = to_index(changes) as u32;
+        self.offers.iter().enumerate()
+            .filter_map(|(buyer, offers)| {
+                offers.get(&window).map(|&price| Sale { buyer, price: price as usize })
+            })
+            .collect()
+    }
+
+    /// The window that gets the most bananas (the first, by index, if there's a tie), and how many.
+    pub fn best(&self) -> ([isize; 4], usize) {
+        let mut bananas = vec![0; WINDOWS];
+        for offers in &self.offers {
+            for (&window, &price) in offers {
+                bananas[window as usize] += price as usize;
+            }
+        }
+
+        let (window, &most) = bananas.iter().enumerate()
+            .rev()
+            .max_by_key(|&(_, &total)| total)
+            .unwrap();
+        (from_index(window), most)
+    }
+}
+
+/// Initial secret numbers, parsed lazily one line at a time.
+fn parse_input(input: &str) -> impl Iterator<Item=usize> + '_ {
+    parse_lines(input, unsigned).map(|seed| seed.unwrap())
+}
+
+/// Sum of every buyer's 2000th secret number.
+pub fn part1(input: &str) -> usize {
+    parse_input(input)
+        .map(|n| Prng::MONKEY.secrets(n).nth(2000).unwrap())
+        .sum()
+}
+
+fn part2_shared(input: &str) -> usize {
+    let mut bananas = vec![0; WINDOWS];
+    let mut seen = SeenWindows::new();
+
+    for seed in parse_input(input) {
+        seen.next_buyer();
+
+        // Five consecutive prices give a window of four changes, ending at the last price.
+        let windows = Buyer::new(seed).prices().take(2001).tuple_windows();
+        for (p0, p1, p2, p3, p4) in windows {
+            let window = to_index([p1 - p0, p2 - p1, p3 - p2, p4 - p3]);
+            if seen.insert(window) {
+                bananas[window] += p4 as usize;
+            }
+        }
+    }
+
+    bananas.into_iter().max().unwrap()
+}
+
+/// Part 2 as first written, allocating a `seen` array and the prices for each buyer.
+fn part2_allocating(input: &str) -> usize {
+    let mut bananas = vec![0; WINDOWS];
+
+    for seed in parse_input(input) {
+        let mut seen = vec![false; WINDOWS];
+        let buyer = Buyer::new(seed);
+        let prices: Vec<isize> = buyer.prices().take(2001).collect();
+
+        // The window of changes ending at price k+4 is the k-th window of changes.
+        windowed_map(buyer.changes().take(2000), to_index)
+            .zip(prices[4..].iter())
+            .for_each(|(i, &n)| {
+                if !seen[i] {
+                    seen[i] = true;
+                    bananas[i] += n as usize;
+                }
+            });
+    }
+
+    bananas.into_iter().max().unwrap()
+}
+
+/// Most bananas obtainable with a single sequence of four price changes.
+pub const PART2: Strategies<usize> = Strategies(&[
+    ("shared", part2_shared),
+    ("allocating", part2_allocating)
+]);
+
+/// The price table for the buyer given by `--seed` (by default, the puzzle's example).
+pub fn table(_input: &str, args: &Args) -> String {
+    Buyer::new(args.seed).table(args.steps)
+}
+
+/// The buyers who would sell on the window of changes given by `--sequence` (e.g. `-2,1,-1,3`),
+/// or on part 2's best window if there isn't one, with their prices and the total.
+pub fn sellers(input: &str, args: &Args) -> Result<String, String> {
+    let market = Market::new(input);
+    let window = match args.sequence.as_str() {
+        "" => market.best().0,
+        sequence => parse_window(sequence)?
+    };
+
+    let sales = market.sellers(window);
+    let total: usize = sales.iter().map(|sale| sale.price).sum();
+    let mut out = String::new();
+    writeln!(out, "Sequence {}: {} buyers, {total} bananas", window.iter().join(","), sales.len())
+        .unwrap();
+    for sale in &sales {
+        writeln!(out, "Buyer {} (line {}): {}", sale.buyer, sale.buyer + 1, sale.price).unwrap();
+    }
+
+    Ok(out.trim_end().to_owned())
+}
+
+/// The initial secret that leads to `--secret` after `--steps` steps (by default, the example's
+/// 123).
+pub fn recover(_input: &str, args: &Args) -> usize {
+    Prng::MONKEY.recover_seed(args.secret, args.steps)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::gen::{day22_seeds, Rng};
+    use super::*;
+
+    const TEST_INPUT1: &str = "1\n10\n100\n2024";
+    const TEST_INPUT2: &str = "1\n2\n3\n2024";
+
+    crate::test_line_endings! { part1, PART2; TEST_INPUT1, TEST_INPUT2 }
+
+    #[test]
+    fn test_generation() {
+        let first_10: Vec<usize> = Prng::MONKEY.secrets(123)
+            .dropping(1)
+            .take(10)
+            .collect();
+
+        let expected = vec![
+            15887950,
+            16495136,
+            527345,
+            704524,
+            1553684,
+            12683156,
+            11100544,
+            12249484,
+            7753432,
+            5908254
+        ];
+        assert_eq!(first_10, expected);
+    }
+
+    #[test]
+    fn test_inverse_step() {
+        let prng = Prng::MONKEY;
+        assert_eq!(prng.inverse_step(15887950), 123);
+        assert_eq!(prng.recover_seed(5908254, 10), 123);
+        assert_eq!(prng.recover_seed(8685429, 2000), 1);
+        assert_eq!(prng.recover_seed(8667524, 2000), 2024);
+
+        // Every secret has exactly one predecessor.
+        let mut rng = crate::gen::Rng::new(22);
+        for _ in 0..1000 {
+            let secret = rng.below(prng.modulus);
+            assert_eq!(prng.step(prng.inverse_step(secret)), secret);
+            assert_eq!(prng.inverse_step(prng.step(secret)), secret);
+        }
+    }
+
+    #[test]
+    fn test_other_parameters() {
+        let prng = Prng::new(1 << 10, [Shift::Right(3), Shift::Left(1), Shift::Right(7)]);
+        let secrets: Vec<usize> = prng.secrets(5).take(50).collect();
+        assert!(secrets.iter().all(|&secret| secret < 1 << 10));
+        assert_eq!(prng.recover_seed(secrets[49], 49), 5);
+
+        // On 4 bits the generator permutes all 16 secrets.
+        let small = Prng::new(16, [Shift::Left(1), Shift::Right(2), Shift::Left(3)]);
+        let images: Vec<usize> = (0..16).map(|secret| small.step(secret)).sorted().collect();
+        assert_eq!(images, (0..16).collect::<Vec<_>>());
+        assert!((0..16).all(|secret| small.inverse_step(small.step(secret)) == secret));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT1), 37327623);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2_shared(TEST_INPUT2), 23);
+        assert_eq!(part2_allocating(TEST_INPUT2), 23);
+
+        let seeds = day22_seeds(&mut Rng::new(22), 50);
+        assert_eq!(part2_shared(&seeds), part2_allocating(&seeds));
+    }
+
+    #[test]
+    fn test_to_index() {
+        assert_eq!(to_index([-9, -9, -9, -9]), 0);
+        assert_eq!(to_index([9, 9, 9, 9]), WINDOWS - 1);
+        assert_eq!(to_index([-2, 1, -1, 3]), ((7 * 19 + 10) * 19 + 8) * 19 + 12);
+    }
+
+    #[test]
+    fn test_from_index() {
+        assert_eq!(from_index(0), [-9, -9, -9, -9]);
+        assert_eq!(from_index(WINDOWS - 1), [9, 9, 9, 9]);
+        assert!((0..WINDOWS).step_by(97).all(|i| to_index(from_index(i)) == i));
+
+        assert_eq!(parse_window("-2,1,-1,3"), Ok([-2, 1, -1, 3]));
+        assert!(parse_window("-2,1,-1").is_err());
+        assert!(parse_window("-2,1,-1,10").is_err());
+    }
+
+    #[test]
+    fn test_market() {
+        let market = Market::new(TEST_INPUT2);
+        assert_eq!(market.best(), ([-2, 1, -1, 3], 23));
+        assert_eq!(market.sellers([-2, 1, -1, 3]), vec![
+            Sale { buyer: 0, price: 7 },
+            Sale { buyer: 1, price: 7 },
+            Sale { buyer: 3, price: 9 }
+        ]);
+
+        let seeds = day22_seeds(&mut Rng::new(22), 50);
+        assert_eq!(Market::new(&seeds).best().1, part2_shared(&seeds));
+
+        let args = Args { sequence: "-2,1,-1,3".to_owned(), ..Args::default() };
+        let expected = "Sequence -2,1,-1,3: 3 buyers, 23 bananas
+Buyer 0 (line 1): 7
+Buyer 1 (line 2): 7
+Buyer 3 (line 4): 9";
+        assert_eq!(sellers(TEST_INPUT2, &args), Ok(expected.to_owned()));
+        assert_eq!(sellers(TEST_INPUT2, &Args::default()), Ok(expected.to_owned()));
+    }
+
+    #[test]
+    fn test_seen_windows() {
+        let mut seen = SeenWindows::new();
+        seen.next_buyer();
+        assert!(seen.insert(5));
+        assert!(!seen.insert(5));
+
+        seen.next_buyer();
+        assert!(seen.insert(5));
+
+        // Generations wrap around without forgetting the current buyer's windows.
+        seen.current = u16::MAX - 1;
+        seen.next_buyer();
+        assert!(seen.insert(7));
+        seen.next_buyer();
+        assert!(seen.insert(7));
+        assert!(!seen.insert(7));
+    }
+
+    #[test]
+    fn test_buyer_table() {
+        let expected = "     123: 3
+15887950: 0 (-3)
+16495136: 6 (6)
+  527345: 5 (-1)
+  704524: 4 (-1)
+ 1553684: 4 (0)
+12683156: 6 (2)
+11100544: 4 (-2)
+12249484: 4 (0)
+ 7753432: 2 (-2)";
+        assert_eq!(Buyer::new(123).table(10), expected);
+
+        let changes: Vec<isize> = Buyer::new(123).changes().take(4).collect();
+        assert_eq!(changes, vec![-3, 6, -1, -1]);
+    }
+}
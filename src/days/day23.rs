@@ -0,0 +1,209 @@
+//! Day 23: LAN Party
+//!
+//! Find cliques in the network of computers.
+//!
+//! # Approach
+//!
+//! Cliques are grown depth-first, only ever adding vertices that come after all the current
+//! members, so each clique is found once. Searching for the largest one prunes branches that can't
+//! beat the best found so far.
+//!
+//! For the largest clique, "after" means later in a degeneracy ordering (see
+//! [`degeneracy_order`]), rather than in name order. Then a clique started from any vertex can
+//! only grow into at most degeneracy-many others, however dense the rest of the graph is.
+
+use itertools::Itertools;
+use nom::character::complete::{alpha1, char, line_ending};
+use nom::combinator::map;
+use nom::multi::separated_list1;
+use nom::IResult;
+use nom::sequence::separated_pair;
+use crate::graph::{clique_password, cliques_of_size, degeneracy_order, to_dot, DotKind};
+use crate::hash::{HashMap, HashSet};
+
+struct Graph<'a> {
+    verts: Vec<&'a str>,
+    adjlist: HashMap<&'a str, HashSet<&'a str>>
+}
+
+impl<'a> Graph<'a> {
+    fn from_edges(edges: Vec<(&'a str, &'a str)>) -> Graph<'a> {
+        let mut adjlist: HashMap<&str, HashSet<&str>> = HashMap::default();
+        for (a, b) in edges.into_iter() {
+            adjlist.entry(a).or_default().insert(b);
+            adjlist.entry(b).or_default().insert(a);
+        }
+        let mut verts: Vec<&str> = adjlist.keys().copied().collect();
+        verts.sort();
+        Graph { verts, adjlist }
+    }
+}
+
+fn parse_input(input: &str) -> IResult<&str, Graph<'_>> {
+        map(
+            separated_list1(
+                line_ending,
+                separated_pair(alpha1, char('-'), alpha1)
+            ),
+            Graph::from_edges
+        )(input)
+}
+
+/// Number of sets of three inter-connected computers, at least one of which starts with `t`.
+pub fn part1(input: &str) -> usize {
+    let graph = parse_input(input).unwrap().1;
+
+    cliques_of_size(&graph.adjlist, 3).into_iter()
+        .filter(|clique| clique.iter().any(|v| v.starts_with('t')))
+        .count()
+}
+
+fn max_clique<'a>(graph: &Graph<'a>) -> Vec<&'a str> {
+    let (order, _) = degeneracy_order(&graph.adjlist);
+    let position: HashMap<&str, usize> = order.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+
+    let mut best = Vec::new();
+    let mut stack: Vec<(Vec<&str>, Vec<&str>)> = order.iter().rev()
+        .map(|&v| {
+            let candidates = graph.adjlist[v].iter()
+                .copied()
+                .filter(|w| position[w] > position[v])
+                .sorted_by_key(|w| position[w])
+                .collect();
+            (vec![v], candidates)
+        })
+        .collect();
+
+    while let Some((vs, choices)) = stack.pop() {
+        if vs.len() + choices.len() <= best.len() {
+            // No point -- most we could ever add won't beat our best known
+            continue;
+        }
+
+        if choices.is_empty() {
+            best = vs;
+            continue;
+        }
+
+        for (idx, &w) in choices.iter().enumerate() {
+            let mut choice = vs.clone();
+            choice.push(w);
+            let next_choices = choices[idx + 1..].iter()
+                .copied()
+                .filter(|u| graph.adjlist[w].contains(u))
+                .collect();
+            stack.push((choice, next_choices));
+        }
+    }
+
+    best.sort();
+    best
+}
+
+/// The members of the largest set of inter-connected computers, in sorted order.
+pub fn largest_clique(input: &str) -> Vec<&str> {
+    max_clique(&parse_input(input).unwrap().1)
+}
+
+/// Password to the LAN party: the members of the largest clique, sorted and comma separated.
+pub fn part2(input: &str) -> String {
+    clique_password(&largest_clique(input))
+}
+
+/// Size of the network: its numbers of computers and of connections, and its degeneracy (the most
+/// connections any computer has to those after it in a degeneracy ordering).
+pub fn stats(input: &str) -> String {
+    let graph = parse_input(input).unwrap().1;
+    let edges = graph.adjlist.values().map(HashSet::len).sum::<usize>() / 2;
+    let (_, degeneracy) = degeneracy_order(&graph.adjlist);
+
+    format!("Vertices: {}\nEdges: {edges}\nDegeneracy: {degeneracy}", graph.verts.len())
+}
+
+/// Graphviz rendering of the network, with the largest LAN party highlighted.
+pub fn dot(input: &str) -> String {
+    let graph = parse_input(input).unwrap().1;
+    let clique = max_clique(&graph);
+
+    let edges = graph.verts.iter()
+        .flat_map(|&a| graph.adjlist[a].iter().sorted().map(move |&b| (a, b)));
+    let highlighted_nodes = clique.iter().copied().collect();
+    let highlighted_edges = clique.iter().copied().tuple_combinations().collect();
+
+    to_dot(DotKind::Undirected, edges, |&v| v, &highlighted_nodes, &highlighted_edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "kh-tc
+qp-kh
+de-cg
+ka-co
+yn-aq
+qp-ub
+cg-tb
+vc-aq
+tb-ka
+wh-tc
+yn-cg
+kh-ub
+ta-co
+de-co
+tc-td
+tb-wq
+wh-td
+ta-ka
+td-qp
+aq-cg
+wq-ub
+ub-vc
+de-ta
+wq-aq
+wq-vc
+wh-yn
+ka-de
+kh-ta
+co-tc
+wh-qp
+tb-vc
+td-yn";
+
+    crate::test_line_endings! { part1, part2; TEST_INPUT }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST_INPUT), 7);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST_INPUT), "co,de,ka,ta")
+    }
+
+    #[test]
+    fn test_largest_clique() {
+        assert_eq!(largest_clique(TEST_INPUT), vec!["co", "de", "ka", "ta"]);
+    }
+
+    #[test]
+    fn test_stats() {
+        assert_eq!(stats(TEST_INPUT), "Vertices: 16\nEdges: 32\nDegeneracy: 4");
+    }
+
+    #[test]
+    fn test_cliques_of_size() {
+        let graph = parse_input(TEST_INPUT).unwrap().1;
+
+        let triangles = cliques_of_size(&graph.adjlist, 3);
+        assert_eq!(triangles.len(), 12);
+        assert!(triangles.contains(&vec!["aq", "cg", "yn"]));
+        assert!(triangles.contains(&vec!["co", "de", "ta"]));
+
+        let quads: Vec<String> = cliques_of_size(&graph.adjlist, 4).iter()
+            .map(|clique| clique.join(","))
+            .collect();
+        assert_eq!(quads, vec!["co,de,ka,ta"]);
+    }
+}
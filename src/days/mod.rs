@@ -0,0 +1,105 @@
+//! Every day's solutions, each behind its own `dayNN` feature.
+//!
+//! The `dayNN` binaries are thin wrappers that run these against the embedded input; [`ALL`] lists
+//! the days compiled into this build, and [`YEAR`] registers them as 2024's, for tools that work
+//! across days.
+
+pub use aoc_core::day::{solve, Day, Part, Year};
+
+/// A day's `Args` type, or `()` if it has none.
+// Only `days!`'s per-day entries use this, so it's unused when no days are enabled.
+#[allow(unused_macros)]
+macro_rules! args_of {
+    ( $day:ident ) => { () };
+    ( $day:ident $args:ident ) => { $day::$args };
+}
+
+macro_rules! days {
+    (
+        $(
+        $feature:literal => $day:ident = $number:literal
+            ( $part1:ident, $part2:ident $(, $args:ident)? )
+        ),+ $(,)?
+    ) => {
+        $(
+        #[cfg(feature = $feature)]
+        pub mod $day;
+        )+
+
+        /// Every day compiled into this build, in order.
+        pub const ALL: &[Day] = &[
+            $(
+            #[cfg(feature = $feature)]
+            Day {
+                number: $number,
+                source: include_str!(concat!(stringify!($day), ".rs")),
+                parts: [
+                    |input, strategy, config| {
+                        let solver = &$day::$part1;
+                        solve::<_, args_of!($day $($args)?), _, _>(solver, input, strategy, config)
+                    },
+                    |input, strategy, config| {
+                        let solver = &$day::$part2;
+                        solve::<_, args_of!($day $($args)?), _, _>(solver, input, strategy, config)
+                    }
+                ]
+            },
+            )+
+        ];
+    };
+}
+
+// Each day's two parts, and its `Args` type if it has extra arguments.
+days! {
+    "day01" => day01 = 1 (part1, PART2),
+    "day02" => day02 = 2 (part1, part2),
+    "day03" => day03 = 3 (part1, part2),
+    "day04" => day04 = 4 (PART1, part2),
+    "day05" => day05 = 5 (part1, part2),
+    "day06" => day06 = 6 (part1, PART2),
+    "day07" => day07 = 7 (part1, part2),
+    "day08" => day08 = 8 (part1, part2),
+    "day09" => day09 = 9 (part1, part2),
+    "day10" => day10 = 10 (part1, part2),
+    "day11" => day11 = 11 (part1, part2),
+    "day12" => day12 = 12 (part1, part2),
+    "day13" => day13 = 13 (part1, part2, Args),
+    "day14" => day14 = 14 (part1, PART2, Args),
+    "day15" => day15 = 15 (part1, part2),
+    "day16" => day16 = 16 (PART1, PART2),
+    "day17" => day17 = 17 (part1, PART2),
+    "day18" => day18 = 18 (part1, part2, Args),
+    "day19" => day19 = 19 (part1, part2),
+    "day20" => day20 = 20 (part1, part2, Args),
+    "day21" => day21 = 21 (PART1, PART2, Args),
+    "day22" => day22 = 22 (part1, PART2, Args),
+    "day23" => day23 = 23 (part1, part2),
+}
+
+/// This year's puzzles.
+pub const YEAR: Year = Year { year: 2024, days: ALL };
+
+/// The day numbered `number`, if it's compiled into this build.
+pub fn get(number: u32) -> Option<&'static Day> {
+    YEAR.get(number)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::describe::{item_docs, module_docs};
+    use super::ALL;
+
+    #[test]
+    fn test_days_are_documented() {
+        for day in ALL {
+            let docs = module_docs(day.source);
+            assert!(docs.starts_with(&format!("Day {}: ", day.number)), "day {}", day.number);
+
+            for n in 1..=2 {
+                let part = item_docs(day.source, &format!("pub fn part{n}"))
+                    .or_else(|| item_docs(day.source, &format!("pub const PART{n}")));
+                assert!(part.is_some(), "day {} part {n}", day.number);
+            }
+        }
+    }
+}
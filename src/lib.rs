@@ -1,31 +1,17 @@
-#[macro_export]
-macro_rules! build_main {
-    ($input:literal, $( $part:literal => $solver:expr),+) => {
-        use std::time::Instant;
-        fn main() {
-            let input: &str = include_str!($input);
-            $(
-            let start = Instant::now();
-            let result = $solver(input);
-            let duration = start.elapsed().as_micros();
-            println!("{}: {} (Time: {}μs)", $part, result, duration);
-            )+
-        }
-    };
-}
+//! Solutions to Advent of Code 2024, one module per day in [`days`].
+//!
+//! The year-independent utilities live in the `aoc-core` crate; they're re-exported here under
+//! their old paths, so that days (and the `dayNN` binaries) can keep using e.g.
+//! `crate::grid::Grid` and `adventofcode2024::build_main!`.
 
-#[macro_export]
-macro_rules! build_main_res {
-    ($input:literal, $( $part:literal => $solver:expr),+) => {
-        use std::time::Instant;
-        fn main() {
-            let input: &str = include_str!($input);
-            $(
-            let start = Instant::now();
-            let result = $solver(input).unwrap();
-            let duration = start.elapsed().as_micros();
-            println!("{}: {} (Time: {}μs)", $part, result, duration);
-            )+
-        }
-    };
-}
\ No newline at end of file
+pub use aoc_core::{
+    answer, args, cli, columns, config, describe, dp, dump, gen, graph, grid, hash, history, iter,
+    linalg, logging, numtheory, parse, prelude, ranges, runner, samples, sanitize, search,
+    simulation, snapshot, strategy, strings
+};
+pub use aoc_core::{
+    assert_snapshot, build_main, build_main_res, day_args, test_line_endings, test_samples
+};
+
+pub mod cache;
+pub mod days;